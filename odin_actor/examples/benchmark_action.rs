@@ -33,7 +33,7 @@ mod provider {
     impl_actor! { match msg for Actor<Provider<A>,ProviderMsg> where A: DataAction<u64> as
         ExecuteActions => cont! { 
             self.data += 1;
-            self.actions.execute(self.data).await 
+            self.actions.execute(self.data).await; 
         }
     }
 }
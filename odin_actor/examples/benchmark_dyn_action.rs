@@ -37,7 +37,7 @@ mod provider {
         }
         TriggerAction => cont! { 
             self.data += 1;
-            self.actions.execute( self.data, true).await 
+            self.actions.execute( self.data, true).await; 
         }
     }
 }
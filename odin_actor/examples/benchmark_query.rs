@@ -52,7 +52,7 @@ struct Requester <M> where M: MsgReceiver<Query<Question,Answer>> {
 impl_actor! { match msg for Actor<Requester<M>,RequesterMsg> where M: MsgReceiver<Query<Question,Answer>> + Send + Sync as
     StartQueries => term! {
         println!("--- running queries from other actor");
-        run_queries(self.responder.clone(), self.max_rounds).await
+        run_queries(self.responder.clone(), self.max_rounds).await;
     }
 }
 
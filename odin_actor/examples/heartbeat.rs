@@ -51,14 +51,14 @@ struct Actor2State {
 
 impl_actor! { match msg for Actor<Actor2State,Actor2Msg> as 
     _Start_ => cont!{ 
-        self.start_repeat_timer(1, millis(100), false) 
+        self.start_repeat_timer(1, millis(100), false); 
     }
     _Timer_ => cont!{
         self.n += 1;
         self.a1.try_send_msg( MsgB(self.n));
     }
     MsgC => cont!{
-        self.a1.try_send_msg( MsgA(msg.0))
+        self.a1.try_send_msg( MsgA(msg.0));
     }
 }
 
@@ -49,7 +49,7 @@ struct Ponger<P> where P: MsgReceiver<Pong> {
 impl_actor! { match msg for Actor<Ponger<P>,PongerMsg> where P: MsgReceiver<Pong> as
     Ping => term! {
         println!("ponger got {msg:?}");
-        self.pinger.try_send_msg( Pong( msg.0))
+        self.pinger.try_send_msg( Pong( msg.0));
     }
 }
 
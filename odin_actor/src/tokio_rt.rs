@@ -941,7 +941,7 @@ impl ActorSystem {
     }
 }
 
-type ActorTuple<S,M> = (Actor<S,M>, ActorHandle<M>, MpscReceiver<M>);
+pub type ActorTuple<S,M> = (Actor<S,M>, ActorHandle<M>, MpscReceiver<M>);
 
 fn actor_tuple<S,M> (hsys: Arc<ActorSystemHandle>, id: impl ToString, state: S, bound: usize)->ActorTuple<S,M>
     where S: Send + 'static, M: MsgTypeConstraints
@@ -974,6 +974,15 @@ fn pre_actor_tuple<S,M> (hsys: Arc<ActorSystemHandle>, state: S, mut pre_h: PreA
     (actor, actor_handle, rx)
 }
 
+/// run a (fallible) one-shot initialization closure on the state of a constructed but not yet spawned actor.
+/// This is what the `spawn_actor!( sys, name, state, [bounds,] init: |s| {..})` macro variant expands into,
+/// the actor is only spawned if this returns `Ok(())`
+pub fn init_actor_state<S,M,F> (act: &mut ActorTuple<S,M>, init: F)->Result<()>
+    where S: Send + 'static, M: MsgTypeConstraints, F: FnOnce(&mut S)->Result<()>
+{
+    init( &mut act.0.state)
+}
+
 async fn run_actor<M,R> (mut rx: MpscReceiver<M>, mut receiver: R)
     where
         M: MsgTypeConstraints,
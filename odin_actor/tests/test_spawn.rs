@@ -0,0 +1,58 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of 
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License"); 
+ * you may not use this file except in compliance with the License. You may obtain a copy 
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+use std::sync::{Arc,Mutex};
+use odin_actor::prelude::*;
+use odin_actor::errors::{Result,op_failed};
+
+define_actor_msg_set! { CounterMsg }
+
+struct Counter {
+    value: u32,
+    observed: Arc<Mutex<Option<u32>>> // what we saw when processing _Start_
+}
+
+impl_actor! { match msg for Actor<Counter,CounterMsg> as
+    _Start_ => term! {
+        *self.observed.lock().unwrap() = Some(self.value);
+    }
+}
+
+#[tokio::test]
+async fn test_spawn_with_init()->Result<()> {
+    let mut actor_system = ActorSystem::new("test");
+    let observed = Arc::new( Mutex::new( None));
+
+    let state = Counter { value: 1, observed: observed.clone() };
+    spawn_actor!( actor_system, "counter", state, 4, init: |s| { s.value += 41; Ok(()) })?;
+
+    actor_system.timeout_start_all( millis(20)).await?;
+    actor_system.process_requests().await?;
+
+    assert_eq!( *observed.lock().unwrap(), Some(42));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_spawn_with_failed_init() {
+    let mut actor_system = ActorSystem::new("test");
+    let observed = Arc::new( Mutex::new( None));
+
+    let state = Counter { value: 1, observed: observed.clone() };
+    let res = spawn_actor!( actor_system, "counter", state, init: |s| {
+        if s.value < 10 { Err( op_failed("value too small")) } else { Ok(()) }
+    });
+    assert!( res.is_err());
+    assert!( observed.lock().unwrap().is_none());
+}
@@ -12,7 +12,10 @@
  * and limitations under the License.
  */
 
-use std::collections::HashMap;
+use std::collections::{HashMap,VecDeque};
+use std::time::Duration;
+use serde::{Serialize,Serializer,ser::SerializeSeq};
+use crate::datetime::Dated;
 
 /// trait to get a snapshot Vec of cloned entries of the receiver collection.
 /// Useful to iterate over current entries of a mutable collection
@@ -44,4 +47,105 @@ pub fn new_vec<T> ()->Vec<T> {
 
 pub fn empty_vec<T> ()->Vec<T> {
     Vec::with_capacity(0)
-}
\ No newline at end of file
+}
+
+/// a time ordered store of [`Dated`] items (newest first) that is bounded by count and/or by age. The age bound
+/// is relative to the newest item in the store, i.e. it does not depend on wall clock time.
+/// Items older than all retained ones are dropped if the store is full
+#[derive(Debug,Clone)]
+pub struct TimeRingStore<T> where T: Dated {
+    items: VecDeque<T>,
+    max_len: Option<usize>,
+    max_age: Option<Duration>
+}
+
+impl<T> TimeRingStore<T> where T: Dated {
+    pub fn new (max_len: Option<usize>, max_age: Option<Duration>)->Self {
+        let items = if let Some(n) = max_len { VecDeque::with_capacity(n) } else { VecDeque::new() };
+        TimeRingStore { items, max_len, max_age }
+    }
+
+    pub fn with_max_len (max_len: usize)->Self { Self::new( Some(max_len), None) }
+
+    pub fn with_max_age (max_age: Duration)->Self { Self::new( None, Some(max_age)) }
+
+    pub fn len (&self)->usize { self.items.len() }
+
+    pub fn is_empty (&self)->bool { self.items.is_empty() }
+
+    pub fn newest (&self)->Option<&T> { self.items.front() }
+
+    pub fn oldest (&self)->Option<&T> { self.items.back() }
+
+    /// iterate newest-to-old
+    pub fn iter<'a> (&'a self)->impl Iterator<Item=&'a T> { self.items.iter() }
+
+    /// iterate old-to-new, i.e. the newest entry comes last
+    pub fn iter_old_to_new<'a> (&'a self)->impl Iterator<Item=&'a T> { self.items.iter().rev() }
+
+    /// sort in item according to its date and evict excess items. Returns true if the item was stored
+    pub fn insert (&mut self, item: T)->bool {
+        let date = item.date();
+        let idx = self.items.iter().position( |e| e.date() <= date).unwrap_or( self.items.len());
+
+        if let Some(max_len) = self.max_len {
+            if idx >= max_len { return false } // older than anything we keep
+        }
+        if let Some(max_age) = self.max_age {
+            if let Some(newest) = self.items.front() {
+                if is_older( date, newest, max_age) { return false }
+            }
+        }
+
+        self.items.insert( idx, item);
+        self.evict();
+        true
+    }
+
+    /// insert item, replacing an already stored item with the same key (if any). Returns true if the item was stored.
+    /// If the new item is rejected (too old) we keep the stored one
+    pub fn insert_dedup<K,F> (&mut self, item: T, key: F)->bool where K: PartialEq, F: Fn(&T)->K {
+        let k = key(&item);
+        if let Some(idx) = self.items.iter().position( |e| key(e) == k) {
+            let old = self.items.remove(idx).unwrap(); // idx is valid
+            if self.insert( item) {
+                true
+            } else {
+                self.items.insert( idx, old); // insert() did not modify items, i.e. this restores the previous state
+                false
+            }
+        } else {
+            self.insert( item)
+        }
+    }
+
+    fn evict (&mut self) {
+        if let Some(max_len) = self.max_len {
+            self.items.truncate( max_len);
+        }
+
+        if let Some(max_age) = self.max_age {
+            if let Some(newest) = self.items.front() {
+                let newest_date = newest.date();
+                while let Some(oldest) = self.items.back() {
+                    if is_older( oldest.date(), &newest_date, max_age) { self.items.pop_back(); } else { break }
+                }
+            }
+        }
+    }
+}
+
+fn is_older<D: Dated> (date: chrono::DateTime<chrono::Utc>, reference: &D, max_age: Duration)->bool {
+    (reference.date() - date).to_std().map_or( false, |d| d > max_age)
+}
+
+/// we serialize as a sequence of items (newest first)
+impl<T> Serialize for TimeRingStore<T> where T: Dated + Serialize {
+    fn serialize<S: Serializer> (&self, serializer: S)->Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq( Some(self.items.len()))?;
+        for e in &self.items {
+            seq.serialize_element(e)?;
+        }
+        seq.end()
+    }
+}
@@ -252,6 +252,39 @@ pub fn remove_old_files<T> (dir: &T, max_age: Duration)->Result<usize> where T:
     }
 }
 
+/// variant of [`remove_old_files`] that is safe to use while other tasks write to `dir`: files modified within
+/// `grace` are never removed (regardless of `max_age`), and neither are files for which `keep` returns true
+/// (e.g. paths of downloads that are in flight)
+pub fn remove_old_files_except<T,F> (dir: &T, max_age: Duration, grace: Duration, keep: F)->Result<usize> 
+    where T: AsRef<Path>, F: Fn(&Path)->bool
+{
+    let dir: &Path = dir.as_ref();
+    let min_age = max( max_age, grace);
+
+    if dir.is_dir() {
+        let now = SystemTime::now();
+        let mut n_removed = 0;
+
+        for e in fs::read_dir(dir)? {
+            let e = e?;
+            let path = e.path();
+            if path.is_file() && !keep(&path) {
+                if let Ok(last_mod) = fs::metadata(&path).and_then( |meta| meta.modified()) { // file might be gone by now
+                    if let Ok(age) = now.duration_since(last_mod) {
+                        if age > min_age {
+                            if fs::remove_file(&path).is_ok() { n_removed += 1 }
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(n_removed)
+    } else {
+        Err( io_error!(NotFound, "dir {:?}", dir))
+    }
+}
+
 pub fn visit_dirs (dir: &Path, recursive: bool, cb: &mut dyn FnMut(&DirEntry)) -> io::Result<()> {
     if dir.is_dir() {
         for entry in fs::read_dir(dir)? {
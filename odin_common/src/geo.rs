@@ -95,6 +95,13 @@ impl GeoBoundingBox {
             north: LatAngle::from_degrees(wsen[3])
         }
     }
+
+    /// check if `p` is within this bbox (including its edges). A bbox with `west > east` spans the anti-meridian
+    pub fn contains (&self, p: &LatLon) -> bool {
+        let (west, east) = (self.west.degrees(), self.east.degrees());
+        let is_in_lon = if west <= east { p.lon_deg >= west && p.lon_deg <= east } else { p.lon_deg >= west || p.lon_deg <= east };
+        is_in_lon && p.lat_deg >= self.south.degrees() && p.lat_deg <= self.north.degrees()
+    }
 }
 
 impl Hash for GeoBoundingBox {
@@ -124,6 +131,25 @@ impl LatLon {
     pub fn from_degrees (lat_deg: f64, lon_deg: f64)->Self {
         LatLon{ lat_deg, lon_deg }
     }
+
+    /// great circle distance to `other` in meters
+    pub fn distance_to (&self, other: &LatLon)->f64 {
+        haversine_distance( self, other)
+    }
+}
+
+/// mean earth radius in meters (IUGG)
+pub const MEAN_EARTH_RADIUS: f64 = 6_371_008.8;
+
+/// great circle distance in meters between two positions, using the haversine formula on a spherical earth
+pub fn haversine_distance (p1: &LatLon, p2: &LatLon)->f64 {
+    let φ1 = p1.lat_deg.to_radians();
+    let φ2 = p2.lat_deg.to_radians();
+    let Δφ = φ2 - φ1;
+    let Δλ = (p2.lon_deg - p1.lon_deg).to_radians();
+
+    let a = (Δφ/2.0).sin().powi(2) + φ1.cos() * φ2.cos() * (Δλ/2.0).sin().powi(2);
+    2.0 * MEAN_EARTH_RADIUS * a.sqrt().min(1.0).asin()
 }
 
 #[derive(Debug,Copy,Clone,Serialize,Deserialize,PartialEq)]
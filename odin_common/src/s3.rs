@@ -14,7 +14,7 @@
 
 //! support functions for AWS S3 objects
 
-use std::{path::{Path,PathBuf},fmt::{Debug,Display}, fs::File, io::{Write,Error}, ops::Deref};
+use std::{path::{Path,PathBuf},fmt::{Debug,Display}, fs::File, io::{Write,Error}, ops::Deref, collections::HashMap, future::Future};
 use thiserror::Error;
 use aws_sdk_s3::{Client, types::Object, operation::list_objects::builders::ListObjectsFluentBuilder};
 use aws_config::{Region,meta::region::RegionProviderChain};
@@ -54,6 +54,12 @@ pub enum OdinS3Error {
 pub struct S3Object(Object);
 
 impl S3Object {
+    /// create a new object with the given key and modification date, e.g. for mock object listings
+    pub fn new (key: impl Into<String>, last_modified: DateTime<Utc>)->Self {
+        let dt = aws_smithy_types::DateTime::from_millis( last_modified.timestamp_millis());
+        S3Object( Object::builder().key( key).last_modified( dt).build())
+    }
+
     pub fn is_dated (&self)->bool {
         self.last_modified.is_some()
     }
@@ -88,11 +94,30 @@ impl Dated for S3Object {
     }
 }
 
-/// create S3 Client for given region
+/// create S3 Client for given region that uses anonymous (unsigned) requests, which is what we need for public buckets
 pub async fn create_s3_client (region: String) -> Result<Client> {
+    create_s3_client_with( region, true, None).await
+}
+
+/// create S3 Client for given region and optional endpoint URL (e.g. for S3 compatible mirrors). If `anonymous` is set
+/// requests are not signed, otherwise credentials are obtained from the default AWS provider chain (env, profile etc.)
+pub async fn create_s3_client_with (region: String, anonymous: bool, endpoint: Option<&str>) -> Result<Client> {
     let region_provider = RegionProviderChain::first_try( Region::new( region));
-    let aws_config = aws_config::from_env().no_credentials().region(region_provider).load().await; // add anonymous creditials
-    Ok( Client::new(&aws_config) ) 
+    let mut loader = aws_config::from_env().region(region_provider);
+    if anonymous {
+        loader = loader.no_credentials();
+    }
+    if let Some(url) = endpoint {
+        loader = loader.endpoint_url( url);
+    }
+    let aws_config = loader.load().await;
+
+    if endpoint.is_some() { // non-AWS endpoints usually don't support virtual-hosted style bucket addressing
+        let s3_config = aws_sdk_s3::config::Builder::from( &aws_config).force_path_style(true).build();
+        Ok( Client::from_conf( s3_config) )
+    } else {
+        Ok( Client::new(&aws_config) )
+    }
 }
 
 /// retrieve all objects (from optional marker) for given bucket/prefix. If there is no error this always returns a `Vec<S3Object>`
@@ -107,6 +132,65 @@ pub async fn get_s3_objects (client: &Client, bucket: &str, prefix: &str, prev_k
     Ok( result.contents().to_vec().into_iter().map(|o| S3Object(o)).collect() )
 }
 
+/// abstraction for object listings so that we can use mock or caching sources
+pub trait S3ObjectLister {
+    /// retrieve all objects (from optional marker) for given bucket/prefix, see [`get_s3_objects`]
+    fn list_objects (&self, bucket: &str, prefix: &str, prev_key: Option<&str>) -> impl Future<Output=Result<Vec<S3Object>>> + Send;
+}
+
+impl S3ObjectLister for Client {
+    fn list_objects (&self, bucket: &str, prefix: &str, prev_key: Option<&str>) -> impl Future<Output=Result<Vec<S3Object>>> + Send {
+        get_s3_objects( self, bucket, prefix, prev_key)
+    }
+}
+
+/// a least-recently-used cache for object listings of immutable (bucket,prefix) pairs, such as past hours of
+/// time-partitioned buckets. It is the callers responsibility to decide if a prefix can be cached
+pub struct S3ListingCache {
+    max_prefixes: usize,
+    tick: u64,
+    entries: HashMap<(String,String),(u64,Vec<S3Object>)>, // (bucket,prefix) -> (last access, listing)
+}
+
+impl S3ListingCache {
+    pub fn new (max_prefixes: usize)->Self {
+        S3ListingCache { max_prefixes, tick: 0, entries: HashMap::new() }
+    }
+
+    pub fn len (&self)->usize { self.entries.len() }
+
+    /// list objects (from optional marker) for given bucket/prefix. If `is_immutable` is set the complete listing is
+    /// cached and subsequent calls are served from the cache. Otherwise this always uses `lister`
+    pub async fn list_objects<L> (&mut self, lister: &L, bucket: &str, prefix: &str, prev_key: Option<&str>, is_immutable: bool) -> Result<Vec<S3Object>>
+        where L: S3ObjectLister
+    {
+        if !is_immutable || self.max_prefixes == 0 {
+            return lister.list_objects( bucket, prefix, prev_key).await
+        }
+
+        self.tick += 1;
+        let k = (bucket.to_string(), prefix.to_string());
+        let objs = if let Some((tick,objs)) = self.entries.get_mut( &k) {
+            *tick = self.tick;
+            objs.clone()
+        } else {
+            let objs = lister.list_objects( bucket, prefix, None).await?; // cache the whole listing
+            if self.entries.len() >= self.max_prefixes {
+                if let Some(lru) = self.entries.iter().min_by_key( |(_,(tick,_))| *tick).map( |(k,_)| k.clone()) {
+                    self.entries.remove( &lru);
+                }
+            }
+            self.entries.insert( k, (self.tick, objs.clone()));
+            objs
+        };
+
+        match prev_key { // S3 markers are exclusive and objects are listed in key order
+            Some(marker) => Ok( objs.into_iter().filter( |o| o.key().map_or( false, |key| key > marker)).collect()),
+            None => Ok(objs)
+        }
+    }
+}
+
 /// retrieve last object (from optional marker) for given bucket/prefix. Note this can return Ok(None) in case the
 /// query was without error but there is no matching object
 pub async fn get_last_s3_object (client: &Client, dt: DateTime<Utc>, bucket: &str, prefix: &str, prev_key: Option<&String>) -> Result<Option<S3Object>> {
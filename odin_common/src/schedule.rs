@@ -14,6 +14,7 @@
 
 //! support module for computing schedules
 
+use std::time::Duration;
 use crate::datetime::Dated;
 use chrono::{DateTime, TimeDelta, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike, Utc};
 
@@ -146,4 +147,52 @@ fn right_edge_compact_histogram (hist: &mut Vec<u32>){
     } 
 }
 
-/* #endregion hourly schedule */
\ No newline at end of file
+/* #endregion hourly schedule */
+
+/* #region adaptive poll interval *********************************************************************************/
+
+const RATE_ALPHA: f64 = 0.3; // smoothing factor for the arrival rate moving average
+
+/// poll interval that adapts to the observed data arrival rate within `[min,max]` bounds: each poll that found new
+/// data shortens the interval by the `speedup` factor, each poll without new data lengthens it by the `slowdown` factor.
+/// This keeps polling frequent while data is flowing and reduces server requests during quiet periods
+#[derive(Debug,Clone)]
+pub struct AdaptiveInterval {
+    min: Duration,
+    max: Duration,
+    current: Duration,
+    speedup: f64,  // < 1
+    slowdown: f64, // > 1
+    rate: f64      // exponential moving average of arrivals per second
+}
+
+impl AdaptiveInterval {
+    /// note we start with the `min` interval since we don't know the arrival rate yet
+    pub fn new (min: Duration, max: Duration)->Self {
+        AdaptiveInterval { min, max: max.max(min), current: min, speedup: 0.5, slowdown: 1.5, rate: 0.0 }
+    }
+
+    pub fn with_factors (mut self, speedup: f64, slowdown: f64)->Self {
+        self.speedup = speedup;
+        self.slowdown = slowdown;
+        self
+    }
+
+    /// the interval to wait before the next poll
+    pub fn current (&self)->Duration { self.current }
+
+    /// smoothed number of arrivals per second
+    pub fn arrival_rate (&self)->f64 { self.rate }
+
+    /// record the number of new items found by the last poll and return the interval until the next poll
+    pub fn update (&mut self, n_new: usize)->Duration {
+        let secs = self.current.as_secs_f64();
+        self.rate = RATE_ALPHA * (n_new as f64 / secs) + (1.0 - RATE_ALPHA) * self.rate;
+
+        let factor = if n_new > 0 { self.speedup } else { self.slowdown };
+        self.current = Duration::from_secs_f64( secs * factor).clamp( self.min, self.max);
+        self.current
+    }
+}
+
+/* #endregion adaptive poll interval */
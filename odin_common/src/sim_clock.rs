@@ -114,22 +114,60 @@ impl SettableSimClock {
     }
 }
 
-enum SimClock {
+/// abstraction for clock instances that can be passed into functions which would otherwise use `Utc::now()`.
+/// This is an alternative to the global sim clock below for components that need to be driven by their own
+/// (e.g. replay or test) time 
+pub trait Clock: Send + Sync {
+    fn now (&self)->DateTime<Utc>;
+}
+
+/// a [`Clock`] that just returns wall time
+#[derive(Debug,Clone,Copy,Default)]
+pub struct RealClock;
+
+impl Clock for RealClock {
+    #[inline]
+    fn now (&self)->DateTime<Utc> { Utc::now() }
+}
+
+/// a [`Clock`] instance for simulation time that progresses with the given timescale from a settable start time.
+/// Use a timescale of 0 to get a clock that only changes when it is explicitly set
+pub struct SimClock {
+    clock: Mutex<SettableSimClock>,
+    timescale: u32,
+}
+
+impl SimClock {
+    pub fn new (start_dt: DateTime<Utc>, timescale: u32)->Self {
+        SimClock { clock: Mutex::new( SettableSimClock::new( start_dt, timescale, true, false)), timescale }
+    }
+
+    pub fn set (&self, dt: DateTime<Utc>) {
+        // we can't fail since our SettableSimClock is resettable. Ok to panic on poisoned lock
+        self.clock.lock().unwrap().reset( dt, self.timescale);
+    }
+}
+
+impl Clock for SimClock {
+    fn now (&self)->DateTime<Utc> { self.clock.lock().unwrap().now() }
+}
+
+enum GlobalClock {
     Settable(Mutex<SettableSimClock>),
     Wall
 }
 
-/// our global SimClock instance (don't make this public)
-static SIM_CLOCK: OnceLock<SimClock> = OnceLock::new();
+/// our global clock instance (don't make this public)
+static SIM_CLOCK: OnceLock<GlobalClock> = OnceLock::new();
 
 pub fn initialize (start_dt: DateTime<Utc>, timescale: u32, is_resettable: bool, is_suspendable: bool)->Result<(),OdinClockError> {
-    SIM_CLOCK.set( SimClock::Settable(Mutex::new(SettableSimClock::new(start_dt, timescale, is_resettable, is_suspendable))))
+    SIM_CLOCK.set( GlobalClock::Settable(Mutex::new(SettableSimClock::new(start_dt, timescale, is_resettable, is_suspendable))))
         .map_err(|e| OdinClockError::ClockInitError("sim clock already initialized".to_string()))
 }
 
  /// this is infallible but we want to keep the return type the same 
 pub fn initialize_wall ()->Result<(),OdinClockError> {
-    SIM_CLOCK.set( SimClock::Wall);
+    SIM_CLOCK.set( GlobalClock::Wall);
     Ok(())
 }
 
@@ -137,8 +175,8 @@ pub fn is_settable ()->Result<bool,OdinClockError> {
     match SIM_CLOCK.get() {
         Some(sim_clock) => {
             match sim_clock {
-                SimClock::Settable(_) => Ok(true),
-                SimClock::Wall => Ok(false)
+                GlobalClock::Settable(_) => Ok(true),
+                GlobalClock::Wall => Ok(false)
             }
         }
         None => Err( OdinClockError::ClockNotInitialized)
@@ -149,8 +187,8 @@ pub fn now()->Result<DateTime<Utc>,OdinClockError> {
     match SIM_CLOCK.get() {
         Some(sim_clock) => {
             match sim_clock {
-                SimClock::Settable(sim_clock) => Ok(sim_clock.lock()?.now()),
-                SimClock::Wall => Ok(Utc::now())
+                GlobalClock::Settable(sim_clock) => Ok(sim_clock.lock()?.now()),
+                GlobalClock::Wall => Ok(Utc::now())
             }
         }
         None => Err( OdinClockError::ClockNotInitialized)
@@ -161,8 +199,8 @@ pub fn now_local()->Result<DateTime<Local>,OdinClockError> {
     match SIM_CLOCK.get() {
         Some(sim_clock) => 
             match sim_clock {
-                SimClock::Settable(sim_clock) => Ok(sim_clock.lock()?.now_local()),
-                SimClock::Wall => Ok(Local::now())
+                GlobalClock::Settable(sim_clock) => Ok(sim_clock.lock()?.now_local()),
+                GlobalClock::Wall => Ok(Local::now())
             }
         None => Err( OdinClockError::ClockNotInitialized)
     }
@@ -172,8 +210,8 @@ pub fn epoch_millis()->Result<i64,OdinClockError> {
     match SIM_CLOCK.get() {
         Some(sim_clock) => 
             match sim_clock {
-                SimClock::Settable(sim_clock) => Ok(sim_clock.lock()?.epoch_millis()),
-                SimClock::Wall => Ok(Utc::now().timestamp_millis())
+                GlobalClock::Settable(sim_clock) => Ok(sim_clock.lock()?.epoch_millis()),
+                GlobalClock::Wall => Ok(Utc::now().timestamp_millis())
             }        
         None => Err( OdinClockError::ClockNotInitialized)
     }
@@ -183,11 +221,11 @@ pub fn reset (start_dt: DateTime<Utc>, timescale: u32)->Result<(),OdinClockError
     match SIM_CLOCK.get() {
         Some(sim_clock) => {
             match sim_clock {
-                SimClock::Settable(sim_clock) => {
+                GlobalClock::Settable(sim_clock) => {
                     let mut sim_clock = sim_clock.lock()?;
                     sim_clock.reset( start_dt, timescale)
                 }
-                SimClock::Wall => Err( OdinClockError::IllegalClockOp("wall clock cannot be reset".to_string()))
+                GlobalClock::Wall => Err( OdinClockError::IllegalClockOp("wall clock cannot be reset".to_string()))
             }   
         }
         None => Err( OdinClockError::ClockNotInitialized)
@@ -198,11 +236,11 @@ pub fn suspend ()->Result<(),OdinClockError> {
     match SIM_CLOCK.get() {
         Some(sim_clock) => {
             match sim_clock {
-                SimClock::Settable(sim_clock) => {
+                GlobalClock::Settable(sim_clock) => {
                     let mut sim_clock = sim_clock.lock()?;
                     sim_clock.suspend()
                 }
-                SimClock::Wall => Err( OdinClockError::IllegalClockOp("wall clock cannot be suspended".to_string()))
+                GlobalClock::Wall => Err( OdinClockError::IllegalClockOp("wall clock cannot be suspended".to_string()))
             }   
         }
         None => Err( OdinClockError::ClockNotInitialized)
@@ -213,11 +251,11 @@ pub fn resume ()->Result<(),OdinClockError> {
     match SIM_CLOCK.get() {
         Some(sim_clock) => {
             match sim_clock {
-                SimClock::Settable(sim_clock) => {
+                GlobalClock::Settable(sim_clock) => {
                     let mut sim_clock = sim_clock.lock()?;
                     sim_clock.resume()
                 }
-                SimClock::Wall => Err( OdinClockError::IllegalClockOp("wall clock cannot be resumed".to_string()))
+                GlobalClock::Wall => Err( OdinClockError::IllegalClockOp("wall clock cannot be resumed".to_string()))
             }   
         }
         None => Err( OdinClockError::ClockNotInitialized)
@@ -228,8 +266,8 @@ pub fn is_suspended ()->Result<bool,OdinClockError> {
     match SIM_CLOCK.get() {
         Some(sim_clock) => 
             match sim_clock {
-                SimClock::Settable(sim_clock) => Ok(sim_clock.lock()?.is_suspended()),
-                SimClock::Wall => Ok(false) // wall clock can't be suspended
+                GlobalClock::Settable(sim_clock) => Ok(sim_clock.lock()?.is_suspended()),
+                GlobalClock::Wall => Ok(false) // wall clock can't be suspended
             }        
         None => Err( OdinClockError::ClockNotInitialized)
     }
@@ -0,0 +1,86 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License. You may obtain a copy
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+use std::time::Duration;
+use chrono::{DateTime,Utc};
+use odin_common::{collections::TimeRingStore, datetime::Dated};
+
+#[derive(Debug,Clone,PartialEq)]
+struct Item { id: u32, secs: i64 }
+
+impl Dated for Item {
+    fn date (&self)->DateTime<Utc> { DateTime::<Utc>::from_timestamp(self.secs, 0).unwrap() }
+}
+
+fn ids (store: &TimeRingStore<Item>)->Vec<u32> { store.iter().map(|i| i.id).collect() }
+
+#[test]
+fn test_count_eviction() {
+    let mut store = TimeRingStore::with_max_len(3);
+    for (id,secs) in [(1,100), (2,200), (3,300), (4,400)] {
+        assert!( store.insert( Item{id,secs}));
+    }
+    assert_eq!( ids(&store), vec![4,3,2]); // newest first, oldest evicted
+
+    assert!( !store.insert( Item{id:5, secs:50})); // older than anything we keep
+    assert_eq!( store.len(), 3);
+}
+
+#[test]
+fn test_age_eviction() {
+    let mut store = TimeRingStore::with_max_age( Duration::from_secs(150));
+    store.insert( Item{id:1, secs:100});
+    store.insert( Item{id:2, secs:200});
+    assert_eq!( ids(&store), vec![2,1]);
+
+    store.insert( Item{id:3, secs:300}); // 1 is now 200s older than the newest item
+    assert_eq!( ids(&store), vec![3,2]);
+
+    assert!( !store.insert( Item{id:4, secs:120})); // too old to be stored
+}
+
+#[test]
+fn test_out_of_order_insert() {
+    let mut store = TimeRingStore::with_max_len(5);
+    store.insert( Item{id:1, secs:100});
+    store.insert( Item{id:3, secs:300});
+    store.insert( Item{id:2, secs:200});
+    assert_eq!( ids(&store), vec![3,2,1]);
+
+    let old_to_new: Vec<u32> = store.iter_old_to_new().map(|i| i.id).collect();
+    assert_eq!( old_to_new, vec![1,2,3]);
+}
+
+#[test]
+fn test_dedup_insert() {
+    let mut store = TimeRingStore::with_max_len(5);
+    store.insert_dedup( Item{id:1, secs:100}, |i| i.id);
+    store.insert_dedup( Item{id:2, secs:200}, |i| i.id);
+    store.insert_dedup( Item{id:1, secs:300}, |i| i.id); // replaces the first one
+
+    assert_eq!( ids(&store), vec![1,2]);
+    assert_eq!( store.newest().unwrap().secs, 300);
+}
+
+#[test]
+fn test_rejected_dedup_insert() {
+    let mut store = TimeRingStore::with_max_age( Duration::from_secs(150));
+    store.insert_dedup( Item{id:1, secs:300}, |i| i.id);
+    store.insert_dedup( Item{id:2, secs:250}, |i| i.id);
+
+    // too old to be stored - this should not remove the stored item with the same key
+    assert!( !store.insert_dedup( Item{id:2, secs:100}, |i| i.id));
+    assert_eq!( ids(&store), vec![1,2]);
+    assert_eq!( store.iter().find( |i| i.id == 2).unwrap().secs, 250);
+}
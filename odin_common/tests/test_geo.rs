@@ -0,0 +1,33 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License. You may obtain a copy
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+use odin_common::geo::{GeoBoundingBox,LatLon};
+
+#[test]
+fn test_bbox_contains() {
+    let bbox = GeoBoundingBox::from_wsen_degrees( &[-123.0, 37.0, -121.0, 39.0]);
+    assert!( bbox.contains( &LatLon::from_degrees( 38.0, -122.0)));
+    assert!( bbox.contains( &LatLon::from_degrees( 37.0, -121.0))); // edges are inside
+    assert!( !bbox.contains( &LatLon::from_degrees( 40.0, -122.0)));
+    assert!( !bbox.contains( &LatLon::from_degrees( 38.0, -120.0)));
+}
+
+#[test]
+fn test_antimeridian_bbox_contains() {
+    let bbox = GeoBoundingBox::from_wsen_degrees( &[170.0, -20.0, -170.0, -10.0]);
+    assert!( bbox.contains( &LatLon::from_degrees( -15.0, 175.0)));
+    assert!( bbox.contains( &LatLon::from_degrees( -15.0, -175.0)));
+    assert!( !bbox.contains( &LatLon::from_degrees( -15.0, 0.0)));
+    assert!( !bbox.contains( &LatLon::from_degrees( -25.0, 175.0)));
+}
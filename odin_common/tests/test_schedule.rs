@@ -0,0 +1,53 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License. You may obtain a copy
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+use std::time::Duration;
+use odin_common::schedule::AdaptiveInterval;
+
+const MIN: Duration = Duration::from_secs(30);
+const MAX: Duration = Duration::from_secs(300);
+
+#[test]
+fn test_quiet_then_bursty() {
+    let mut interval = AdaptiveInterval::new( MIN, MAX);
+    assert_eq!( interval.current(), MIN);
+
+    // quiet period - interval grows until it hits the max bound
+    let mut last = interval.current();
+    for _ in 0..10 {
+        let next = interval.update(0);
+        assert!( next >= last && next <= MAX);
+        last = next;
+    }
+    assert_eq!( interval.current(), MAX);
+    assert_eq!( interval.arrival_rate(), 0.0);
+
+    // burst - interval shrinks until it hits the min bound
+    for _ in 0..10 {
+        let next = interval.update(3);
+        assert!( next <= last && next >= MIN);
+        last = next;
+    }
+    assert_eq!( interval.current(), MIN);
+    assert!( interval.arrival_rate() > 0.0);
+}
+
+#[test]
+fn test_alternating_stays_in_bounds() {
+    let mut interval = AdaptiveInterval::new( MIN, MAX).with_factors( 0.8, 1.2);
+    for i in 0..50 {
+        let d = interval.update( if i % 3 == 0 { 1 } else { 0 });
+        assert!( d >= MIN && d <= MAX);
+    }
+}
@@ -0,0 +1,55 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License. You may obtain a copy
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+///! compare serial and parallel scanline search for fire pixels in a GOES-R NetCDF file, e.g.
+///!   cargo run --release --example benchmark_find_grid_points -- OR_ABI-L2-FDCF-M6_G18_....nc
+///! like the odin_actor benchmarks this is not in the benches/ dir since it needs external (large) data
+
+use std::time::Instant;
+use odin_gdal::{GridPoint, find_grid_points_in_slice, par_find_grid_points_in_slice, quiet_nc_dataset};
+
+fn find_fire_pixels (i1: usize, row: &[u16], grid_points: &mut Vec<GridPoint<u16>>) {
+    for i0 in 0..row.len() {
+        let mask = row[i0];
+        if mask >= 10 && mask <= 35 { grid_points.push( GridPoint{i0,i1,value: mask}) }
+    }
+}
+
+fn main()->std::result::Result<(),Box<dyn std::error::Error>> {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() < 2 {
+        eprintln!("usage: benchmark_find_grid_points <goesr-fdc.nc> [var-name] [rounds]");
+        return Ok(())
+    }
+    let var_name = args.get(2).map(|s| s.as_str()).unwrap_or("Mask");
+    let rounds: u32 = args.get(3).map(|s| s.parse().expect("rounds not an integer")).unwrap_or(10);
+
+    let ds = quiet_nc_dataset( &args[1], var_name)?;
+    let (x_size,y_size) = ds.rasterband(1)?.size();
+    println!("-- scanning {}x{} grid of {} in {} rounds", x_size, y_size, var_name, rounds);
+
+    let start = Instant::now();
+    let mut n = 0;
+    for _ in 0..rounds { n = find_grid_points_in_slice( &ds, 1, find_fire_pixels)?.len(); }
+    let serial = start.elapsed() / rounds;
+    println!("serial:   {} points in {} μs", n, serial.as_micros());
+
+    let start = Instant::now();
+    for _ in 0..rounds { n = par_find_grid_points_in_slice( &ds, 1, find_fire_pixels)?.len(); }
+    let parallel = start.elapsed() / rounds;
+    println!("parallel: {} points in {} μs ({} threads) -> speedup {:.2}", 
+             n, parallel.as_micros(), rayon::current_num_threads(), serial.as_secs_f64() / parallel.as_secs_f64());
+
+    Ok(())
+}
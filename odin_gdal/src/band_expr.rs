@@ -0,0 +1,273 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of 
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License"); 
+ * you may not use this file except in compliance with the License. You may obtain a copy 
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+use std::path::Path;
+use gdal::Dataset;
+use gdal::raster::Buffer;
+use crate::create_dataset;
+use crate::errors::{Result, misc_error};
+
+/// compute a single f32 band dataset at `out_path` from the bands `band_indices` of `ds`, one raster line at a time.
+/// `f` is called with the input lines (in `band_indices` order) and the output line to fill. Input nodata values are
+/// replaced by NaN before calling `f`, and non-finite output values are stored as NaN, which is the nodata value of
+/// the output band
+pub fn compute_rasterband_lines<F> (ds: &Dataset, band_indices: &[usize], out_path: impl AsRef<Path>, mut f: F) -> Result<Dataset>
+    where F: FnMut(&[Vec<f64>], &mut [f64])
+{
+    if band_indices.is_empty() { return Err( misc_error( "no input bands".to_string())) }
+
+    let (x_size, y_size) = ds.raster_size();
+    let mut src_bands = Vec::with_capacity( band_indices.len());
+    for i in band_indices {
+        let band = ds.rasterband(*i)?;
+        let no_data = band.no_data_value();
+        src_bands.push( (band, no_data));
+    }
+
+    let mut tgt_ds = create_dataset::<f32>( out_path, x_size, y_size, 1)?;
+    if let Ok(gt) = ds.geo_transform() { tgt_ds.set_geo_transform( &gt)?; }
+    if let Ok(srs) = ds.spatial_ref() { tgt_ds.set_spatial_ref( &srs)?; }
+    let mut tgt_band = tgt_ds.rasterband(1)?;
+    tgt_band.set_no_data_value( Some(f64::NAN))?;
+
+    let mut lines: Vec<Vec<f64>> = vec![ vec![0.0; x_size]; band_indices.len()];
+    let mut out_line: Vec<f64> = vec![0.0; x_size];
+
+    for y in 0..y_size {
+        for ((band,no_data), line) in src_bands.iter().zip( lines.iter_mut()) {
+            band.read_into_slice( (0, y as isize), (x_size,1), (x_size,1), line, None)?;
+            if let Some(nd) = no_data {
+                line.iter_mut().for_each( |v| if *v == *nd { *v = f64::NAN });
+            }
+        }
+
+        f( &lines, &mut out_line);
+
+        let out: Vec<f32> = out_line.iter().map( |v| if v.is_finite() { *v as f32 } else { f32::NAN }).collect();
+        tgt_band.write( (0, y as isize), (x_size,1), &mut Buffer::new( (x_size,1), out))?;
+    }
+    drop(tgt_band);
+
+    Ok(tgt_ds)
+}
+
+/// evaluate the arithmetic expression `expr` over the named bands of `ds` and write the result as a single f32 band
+/// dataset to `out_path`. `band_names` maps the identifiers used in `expr` to band indices, e.g. 
+/// `eval_band_expr( &ds, "(b1 - b2) / (b1 + b2)", &[("b1",4),("b2",3)], "ndvi.tif")`.
+/// Expressions support numbers, `+ - * /`, unary minus, parentheses and the functions `min(a,b)`, `max(a,b)`,
+/// `abs(a)`, `sqrt(a)` and `clamp(a,lo,hi)`. If any input of a pixel is nodata (or the result is not finite, e.g. due
+/// to a division by zero) the output pixel is nodata (NaN)
+pub fn eval_band_expr (ds: &Dataset, expr: &str, band_names: &[(&str,usize)], out_path: impl AsRef<Path>) -> Result<()> {
+    let band_expr = BandExpr::parse( expr, band_names)?;
+    let band_indices: Vec<usize> = band_names.iter().map( |(_,i)| *i).collect();
+    let mut vals: Vec<f64> = vec![0.0; band_indices.len()];
+
+    compute_rasterband_lines( ds, &band_indices, out_path, |lines, out_line| {
+        for x in 0..out_line.len() {
+            for (v,line) in vals.iter_mut().zip( lines.iter()) { *v = line[x] }
+            out_line[x] = band_expr.eval( &vals);
+        }
+    })?;
+
+    Ok(())
+}
+
+/// a parsed band expression. Band references are indices into the `band_names` slice it was parsed with
+#[derive(Debug,Clone,PartialEq)]
+pub enum BandExpr {
+    Const(f64),
+    Band(usize),
+    Neg(Box<BandExpr>),
+    Add(Box<BandExpr>,Box<BandExpr>),
+    Sub(Box<BandExpr>,Box<BandExpr>),
+    Mul(Box<BandExpr>,Box<BandExpr>),
+    Div(Box<BandExpr>,Box<BandExpr>),
+    Call(BandFunc,Vec<BandExpr>)
+}
+
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub enum BandFunc { Min, Max, Abs, Sqrt, Clamp }
+
+impl BandFunc {
+    fn from_name (name: &str)->Option<(Self,usize)> { // function and arity
+        match name {
+            "min" => Some( (BandFunc::Min, 2)),
+            "max" => Some( (BandFunc::Max, 2)),
+            "abs" => Some( (BandFunc::Abs, 1)),
+            "sqrt" => Some( (BandFunc::Sqrt, 1)),
+            "clamp" => Some( (BandFunc::Clamp, 3)),
+            _ => None
+        }
+    }
+}
+
+impl BandExpr {
+    pub fn parse (expr: &str, band_names: &[(&str,usize)]) -> Result<BandExpr> {
+        let tokens = tokenize( expr)?;
+        let mut parser = Parser { tokens, pos: 0, band_names };
+        let e = parser.parse_sum()?;
+        if parser.pos < parser.tokens.len() {
+            return Err( misc_error( format!("unexpected {:?} in band expression '{}'", parser.tokens[parser.pos], expr)))
+        }
+        Ok(e)
+    }
+
+    /// evaluate for the given band values (in `band_names` order). NaN inputs propagate to the result
+    pub fn eval (&self, vals: &[f64]) -> f64 {
+        match self {
+            BandExpr::Const(c) => *c,
+            BandExpr::Band(i) => vals[*i],
+            BandExpr::Neg(a) => -a.eval(vals),
+            BandExpr::Add(a,b) => a.eval(vals) + b.eval(vals),
+            BandExpr::Sub(a,b) => a.eval(vals) - b.eval(vals),
+            BandExpr::Mul(a,b) => a.eval(vals) * b.eval(vals),
+            BandExpr::Div(a,b) => a.eval(vals) / b.eval(vals),
+            BandExpr::Call(func,args) => {
+                let args: Vec<f64> = args.iter().map( |a| a.eval(vals)).collect();
+                if args.iter().any( |a| a.is_nan()) { return f64::NAN } // f64::min/max would ignore NaNs
+                match func {
+                    BandFunc::Min => args[0].min( args[1]),
+                    BandFunc::Max => args[0].max( args[1]),
+                    BandFunc::Abs => args[0].abs(),
+                    BandFunc::Sqrt => args[0].sqrt(),
+                    BandFunc::Clamp => args[0].max( args[1]).min( args[2]),
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug,Clone,PartialEq)]
+enum Token { Num(f64), Ident(String), Op(char), LParen, RParen, Comma }
+
+fn tokenize (expr: &str) -> Result<Vec<Token>> {
+    let chars: Vec<char> = expr.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() || c == '.' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') { i += 1 }
+            let s: String = chars[start..i].iter().collect();
+            tokens.push( Token::Num( s.parse()?));
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') { i += 1 }
+            tokens.push( Token::Ident( chars[start..i].iter().collect()));
+        } else {
+            tokens.push( match c {
+                '+' | '-' | '*' | '/' => Token::Op(c),
+                '(' => Token::LParen,
+                ')' => Token::RParen,
+                ',' => Token::Comma,
+                _ => return Err( misc_error( format!("invalid character '{}' in band expression '{}'", c, expr)))
+            });
+            i += 1;
+        }
+    }
+    Ok(tokens)
+}
+
+/// simple recursive descent parser for:
+///   sum     := product (('+'|'-') product)*
+///   product := unary (('*'|'/') unary)*
+///   unary   := '-' unary | primary
+///   primary := number | band | func '(' sum (',' sum)* ')' | '(' sum ')'
+struct Parser<'a> {
+    tokens: Vec<Token>,
+    pos: usize,
+    band_names: &'a [(&'a str,usize)]
+}
+
+impl<'a> Parser<'a> {
+    fn peek (&self)->Option<&Token> { self.tokens.get( self.pos) }
+
+    fn next (&mut self)->Result<Token> {
+        let t = self.tokens.get( self.pos).cloned().ok_or_else( || misc_error( "unexpected end of band expression".to_string()))?;
+        self.pos += 1;
+        Ok(t)
+    }
+
+    fn expect (&mut self, expected: Token)->Result<()> {
+        let t = self.next()?;
+        if t == expected { Ok(()) } else { Err( misc_error( format!("expected {:?}, got {:?} in band expression", expected, t))) }
+    }
+
+    fn parse_sum (&mut self)->Result<BandExpr> {
+        let mut e = self.parse_product()?;
+        while let Some(Token::Op(op)) = self.peek().cloned() {
+            if op != '+' && op != '-' { break }
+            self.pos += 1;
+            let rhs = self.parse_product()?;
+            e = if op == '+' { BandExpr::Add( Box::new(e), Box::new(rhs)) } else { BandExpr::Sub( Box::new(e), Box::new(rhs)) };
+        }
+        Ok(e)
+    }
+
+    fn parse_product (&mut self)->Result<BandExpr> {
+        let mut e = self.parse_unary()?;
+        while let Some(Token::Op(op)) = self.peek().cloned() {
+            if op != '*' && op != '/' { break }
+            self.pos += 1;
+            let rhs = self.parse_unary()?;
+            e = if op == '*' { BandExpr::Mul( Box::new(e), Box::new(rhs)) } else { BandExpr::Div( Box::new(e), Box::new(rhs)) };
+        }
+        Ok(e)
+    }
+
+    fn parse_unary (&mut self)->Result<BandExpr> {
+        if self.peek() == Some(&Token::Op('-')) {
+            self.pos += 1;
+            Ok( BandExpr::Neg( Box::new( self.parse_unary()?)))
+        } else {
+            self.parse_primary()
+        }
+    }
+
+    fn parse_primary (&mut self)->Result<BandExpr> {
+        match self.next()? {
+            Token::Num(v) => Ok( BandExpr::Const(v)),
+            Token::LParen => {
+                let e = self.parse_sum()?;
+                self.expect( Token::RParen)?;
+                Ok(e)
+            }
+            Token::Ident(name) => {
+                if self.peek() == Some(&Token::LParen) {
+                    let (func,arity) = BandFunc::from_name( &name).ok_or_else( || misc_error( format!("unknown function '{}' in band expression", name)))?;
+                    self.pos += 1;
+                    let mut args = vec![ self.parse_sum()?];
+                    while self.peek() == Some(&Token::Comma) {
+                        self.pos += 1;
+                        args.push( self.parse_sum()?);
+                    }
+                    self.expect( Token::RParen)?;
+                    if args.len() != arity {
+                        return Err( misc_error( format!("function '{}' expects {} arguments, got {}", name, arity, args.len())))
+                    }
+                    Ok( BandExpr::Call( func, args))
+                } else {
+                    let idx = self.band_names.iter().position( |(n,_)| *n == name)
+                        .ok_or_else( || misc_error( format!("unknown band '{}' in band expression", name)))?;
+                    Ok( BandExpr::Band(idx))
+                }
+            }
+            t => Err( misc_error( format!("unexpected {:?} in band expression", t)))
+        }
+    }
+}
@@ -46,6 +46,14 @@ struct CliOpts {
     #[structopt(long)]
     is_south: bool,
 
+    /// max number of UTM zones the lat/lon bounds can span
+    #[structopt(long)]
+    max_zones: Option<u32>,
+
+    /// project bounds into a single UTM zone regardless of how many zones they span
+    #[structopt(long)]
+    allow_wide: bool,
+
     // reverse transformation (UTM -> epsg:4326 (lat,lon))
     #[structopt(short="r",long)]
     utm_to_latlon: bool,
@@ -75,7 +83,7 @@ fn main() {
          }
 
      } else {
-         let res = transform_latlon_to_utm_bounds(ARGS.west, ARGS.south, ARGS.east, ARGS.north, ARGS.interior, ARGS.zone, ARGS.is_south);
+         let res = transform_latlon_to_utm_bounds(ARGS.west, ARGS.south, ARGS.east, ARGS.north, ARGS.interior, ARGS.zone, ARGS.is_south, ARGS.max_zones, ARGS.allow_wide);
 
          match res {
              Ok((x_min,y_min,x_max,y_max, utm_zone)) => {
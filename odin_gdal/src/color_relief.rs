@@ -0,0 +1,87 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of 
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License"); 
+ * you may not use this file except in compliance with the License. You may obtain a copy 
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+use std::path::Path;
+use gdal::Dataset;
+use gdal::raster::Buffer;
+use crate::create_dataset;
+use crate::errors::{Result, misc_error};
+
+/// the RGBA output for nodata pixels
+pub const TRANSPARENT: [u8;4] = [0,0,0,0];
+
+/// map `value` through a piecewise-linear color `ramp` of (value,RGBA) stops that is sorted by ascending value.
+/// Colors are interpolated per channel between the two bracketing stops, values outside the ramp get the color
+/// of the respective end stop. An empty ramp maps everything to [`TRANSPARENT`] (see [`check_color_ramp`])
+pub fn ramp_color (ramp: &[(f64,[u8;4])], value: f64) -> [u8;4] {
+    if ramp.is_empty() { return TRANSPARENT }
+
+    let i = ramp.partition_point( |(v,_)| *v <= value);
+    if i == 0 { return ramp[0].1 }
+    if i == ramp.len() { return ramp[i-1].1 }
+
+    let (v0,c0) = ramp[i-1];
+    let (v1,c1) = ramp[i];
+    let t = (value - v0) / (v1 - v0);
+
+    let mut c = [0u8;4];
+    for k in 0..4 {
+        c[k] = (c0[k] as f64 + t * (c1[k] as f64 - c0[k] as f64)).round() as u8;
+    }
+    c
+}
+
+/// check that `ramp` is a valid argument for [`ramp_color`], i.e. it is not empty and its stops are sorted by
+/// strictly ascending (non-NaN) values
+pub fn check_color_ramp (ramp: &[(f64,[u8;4])]) -> Result<()> {
+    if ramp.is_empty() { return Err( misc_error( "empty color ramp".to_string())) }
+    if ramp.iter().any( |(v,_)| v.is_nan()) { return Err( misc_error( "NaN value in color ramp".to_string())) }
+    if ramp.windows(2).any( |w| w[0].0 >= w[1].0) { return Err( misc_error( "color ramp not sorted by ascending value".to_string())) }
+    Ok(())
+}
+
+/// create a 4 band (RGBA) u8 color relief image for the elevation values in `band` of `ds` and write it to `out_path`.
+/// Each value is mapped through `ramp` (see [`ramp_color`]), nodata pixels are fully transparent
+pub fn color_relief (ds: &Dataset, band: usize, ramp: &[(f64,[u8;4])], out_path: impl AsRef<Path>) -> Result<()> {
+    check_color_ramp( ramp)?;
+
+    let src_band = ds.rasterband(band)?;
+    let (x_size, y_size) = src_band.size();
+    let no_data = src_band.no_data_value();
+    let is_valid = |v: f64| !v.is_nan() && no_data.map_or( true, |nd| v != nd);
+
+    let mut tgt_ds = create_dataset::<u8>( out_path, x_size, y_size, 4)?;
+    if let Ok(gt) = ds.geo_transform() { tgt_ds.set_geo_transform( &gt)?; }
+    if let Ok(srs) = ds.spatial_ref() { tgt_ds.set_spatial_ref( &srs)?; }
+
+    let mut row: Vec<f64> = vec![0.0; x_size];
+    let mut out_rows: [Vec<u8>;4] = [vec![0; x_size], vec![0; x_size], vec![0; x_size], vec![0; x_size]];
+
+    for y in 0..y_size {
+        src_band.read_into_slice( (0, y as isize), (x_size,1), (x_size,1), &mut row, None)?;
+
+        for x in 0..x_size {
+            let v = row[x];
+            let c = if is_valid(v) { ramp_color( ramp, v) } else { TRANSPARENT };
+            for k in 0..4 { out_rows[k][x] = c[k] }
+        }
+
+        for k in 0..4 {
+            tgt_ds.rasterband(k+1)?.write( (0, y as isize), (x_size,1), &mut Buffer::new( (x_size,1), out_rows[k].clone()))?;
+        }
+    }
+
+    tgt_ds.flush_cache()?;
+    Ok(())
+}
@@ -33,6 +33,7 @@ pub struct ContourBuilder <'a> {
     tgt_layer_name: CString,
     field_id_index: Option<c_int>,
     interval: Option<c_int>,
+    fixed_levels: Option<Vec<f64>>,
     attr_min_name: Option<CString>,
     attr_min_id: Option<c_int>,
     attr_max_name: Option<CString>,
@@ -58,6 +59,7 @@ impl <'a> ContourBuilder<'a> {
             tgt_layer_name : tgt_layer_name,
             field_id_index: None,
             interval: None,
+            fixed_levels: None,
             attr_min_name: None,
             attr_min_id: None,
             attr_max_name: None,
@@ -82,6 +84,12 @@ impl <'a> ContourBuilder<'a> {
         self
     }
 
+    /// use explicit (possibly non-uniform) contour levels. This is mutually exclusive with [`set_interval`]
+    pub fn set_fixed_levels (&mut self, levels: &[f64]) -> &mut ContourBuilder<'a>  {
+        self.fixed_levels = Some(levels.to_vec());
+        self
+    }
+
     pub fn set_poly (&mut self) -> &mut ContourBuilder<'a> {
         self.polygonize = true;
         self
@@ -206,6 +214,7 @@ impl <'a> ContourBuilder<'a> {
     }
 
     pub fn exec(&mut self)-> Result<()> {
+        self.check_levels()?; // before we create anything
         let tgt_ds = self.create_tgt_ds()?;
         let tgt_layer = self.create_tgt_layer(tgt_ds)?;
         let options = self.build_options()?;
@@ -249,14 +258,34 @@ impl <'a> ContourBuilder<'a> {
                 options.add_string("POLYGONIZE=YES");
             }
             options.add_string(&format!("ID_FIELD={:?}", self.field_id_index.unwrap()).to_string());
-            if let Some(interval) = self.interval{
+            if let Some(interval) = self.interval {
                 options.add_string(&format!("LEVEL_INTERVAL={}", interval).to_string());
-            } else {
-                return Err(OdinGdalError::MiscError("no interval set for contour operations".to_string()))
-            };
+            } else if let Some(levels) = &self.fixed_levels { // exclusivity was checked in check_levels
+                let levels: Vec<String> = levels.iter().map(|l| l.to_string()).collect();
+                options.add_string(&format!("FIXED_LEVELS={}", levels.join(",")));
+            }
             Ok(options)
         }
     }
+
+    fn check_levels(&self) -> Result<()> {
+        match (self.interval, &self.fixed_levels) {
+            (Some(_), None) => Ok(()),
+            (None, Some(levels)) => {
+                if levels.is_empty() {
+                    Err(OdinGdalError::MiscError("empty fixed levels for contour operations".to_string()))
+                } else {
+                    Ok(())
+                }
+            }
+            (Some(_), Some(_)) => {
+                Err(OdinGdalError::MiscError("interval and fixed levels are mutually exclusive for contour operations".to_string()))
+            }
+            (None, None) => {
+                Err(OdinGdalError::MiscError("no interval or fixed levels set for contour operations".to_string()))
+            }
+        }
+    }
 }
 
 
@@ -0,0 +1,84 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of 
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License"); 
+ * you may not use this file except in compliance with the License. You may obtain a copy 
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+use std::path::Path;
+use gdal::Dataset;
+use gdal::raster::Buffer;
+use crate::create_dataset;
+use crate::errors::{Result, misc_error};
+
+/// the output value for nodata pixels (shaded values are in 1..=255)
+pub const HILLSHADE_NODATA: u8 = 0;
+
+/// compute a hillshade for the elevation values in `band` of `ds` and write it as a single u8 band dataset to `out_path`.
+/// This uses Horn's algorithm (3x3 weighted gradient) on a sliding window of three rows. Azimuth is clockwise from north, 
+/// altitude is the sun elevation above the horizon. Edge pixels and pixels with a nodata neighbor are set to [`HILLSHADE_NODATA`]
+pub fn hillshade (ds: &Dataset, band: usize, out_path: impl AsRef<Path>, azimuth_deg: f64, altitude_deg: f64, z_factor: f64) -> Result<Dataset> {
+    let src_band = ds.rasterband(band)?;
+    let (x_size, y_size) = src_band.size();
+    if x_size < 3 || y_size < 3 { return Err( misc_error( format!("raster too small for hillshade: {}x{}", x_size, y_size))) }
+
+    let no_data = src_band.no_data_value();
+    let gt = ds.geo_transform()?;
+    let ew_res = gt[1];
+    let ns_res = gt[5].abs();
+
+    // unit vector pointing to the light source (x: east, y: north, z: up)
+    let az = azimuth_deg.to_radians();
+    let alt = altitude_deg.to_radians();
+    let (lx, ly, lz) = (az.sin() * alt.cos(), az.cos() * alt.cos(), alt.sin());
+
+    let mut tgt_ds = create_dataset::<u8>( out_path, x_size, y_size, 1)?;
+    tgt_ds.set_geo_transform( &gt)?;
+    if let Ok(srs) = ds.spatial_ref() { tgt_ds.set_spatial_ref( &srs)?; }
+    let mut tgt_band = tgt_ds.rasterband(1)?;
+    tgt_band.set_no_data_value( Some(HILLSHADE_NODATA as f64))?;
+
+    // the 3-row window (north to south)
+    let mut rows: [Vec<f64>;3] = [vec![0.0; x_size], vec![0.0; x_size], vec![0.0; x_size]];
+    src_band.read_into_slice( (0,0), (x_size,1), (x_size,1), &mut rows[1], None)?;
+    src_band.read_into_slice( (0,1), (x_size,1), (x_size,1), &mut rows[2], None)?;
+
+    let is_valid = |v: f64| !v.is_nan() && no_data.map_or( true, |nd| v != nd);
+    let mut out_row: Vec<u8> = vec![HILLSHADE_NODATA; x_size];
+
+    for y in 0..y_size {
+        out_row.fill( HILLSHADE_NODATA);
+
+        if y > 0 && y < y_size-1 {
+            rows.rotate_left(1);
+            src_band.read_into_slice( (0, (y+1) as isize), (x_size,1), (x_size,1), &mut rows[2], None)?;
+            let (n, c, s) = (&rows[0], &rows[1], &rows[2]);
+
+            for x in 1..x_size-1 {
+                let (a, b, cc) = (n[x-1], n[x], n[x+1]);
+                let (d, e, f) = (c[x-1], c[x], c[x+1]);
+                let (g, h, i) = (s[x-1], s[x], s[x+1]);
+                if ![a,b,cc,d,e,f,g,h,i].iter().all( |v| is_valid(*v)) { continue }
+
+                let dzdx = z_factor * ((cc + 2.0*f + i) - (a + 2.0*d + g)) / (8.0 * ew_res);
+                let dzdy = z_factor * ((a + 2.0*b + cc) - (g + 2.0*h + i)) / (8.0 * ns_res);
+
+                // cosine between surface normal (-dzdx,-dzdy,1) and light vector
+                let cos_i = (lz - dzdx*lx - dzdy*ly) / (1.0 + dzdx*dzdx + dzdy*dzdy).sqrt();
+                out_row[x] = (255.0 * cos_i).round().clamp( 1.0, 255.0) as u8;
+            }
+        }
+
+        tgt_band.write( (0, y as isize), (x_size,1), &mut Buffer::new( (x_size,1), out_row.clone()))?;
+    }
+
+    tgt_ds.flush_cache()?;
+    Ok(tgt_ds)
+}
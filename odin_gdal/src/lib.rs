@@ -17,19 +17,23 @@
 pub mod errors;
 pub mod warp;
 pub mod contour;
+pub mod hillshade;
+pub mod color_relief;
+pub mod band_expr;
 
 use lazy_static::lazy_static;
-use std::{path::Path, fs::File, sync::Mutex, ops::{Sub,Index,Fn}, ffi::{CString,CStr}, ptr::{null, null_mut}, collections::HashMap};
+use std::{path::{Path,PathBuf}, fs::File, sync::{Arc,Mutex,OnceLock}, ops::{Sub,Index,Fn}, ffi::{CString,CStr}, ptr::{null, null_mut}, collections::HashMap};
 use libc::{c_void,c_char,c_uint, c_int};
 
 // we re-export these so that other crates don't have to use a direct gdal depedency to import.
 // this is to ensure we run bindgen for new GDAL versions that don't yet have pre-computed bindings in gdal-sys
 pub use gdal::{self, Driver, DriverManager, Metadata, MetadataEntry, Dataset, errors::GdalError, GeoTransform, cpl::CslStringList};
-pub use gdal::raster::{GdalType,RasterBand,Buffer};
+pub use gdal::raster::{GdalType,GdalDataType,RasterBand,Buffer,ResampleAlg};
 pub use gdal::spatial_ref::{CoordTransform, CoordTransformOptions, SpatialRef};
 
 use gdal_sys::{self,CPLErrorReset, OGRErr, OSRExportToWkt, OSRNewSpatialReference, OSRSetFromUserInput, CPLErr};
 use geo::{Coord, Rect};
+use rayon::prelude::*;
 
 use odin_common::{fs::{existing_non_empty_file_from_path,get_filename_extension},geo::*,ranges::LinearRange};
 use odin_common::macros::if_let;
@@ -63,6 +67,9 @@ lazy_static! {
     ]);
 }
 
+// extension -> driver short name mappings that were resolved from DriverManager (see get_driver_for_path_dynamic)
+static DYN_EXT_MAP: OnceLock<Mutex<HashMap<String,String>>> = OnceLock::new();
+
 /// use this to protect non-threadsafe GDAL operations
 static GLOB_GDAL_MUTEX: Mutex<usize> = Mutex::new(0);
 
@@ -87,6 +94,34 @@ pub fn get_driver_from_filename (filename: &str) -> Option<gdal::Driver> {
         .and_then( |n| DriverManager::get_driver_by_name(&**n).ok())
 }
 
+/// get driver for path, first checking our [`EXT_MAP`] and then (on a miss) the `DMD_EXTENSIONS` metadata of all
+/// registered GDAL drivers. Resolved (lower case) extensions are cached so that repeated lookups stay cheap
+pub fn get_driver_for_path_dynamic (path: impl AsRef<Path>) -> Option<gdal::Driver> {
+    let filename = path.as_ref().to_str()?;
+    let ext = get_filename_extension(filename)?.to_lowercase();
+
+    if let Some(name) = EXT_MAP.get( ext.as_str()) {
+        return DriverManager::get_driver_by_name( name).ok()
+    }
+
+    let mut cache = DYN_EXT_MAP.get_or_init( || Mutex::new( HashMap::new())).lock().ok()?;
+    if let Some(name) = cache.get( &ext) {
+        return DriverManager::get_driver_by_name( name).ok()
+    }
+
+    for i in 0..DriverManager::count() {
+        if let Ok(driver) = DriverManager::get_driver(i) {
+            if let Some(exts) = driver.metadata_item( "DMD_EXTENSIONS", "") {
+                if exts.split_whitespace().any( |e| e.eq_ignore_ascii_case( &ext)) {
+                    cache.insert( ext, driver.short_name());
+                    return Some(driver)
+                }
+            }
+        }
+    }
+    None
+}
+
 pub fn pc_char_to_string (pc_char: *const c_char) -> String {
     let cstr = unsafe { CStr::from_ptr(pc_char) };
     String::from_utf8_lossy(cstr.to_bytes()).to_string()
@@ -225,28 +260,174 @@ pub fn latlon_to_utm_bounds (bbox: &BoundingBox<f64>, interior:  bool) -> (Bound
     (BoundingBox {west, south: ll_utm.northing, east, north: ul_utm.northing}, zone)
 }
 
+/// default for the max number of UTM zones a lat/lon bounding box can span before we refuse to project it into a single zone
+pub const DEFAULT_MAX_UTM_ZONES: u32 = 1;
+
+const UTM_ZONE_WIDTH_DEG: f64 = 6.0;
+
+/// check that the east-west extent of the given lat/lon bounds does not exceed `max_zones` UTM zone widths. Projecting
+/// wider bounds into a single UTM zone results in large distortions at the edges
+pub fn check_utm_zone_span (west_deg: f64, east_deg: f64, max_zones: u32) -> Result<()> {
+    let extent = if east_deg >= west_deg { east_deg - west_deg } else { east_deg + 360.0 - west_deg }; // anti-meridian
+    if extent > max_zones as f64 * UTM_ZONE_WIDTH_DEG {
+        let zone_of = |lon: f64| ((lon + 180.0) / UTM_ZONE_WIDTH_DEG).floor() as u32 % 60 + 1;
+        Err( misc_error( format!("bounds {}..{} span {:.3}° (UTM zones {}..{}), which exceeds the max of {} zone(s)", 
+                                 west_deg, east_deg, extent, zone_of(west_deg), zone_of(east_deg), max_zones)))
+    } else {
+        Ok(())
+    }
+}
+
+/* #region transform cache ****************************************************************************************/
+
+/// the (hashable) subset of [`CoordTransformOptions`] we use. The default is what our bounds transforms need: exact
+/// transformation (desired accuracy 0) without ballpark fallbacks
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub struct TransformOptions {
+    pub desired_accuracy: Option<f64>,
+    pub ballpark_allowed: bool,
+}
+
+impl Default for TransformOptions {
+    fn default()->Self {
+        TransformOptions { desired_accuracy: Some(0.0), ballpark_allowed: false }
+    }
+}
+
+impl TransformOptions {
+    pub fn options_hash (&self) -> u64 {
+        use std::hash::{Hash,Hasher};
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        self.desired_accuracy.map( |a| a.to_bits()).hash( &mut hasher);
+        self.ballpark_allowed.hash( &mut hasher);
+        hasher.finish()
+    }
+
+    pub fn to_coord_transform_options (&self) -> Result<CoordTransformOptions> {
+        let mut ct_options = CoordTransformOptions::new()?;
+        if let Some(accuracy) = self.desired_accuracy { ct_options.desired_accuracy( accuracy); }
+        ct_options.set_ballpark_allowed( self.ballpark_allowed);
+        Ok(ct_options)
+    }
+}
+
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash)]
+pub struct TransformKey {
+    pub src_epsg: u32,
+    pub tgt_epsg: u32,
+    pub options_hash: u64,
+}
+
+/// a [`CoordTransform`] that can be shared between threads. GDAL coordinate transformations are not thread safe,
+/// hence all access is serialized through our `GLOB_GDAL_MUTEX`
+pub struct SharedCoordTransform {
+    transform: CoordTransform
+}
+
+// all access to the (raw pointer) GDAL handle is serialized by GLOB_GDAL_MUTEX
+unsafe impl Send for SharedCoordTransform {}
+unsafe impl Sync for SharedCoordTransform {}
+
+impl SharedCoordTransform {
+    pub fn new (transform: CoordTransform) -> Self {
+        SharedCoordTransform { transform }
+    }
+
+    /// run the provided closure while holding the global GDAL lock. Note the closure must not call [`run_quiet`]
+    pub fn with<T,F> (&self, f: F) -> T where F: FnOnce(&CoordTransform)->T {
+        let lock = GLOB_GDAL_MUTEX.lock().unwrap();
+        f( &self.transform)
+    }
+
+    pub fn transform_point_2d (&self, x: f64, y: f64) -> Result<(f64,f64)> {
+        self.with( |transform| transform_point_2d( transform, x, y))
+    }
+}
+
+/// cache for [`CoordTransform`] objects between EPSG coordinate systems, to avoid the (expensive) creation of
+/// SpatialRefs and transforms on each call
+pub struct TransformCache {
+    transforms: Mutex<HashMap<TransformKey,Arc<SharedCoordTransform>>>
+}
+
+impl Default for TransformCache {
+    fn default() -> Self {
+        TransformCache::new()
+    }
+}
+
+impl TransformCache {
+    pub fn new() -> Self {
+        TransformCache { transforms: Mutex::new( HashMap::new()) }
+    }
+
+    /// get the cached transform for the given EPSG pair and options, creating it if it is not yet cached
+    pub fn get (&self, src_epsg: u32, tgt_epsg: u32, opts: &TransformOptions) -> Result<Arc<SharedCoordTransform>> {
+        let key = TransformKey { src_epsg, tgt_epsg, options_hash: opts.options_hash() };
+        let mut transforms = self.transforms.lock().unwrap(); // we also hold this while creating new transforms
+
+        if let Some(transform) = transforms.get( &key) {
+            Ok( transform.clone())
+        } else {
+            let s_srs = SpatialRef::from_epsg( src_epsg)?;
+            let t_srs = SpatialRef::from_epsg( tgt_epsg)?;
+            let ct_options = opts.to_coord_transform_options()?;
+            let transform = Arc::new( SharedCoordTransform::new( CoordTransform::new_with_options( &s_srs, &t_srs, &ct_options)?));
+            transforms.insert( key, transform.clone());
+            Ok(transform)
+        }
+    }
+
+    pub fn contains (&self, src_epsg: u32, tgt_epsg: u32, opts: &TransformOptions) -> bool {
+        let key = TransformKey { src_epsg, tgt_epsg, options_hash: opts.options_hash() };
+        self.transforms.lock().unwrap().contains_key( &key)
+    }
+
+    pub fn len (&self) -> usize {
+        self.transforms.lock().unwrap().len()
+    }
+
+    pub fn clear (&self) {
+        self.transforms.lock().unwrap().clear()
+    }
+}
+
+static TRANSFORM_CACHE: OnceLock<TransformCache> = OnceLock::new();
+
+/// the process global transform cache that is used by our EPSG based transform functions
+pub fn global_transform_cache() -> &'static TransformCache {
+    TRANSFORM_CACHE.get_or_init( || TransformCache::new())
+}
+
+/* #endregion transform cache */
+
+/// transform lat/lon bounds into a single UTM zone, which is either provided or computed from the center of the bounds.
+/// Returns an error if the bounds span more than `max_zones` (default [`DEFAULT_MAX_UTM_ZONES`]) zone widths unless
+/// `allow_wide` is set
 pub fn transform_latlon_to_utm_bounds (west_deg: f64, south_deg: f64, east_deg: f64, north_deg: f64,
-                                       interior:  bool, utm_zone: Option<u32>, is_south: bool) -> Result<(f64,f64,f64,f64,u32)> {
-    let s_srs = srs_epsg_4326(); // axis order is lat,lon, uom: degrees
+                                       interior:  bool, utm_zone: Option<u32>, is_south: bool, 
+                                       max_zones: Option<u32>, allow_wide: bool) -> Result<(f64,f64,f64,f64,u32)> {
+    if !allow_wide {
+        check_utm_zone_span( west_deg, east_deg, max_zones.unwrap_or( DEFAULT_MAX_UTM_ZONES))?;
+    }
 
-    let (t_srs,zone) = if let Some(zone) = utm_zone {
+    // source is epsg:4326 - axis order is lat,lon, uom: degrees
+    let (tgt_epsg,zone) = if let Some(zone) = utm_zone {
         let zone_base = if is_south { 32700 } else { 32600 };
-        (srs_epsg( zone_base + zone)?, zone)
+        (zone_base + zone, zone)
     } else {
         let (lon_center,lat_center) = bounds_center(west_deg,south_deg,east_deg,north_deg);
-        srs_utm_from_lon_lat(lon_center, lat_center, utm_zone)?
+        let (_,zone) = srs_utm_from_lon_lat(lon_center, lat_center, utm_zone)?;
+        let zone_base = if lat_center < 0.0 { 32700 } else { 32600 };
+        (zone_base + zone, zone)
     };
 
-    //let transform = CoordTransform::new(&s_srs, &t_srs)?;
-    let mut ct_options = CoordTransformOptions::new()?;
-    ct_options.desired_accuracy( 0.0);
-    ct_options.set_ballpark_allowed(false);
-    let transform = CoordTransform::new_with_options(&s_srs, &t_srs, &ct_options)?;
+    let transform = global_transform_cache().get( 4326, tgt_epsg, &TransformOptions::default())?;
 
-    let (x_ll,y_ll) = transform_point_2d( &transform, south_deg, west_deg)?;
-    let (x_lr,y_lr) = transform_point_2d( &transform, south_deg, east_deg)?;
-    let (x_ul,y_ul) = transform_point_2d( &transform, north_deg, west_deg)?;
-    let (x_ur,y_ur) = transform_point_2d( &transform, north_deg, east_deg)?;
+    let (x_ll,y_ll) = transform.transform_point_2d( south_deg, west_deg)?;
+    let (x_lr,y_lr) = transform.transform_point_2d( south_deg, east_deg)?;
+    let (x_ul,y_ul) = transform.transform_point_2d( north_deg, west_deg)?;
+    let (x_ur,y_ur) = transform.transform_point_2d( north_deg, east_deg)?;
 
     if interior {
         Ok( (x_ll.max(x_ul),  y_ll.max(y_lr), x_lr.min(x_ur), y_ul.min(y_ur), zone) )
@@ -257,18 +438,12 @@ pub fn transform_latlon_to_utm_bounds (west_deg: f64, south_deg: f64, east_deg:
 
 pub fn transform_utm_to_latlon_bounds (west_m: f64, south_m: f64, east_m: f64, north_m: f64, interior: bool, utm_zone: u32, is_south: bool) -> Result<(f64,f64,f64,f64)> {
     let zone_base = if is_south { 32700 } else { 32600 };
-    let s_srs = srs_epsg( zone_base + utm_zone)?;
-    let t_srs = srs_epsg_4326();
-
-    let mut ct_options = CoordTransformOptions::new()?;
-    ct_options.desired_accuracy( 0.0);
-    ct_options.set_ballpark_allowed(false);
-    let transform = CoordTransform::new_with_options(&s_srs, &t_srs, &ct_options)?;
+    let transform = global_transform_cache().get( zone_base + utm_zone, 4326, &TransformOptions::default())?;
 
-    let (y_ll,x_ll) = transform_point_2d( &transform, west_m, south_m)?;
-    let (y_lr,x_lr) = transform_point_2d( &transform, east_m, south_m)?;
-    let (y_ul,x_ul) = transform_point_2d( &transform, west_m, north_m)?;
-    let (y_ur,x_ur) = transform_point_2d( &transform, east_m, north_m)?;
+    let (y_ll,x_ll) = transform.transform_point_2d( west_m, south_m)?;
+    let (y_lr,x_lr) = transform.transform_point_2d( east_m, south_m)?;
+    let (y_ul,x_ul) = transform.transform_point_2d( west_m, north_m)?;
+    let (y_ur,x_ur) = transform.transform_point_2d( east_m, north_m)?;
 
     if interior {
         Ok( (x_ll.max(x_ul),  y_ll.max(y_lr), x_lr.min(x_ur), y_ul.min(y_ur)) )
@@ -277,33 +452,59 @@ pub fn transform_utm_to_latlon_bounds (west_m: f64, south_m: f64, east_m: f64, n
     }
 }
 
-// watch out - if source or target are geographic we might have to swap axis order
-// (we don't want to change axis_mapping_strategy in the provided SpatialRefs though)
-// TODO - round trips between epsg:4326 and UTM produce differing results also in lat/northing, find out why
+/// does the given SpatialRef expect/produce coordinates in (lat,lon) order? This is the case for geographic
+/// SRS such as EPSG:4326 if they use the (GDAL >= 3 default) authority compliant axis mapping strategy
+fn has_lat_lon_axis_order (srs: &SpatialRef) -> bool {
+    srs.is_geographic()
+        && unsafe {
+            gdal_sys::OSRGetAxisMappingStrategy( srs.to_c_hsrs()) == gdal_sys::OSRAxisMappingStrategy::OAMS_AUTHORITY_COMPLIANT
+            && gdal_sys::OSREPSGTreatsAsLatLong( srs.to_c_hsrs()) != 0
+        }
+}
+
+/// the EPSG code of `srs` if it is equivalent to `SpatialRef::from_epsg(code)`, i.e. if we can use a cached transform
+/// for it. This requires the default (authority compliant) axis mapping strategy
+fn cacheable_epsg (srs: &SpatialRef) -> Option<u32> {
+    let is_authority_compliant = unsafe {
+        gdal_sys::OSRGetAxisMappingStrategy( srs.to_c_hsrs()) == gdal_sys::OSRAxisMappingStrategy::OAMS_AUTHORITY_COMPLIANT
+    };
+    if is_authority_compliant && srs.auth_name().ok()?.eq_ignore_ascii_case( "EPSG") {
+        srs.auth_code().ok().and_then( |code| u32::try_from( code).ok())
+    } else {
+        None
+    }
+}
+
+/// transform bounds given in (x,y) order (i.e. lon/lat for geographic SRS) between `s_srs` and `t_srs`. The returned
+/// bounds are also in (x,y) order and enclose the densified edges of the source bounds.
+///
+/// Axis order is normalized separately for source and target based on their respective axis mapping strategy, i.e. the
+/// forward and inverse transforms are symmetric (we don't want to change axis_mapping_strategy in the provided
+/// SpatialRefs though). Note that bounds round trips (e.g. epsg:4326 -> UTM -> epsg:4326) are still not identities
+/// since each step returns the enclosing rectangle of the curved (densified) edges, i.e. round trip bounds grow by
+/// an amount that depends on the size and location of the bounds within the UTM zone
 pub fn transform_bounds_2d (s_srs: &SpatialRef, t_srs: &SpatialRef,
                             x_min: f64, y_min: f64,
                             x_max: f64, y_max: f64,
                             opt_densify_pts: Option<i32>) -> Result<(f64,f64,f64,f64)> {
 
-    let s_is_geo = s_srs.is_geographic();
-    let t_is_geo = t_srs.is_geographic();
+    let s_swap = has_lat_lon_axis_order( s_srs);
+    let t_swap = has_lat_lon_axis_order( t_srs);
 
-    let mut bounds: [f64;4] = if s_is_geo && !t_is_geo { [y_min,x_min,y_max,x_max] } else { [x_min,y_min,x_max,y_max] };
-    let densify_pts: i32 = if let Some(dp) = opt_densify_pts { dp } else { 21 }; // default recommended by GDAL OCTTransformBounds doc
+    let mut bounds: [f64;4] = if s_swap { [y_min,x_min,y_max,x_max] } else { [x_min,y_min,x_max,y_max] };
+    let densify_pts: i32 = opt_densify_pts.unwrap_or( 21); // default recommended by GDAL OCTTransformBounds doc
 
-    let mut ct_options = CoordTransformOptions::new()?;
-    ct_options.desired_accuracy( 0.0);
-    ct_options.set_ballpark_allowed(false);
+    let opts = TransformOptions::default(); // exact, no ballpark
+    let transform = match (cacheable_epsg( s_srs), cacheable_epsg( t_srs)) {
+        (Some(src_epsg), Some(tgt_epsg)) => global_transform_cache().get( src_epsg, tgt_epsg, &opts)?,
+        _ => {
+            let ct_options = opts.to_coord_transform_options()?;
+            Arc::new( SharedCoordTransform::new( CoordTransform::new_with_options(s_srs,t_srs, &ct_options).map_err(gdal_error)?))
+        }
+    };
+    let a = transform.with( |transform| transform.transform_bounds(&mut bounds, densify_pts)).map_err(gdal_error)?;
 
-    //CoordTransform::new(s_srs,t_srs)
-    CoordTransform::new_with_options(s_srs,t_srs, &ct_options)
-        .and_then( |transform| transform.transform_bounds(&mut bounds, densify_pts))
-        .map_err(|e| {
-            gdal_error(e)})
-        .and_then( |a| {
-            let ta = if t_is_geo && !s_is_geo { (a[1], a[0], a[3], a[2]) } else { (a[0], a[1], a[2], a[3]) };
-            Ok(ta)
-        })
+    if t_swap { Ok( (a[1], a[0], a[3], a[2])) } else { Ok( (a[0], a[1], a[2], a[3])) }
 }
 
 /* #region well known SpatialRefs *********************************************************************************/
@@ -412,6 +613,43 @@ pub fn find_grid_points_in_slice<T,F> (ds: &Dataset, band_index: usize, accumula
 }
 
 
+/// parallel version of [`find_grid_points_in_slice`] that reads disjoint blocks of rows on the rayon thread pool and
+/// merges the per-block results in row order, i.e. the result is the same as for the serial version.
+///
+/// Since neither `Dataset` nor `RasterBand` are `Sync` we cannot share `ds` between workers. Each worker therefore
+/// re-opens the dataset from its description (file path or GDAL subdataset name such as `NETCDF:"file.nc":Mask`),
+/// which means `ds` has to be re-openable read-only and should not have unsaved modifications. This only pays off for
+/// large grids (e.g. GOES-R full disk scenes) where the read time dominates the open overhead
+pub fn par_find_grid_points_in_slice<T,F> (ds: &Dataset, band_index: usize, accumulator: F)->Result<Vec<GridPoint<T>>> 
+    where T: GdalValueType + Send, F: Fn(usize,&[T],&mut Vec<GridPoint<T>>) + Sync
+{
+    let ds_name = ds.description()?;
+    let (x_size, y_size) = ds.rasterband(band_index)?.size();
+
+    let n_blocks = (rayon::current_num_threads() * 4).min( y_size.max(1));
+    let block_rows = (y_size + n_blocks - 1) / n_blocks.max(1);
+
+    let blocks: Vec<Vec<GridPoint<T>>> = (0..n_blocks).into_par_iter()
+        .map_init(
+            || Dataset::open( &ds_name),
+            |opt_ds, block| {
+                let ds = opt_ds.as_ref().map_err( |e| misc_error( format!("failed to re-open {}: {}", ds_name, e)))?;
+                let band = ds.rasterband(band_index)?;
+                let mut scan_line: Vec<T> = vec![ 0.into(); x_size];
+                let mut result: Vec<GridPoint<T>> = Vec::new();
+
+                let i1_end = ((block+1) * block_rows).min( y_size);
+                for i1 in (block * block_rows)..i1_end {
+                    band.read_into_slice( (0, i1 as isize), (x_size,1), (x_size,1), &mut scan_line, None)?;
+                    accumulator( i1, &scan_line, &mut result);
+                }
+                Ok(result)
+            })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok( blocks.into_iter().flatten().collect() )
+}
+
 /// get Vec of values for given Vec<GridPoint2D> reference
 pub fn get_grid_point_values<T,U> (ds: &Dataset, band_index: usize, sub_no_data: Option<T>, pts: &Vec<GridPoint<U>> )->Result<Vec<T>> 
     where T: GdalValueType + Into<f64>, U: GdalValueType
@@ -440,6 +678,113 @@ pub fn get_grid_point_values<T,U> (ds: &Dataset, band_index: usize, sub_no_data:
     Ok(result)
 }
 
+/// raster size and geo transform of a dataset, together with the inverse transform so that we can
+/// convert between (continuous) pixel coordinates and positions in the SRS of the dataset.
+/// Note that cell (col,row) covers the pixel coordinates [col..col+1) x [row..row+1)
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub struct RasterInfo {
+    pub x_size: usize,
+    pub y_size: usize,
+    pub geo_transform: GeoTransform,
+    pub inv_geo_transform: GeoTransform
+}
+
+impl RasterInfo {
+    pub fn new (x_size: usize, y_size: usize, geo_transform: GeoTransform) -> Result<Self> {
+        let mut gt = geo_transform;
+        let mut inv_gt: GeoTransform = [0.0; 6];
+        ok_not_zero( unsafe { gdal_sys::GDALInvGeoTransform( gt.as_mut_ptr(), inv_gt.as_mut_ptr()) }, 
+                     || "geo transform not invertible".to_string())?;
+
+        Ok( RasterInfo { x_size, y_size, geo_transform, inv_geo_transform: inv_gt } )
+    }
+
+    pub fn from_dataset (ds: &Dataset) -> Result<Self> {
+        let (x_size, y_size) = ds.raster_size();
+        RasterInfo::new( x_size, y_size, ds.geo_transform()?)
+    }
+
+    /// continuous pixel coordinates (col,row) of position (x,y)
+    pub fn geo_to_pixel (&self, x: f64, y: f64) -> (f64,f64) {
+        let t = &self.inv_geo_transform;
+        ( t[0] + x * t[1] + y * t[2], t[3] + x * t[4] + y * t[5] )
+    }
+
+    /// position (x,y) of continuous pixel coordinates (col,row). Use `col + 0.5, row + 0.5` to get cell centers
+    pub fn pixel_to_geo (&self, col: f64, row: f64) -> (f64,f64) {
+        let t = &self.geo_transform;
+        ( t[0] + col * t[1] + row * t[2], t[3] + col * t[4] + row * t[5] )
+    }
+
+    /// integer (col,row) of the cell that contains position (x,y). This might be outside the raster
+    pub fn geo_to_cell (&self, x: f64, y: f64) -> (isize,isize) {
+        let (px,py) = self.geo_to_pixel( x, y);
+        ( px.floor() as isize, py.floor() as isize )
+    }
+
+    /// is the continuous pixel coordinate within the raster
+    pub fn contains_pixel (&self, px: f64, py: f64) -> bool {
+        px >= 0.0 && py >= 0.0 && px < self.x_size as f64 && py < self.y_size as f64
+    }
+}
+
+/// how to sample band values at (non grid-aligned) positions
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Default)]
+pub enum Sampling {
+    /// value of the cell that contains the position
+    #[default]
+    Nearest,
+    /// interpolate between the 2x2 cell centers around the position. Falls back to `Nearest` if any of the
+    /// neighbors is nodata or outside the raster
+    Bilinear
+}
+
+/// get values of band `band_index` at the given (x,y) positions, which are in the SRS of the dataset.
+/// Positions outside the raster produce NaN values
+pub fn get_values_for_positions (ds: &Dataset, band_index: usize, positions: &[(f64,f64)], sampling: Sampling) -> Result<Vec<f64>> {
+    let band = ds.rasterband(band_index)?;
+    let (x_size, y_size) = band.size();
+    let no_data = band.no_data_value();
+    let info = RasterInfo::new( x_size, y_size, ds.geo_transform()?)?;
+
+    let mut cell = [0.0f64;1];
+    let mut window = [0.0f64;4];
+    let mut result: Vec<f64> = Vec::with_capacity( positions.len());
+
+    for (x,y) in positions {
+        let (px,py) = info.geo_to_pixel( *x, *y);
+
+        if !info.contains_pixel( px, py) {
+            result.push( f64::NAN);
+            continue;
+        }
+
+        if sampling == Sampling::Bilinear {
+            // upper left of the 2x2 cell centers around (px,py)
+            let x0 = (px - 0.5).floor();
+            let y0 = (py - 0.5).floor();
+
+            if x0 >= 0.0 && y0 >= 0.0 && x0 + 1.0 < x_size as f64 && y0 + 1.0 < y_size as f64 {
+                band.read_into_slice( (x0 as isize, y0 as isize), (2,2), (2,2), &mut window, None)?;
+
+                if !window.iter().any( |v| no_data.map_or( false, |nd| *v == nd)) {
+                    let fx = px - 0.5 - x0;
+                    let fy = py - 0.5 - y0;
+                    let top = window[0] + fx * (window[1] - window[0]);
+                    let bottom = window[2] + fx * (window[3] - window[2]);
+                    result.push( top + fy * (bottom - top));
+                    continue;
+                }
+            }
+        }
+
+        band.read_into_slice( (px as isize, py as isize), (1,1), (1,1), &mut cell, None)?;
+        result.push( cell[0]);
+    }
+
+    Ok(result)
+}
+
 
 pub fn get_vec_f64<T> (ds: &Dataset, band_index: usize)->Result<Vec<f64>> 
     where T: GdalValueType + Into<f64>
@@ -460,6 +805,64 @@ pub fn get_vec_f64<T> (ds: &Dataset, band_index: usize)->Result<Vec<f64>>
     Ok(values)
 }
 
+/// get the index of the coarsest overview of band `band_index` that still has at least `target_w` x `target_h` pixels,
+/// or `None` if there is no such overview (in which case we have to use the full resolution band)
+pub fn select_overview (ds: &Dataset, band_index: usize, target_w: usize, target_h: usize)->Result<Option<usize>> {
+    let band = ds.rasterband(band_index)?;
+    let mut selected: Option<(usize,usize)> = None; // (overview index, number of pixels)
+
+    for i in 0..band.overview_count()?.max(0) as usize {
+        let (w,h) = band.overview(i as isize)?.size();
+        if w >= target_w && h >= target_h && selected.map_or( true, |(_,n)| w*h < n) {
+            selected = Some( (i, w*h))
+        }
+    }
+    Ok( selected.map( |(i,_)| i) )
+}
+
+/// read band `band_index` with a size of `target_w` x `target_h` pixels, e.g. for previews of large rasters. This reads
+/// from the overview that is closest to but not coarser than the requested size (see [`select_overview`]), falling back
+/// to GDAL's on-the-fly decimation of the full resolution band if there is no such overview
+pub fn read_band_at_resolution (ds: &Dataset, band_index: usize, target_w: usize, target_h: usize)->Result<Buffer<f64>> {
+    if target_w == 0 || target_h == 0 {
+        return Err( misc_error( format!("invalid target size {}x{}", target_w, target_h)))
+    }
+
+    let band = ds.rasterband(band_index)?;
+    let src = match select_overview( ds, band_index, target_w, target_h)? {
+        Some(i) => band.overview(i as isize)?,
+        None => band
+    };
+    Ok( src.read_as::<f64>( (0,0), src.size(), (target_w,target_h), None)? )
+}
+
+/// iterate over band `band_index` in windows of `block_w` x `block_h` pixels (smaller at the right and bottom edges),
+/// calling `f` with the (x,y) pixel origin and the row-major values of each block. This only keeps one block in
+/// memory at a time and hence can be used to compute aggregates over rasters that are too large for [`get_vec_f64`].
+/// Note that values are not scaled/offset
+pub fn for_each_block<T,F> (ds: &Dataset, band_index: usize, block_w: usize, block_h: usize, mut f: F)->Result<()>
+    where T: GdalValueType, F: FnMut(usize,usize,&[T])
+{
+    if block_w == 0 || block_h == 0 {
+        return Err( misc_error( format!("invalid block size {}x{}", block_w, block_h)))
+    }
+
+    let band = ds.rasterband(band_index)?;
+    let (x_size, y_size) = band.size();
+    let mut block: Vec<T> = vec![ 0.into(); block_w * block_h];
+
+    for y in (0..y_size).step_by(block_h) {
+        let h = block_h.min( y_size - y);
+        for x in (0..x_size).step_by(block_w) {
+            let w = block_w.min( x_size - x);
+            let data = &mut block[..w*h];
+            band.read_into_slice( (x as isize, y as isize), (w,h), (w,h), data, None)?;
+            f( x, y, data);
+        }
+    }
+    Ok(())
+}
+
 
 pub fn get_linear_range<T> (ds: &Dataset, band_index: usize)->Result<LinearRange<f64>>
     where T: GdalValueType + Into<f64> + Sub<Output=T>
@@ -483,6 +886,95 @@ pub fn get_linear_range<T> (ds: &Dataset, band_index: usize)->Result<LinearRange
     Ok( LinearRange::new( first, inc, n) )
 }
 
+/// basic statistics of raster band values (std_dev is the population standard deviation)
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub struct BandStats {
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub std_dev: f64
+}
+
+/// get statistics for band values, excluding nodata. This uses cached GDAL statistics if there are any (which
+/// might be approximate if `approx_ok` is set) and otherwise computes them row by row, i.e. without reading the 
+/// whole band into memory
+pub fn band_statistics (ds: &Dataset, band_index: usize, approx_ok: bool) -> Result<BandStats> {
+    let band = ds.rasterband(band_index)?;
+    if let Ok(Some(stats)) = band.get_statistics( false, approx_ok) {
+        return Ok( BandStats { min: stats.min, max: stats.max, mean: stats.mean, std_dev: stats.std_dev } )
+    }
+
+    let (x_size, y_size) = band.size();
+    let no_data = band.no_data_value();
+    let mut scan_line: Vec<f64> = vec![0.0; x_size];
+
+    // Welford's online algorithm
+    let (mut n, mut mean, mut m2) = (0usize, 0.0f64, 0.0f64);
+    let (mut min, mut max) = (f64::MAX, f64::MIN);
+
+    for y in 0..y_size {
+        band.read_into_slice( (0, y as isize), (x_size,1), (x_size,1), &mut scan_line, None)?;
+        for &v in &scan_line {
+            if v.is_nan() || no_data.map_or( false, |nd| v == nd) { continue }
+
+            n += 1;
+            let delta = v - mean;
+            mean += delta / n as f64;
+            m2 += delta * (v - mean);
+            if v < min { min = v }
+            if v > max { max = v }
+        }
+    }
+
+    if n == 0 {
+        Err( misc_error("no valid data in band".to_string()))
+    } else {
+        Ok( BandStats { min, max, mean, std_dev: (m2 / n as f64).sqrt() } )
+    }
+}
+
+/// get the tight pixel bounding box of all valid (non-nodata) pixels in `ref_band`. West/east are the min/max
+/// column, north/south the min/max row (all inclusive). This scans all pixels and hence does not make any
+/// assumptions about the shape of the data area, i.e. it works for concave areas and areas with holes
+pub fn get_data_bounds_mask (ds: &Dataset, ref_band: usize) -> Result<BoundingBox<usize>> {
+    let band = ds.rasterband(ref_band)?;
+    let (x_size, y_size) = band.size();
+    let no_data = band.no_data_value();
+    let mut scan_line: Vec<f64> = vec![0.0; x_size];
+
+    let (mut min_x, mut max_x, mut min_y, mut max_y) = (usize::MAX, 0usize, usize::MAX, 0usize);
+
+    for y in 0..y_size {
+        band.read_into_slice( (0, y as isize), (x_size,1), (x_size,1), &mut scan_line, None)?;
+        let is_valid = |v: &f64| !v.is_nan() && no_data.map_or( true, |nd| *v != nd);
+
+        if let Some(x0) = scan_line.iter().position( is_valid) {
+            let x1 = scan_line.iter().rposition( is_valid).unwrap(); // there is at least one
+            if x0 < min_x { min_x = x0 }
+            if x1 > max_x { max_x = x1 }
+            if y < min_y { min_y = y }
+            max_y = y;
+        }
+    }
+
+    if min_y == usize::MAX {
+        Err( misc_error("no valid data in reference band".to_string()))
+    } else {
+        Ok( BoundingBox::new( min_x, max_y, max_x, min_y) )
+    }
+}
+
+/// create a new dataset with given size and band type. The driver is determined by the filename extension
+pub fn create_dataset<T> (path: impl AsRef<Path>, x_size: usize, y_size: usize, n_bands: usize)->Result<Dataset> 
+    where T: GdalType
+{
+    let path = path.as_ref();
+    let filename = path.to_str().ok_or(OdinGdalError::InvalidFileName(path.display().to_string()))?;
+    let driver = get_driver_from_filename( filename).ok_or( misc_error( format!("no driver for {}", filename)))?;
+
+    Ok( driver.create_with_band_type::<T,_>( path, x_size, y_size, n_bands)? )
+}
+
 /* #endregion generic Dataset/Rasterband access */
 
 /* #region misc high level functions *************************************************************************************************/
@@ -529,6 +1021,245 @@ pub fn create_res_image_from_vrt (bbox: &BoundingBox<f64>, tgt_epsg: u32, res_x:
     Ok(())
 }
 
+/// warp `src` to the given EPSG and write it as a Cloud-Optimized GeoTIFF with the given (square) tile size and overview
+/// levels (e.g. `&[2,4,8]`). We first warp into a tiled intermediate GeoTIFF, build the requested overviews on it and then
+/// use the GDAL COG driver to copy it into the COG layout (tiles and overviews ordered for HTTP range requests)
+pub fn write_cog (src: &Dataset, tgt_epsg: u32, out_path: impl AsRef<Path>, block_size: u32, overview_levels: &[u32]) -> Result<()> {
+    let out_path = out_path.as_ref();
+    let tmp_path = out_path.with_extension("tmp.tif");
+    let tgt_srs = SpatialRef::from_epsg( tgt_epsg)?;
+
+    // the temp file has to be removed no matter where we fail
+    let res = (|| -> Result<()> {
+        let mut tile_opts = CslStringList::new();
+        tile_opts.add_string( "TILED=YES")?;
+        tile_opts.add_string( &format!("BLOCKXSIZE={}", block_size))?;
+        tile_opts.add_string( &format!("BLOCKYSIZE={}", block_size))?;
+
+        let mut warp = warp::SimpleWarpBuilder::new( src, &tmp_path)?;
+        warp.set_tgt_srs( &tgt_srs);
+        warp.set_tgt_format( "GTiff")?;
+        warp.set_create_options( &tile_opts);
+        let mut tmp_ds = warp.exec()?;
+
+        if !overview_levels.is_empty() {
+            let levels: Vec<i32> = overview_levels.iter().map( |l| *l as i32).collect();
+            tmp_ds.build_overviews( "AVERAGE", &levels, &[])?;
+        }
+
+        let mut cog_opts = CslStringList::new();
+        cog_opts.add_string( &format!("BLOCKSIZE={}", block_size))?;
+        cog_opts.add_string( "COMPRESS=DEFLATE")?;
+        cog_opts.add_string( if overview_levels.is_empty() { "OVERVIEWS=NONE" } else { "OVERVIEWS=FORCE_USE_EXISTING" })?;
+
+        let cog_driver = DriverManager::get_driver_by_name( "COG")?;
+        tmp_ds.create_copy( &cog_driver, out_path, &cog_opts)?;
+        Ok(()) // tmp_ds is closed here
+    })();
+
+    let rm_res = if tmp_path.is_file() { std::fs::remove_file( &tmp_path) } else { Ok(()) };
+    res?;
+    rm_res?;
+
+    Ok(())
+}
+
+/// build a VRT mosaic at `out_vrt` from the given input rasters (the programmatic equivalent of `gdalbuildvrt -r <resample>`).
+/// If `tgt_epsg` is set each input is wrapped into a warped VRT for this SRS, i.e. inputs with differing SRS are re-projected
+/// on read. These warped VRTs are stored next to `out_vrt` (as `<stem>_<index>.warped.vrt`) since the mosaic refers to them
+pub fn build_vrt (inputs: &[PathBuf], out_vrt: &Path, resample: ResampleAlg, tgt_epsg: Option<u32>) -> Result<()> {
+    if inputs.is_empty() {
+        return Err( misc_error( "no input rasters for VRT".to_string()))
+    }
+
+    let mut datasets: Vec<Dataset> = Vec::with_capacity( inputs.len());
+    for (i,path) in inputs.iter().enumerate() {
+        let ds = Dataset::open( path)?;
+        if let Some(epsg) = tgt_epsg {
+            let warped_path = warped_vrt_path( out_vrt, i);
+            datasets.push( create_warped_vrt( &ds, epsg, resample, &warped_path)?);
+        } else {
+            datasets.push( ds);
+        }
+    }
+
+    let opts = gdal::programs::raster::BuildVRTOptions::new( ["-r", resample_name( resample)])?;
+    let vrt = gdal::programs::raster::build_vrt( Some(out_vrt), &datasets, Some(opts))?;
+    drop(vrt); // this is what writes the VRT file
+
+    Ok(())
+}
+
+fn warped_vrt_path (out_vrt: &Path, idx: usize) -> PathBuf {
+    let stem = out_vrt.file_stem().and_then( |s| s.to_str()).unwrap_or("mosaic");
+    out_vrt.with_file_name( format!("{}_{}.warped.vrt", stem, idx))
+}
+
+/// gdalbuildvrt resampling names
+fn resample_name (resample: ResampleAlg) -> &'static str {
+    match resample {
+        ResampleAlg::NearestNeighbour => "nearest",
+        ResampleAlg::Bilinear => "bilinear",
+        ResampleAlg::Cubic => "cubic",
+        ResampleAlg::CubicSpline => "cubicspline",
+        ResampleAlg::Lanczos => "lanczos",
+        ResampleAlg::Average => "average",
+        ResampleAlg::Mode => "mode",
+        ResampleAlg::Gauss => "gauss",
+    }
+}
+
+/// map RasterIO resampling onto the warp kernel. There is no gaussian warp kernel
+fn warp_resample_alg (resample: ResampleAlg) -> Result<gdal_sys::GDALResampleAlg::Type> {
+    use gdal_sys::GDALResampleAlg::*;
+    match resample {
+        ResampleAlg::NearestNeighbour => Ok(GRA_NearestNeighbour),
+        ResampleAlg::Bilinear => Ok(GRA_Bilinear),
+        ResampleAlg::Cubic => Ok(GRA_Cubic),
+        ResampleAlg::CubicSpline => Ok(GRA_CubicSpline),
+        ResampleAlg::Lanczos => Ok(GRA_Lanczos),
+        ResampleAlg::Average => Ok(GRA_Average),
+        ResampleAlg::Mode => Ok(GRA_Mode),
+        ResampleAlg::Gauss => Err( misc_error( "gauss resampling not supported for warped VRTs".to_string()))
+    }
+}
+
+/// create a warped VRT for `src` that re-projects into `tgt_epsg` on read, and save it as `path`
+fn create_warped_vrt (src: &Dataset, tgt_epsg: u32, resample: ResampleAlg, path: &Path) -> Result<Dataset> {
+    let tgt_wkt = CString::new( SpatialRef::from_epsg( tgt_epsg)?.to_wkt()?)?;
+    let alg = warp_resample_alg( resample)?;
+
+    let warped = unsafe {
+        errors::reset_last_gdal_error();
+        let c_ds = gdal_sys::GDALAutoCreateWarpedVRT(
+            src.c_dataset(),
+            null(), // use src SRS
+            tgt_wkt.as_ptr(),
+            alg,
+            0.125, // max error in pixels (same as gdalwarp default)
+            null()
+        );
+        if c_ds.is_null() { return Err( last_gdal_error()) }
+        Dataset::from_c_dataset( c_ds)
+    };
+
+    let vrt_driver = DriverManager::get_driver_by_name( "VRT")?;
+    Ok( warped.create_copy( &vrt_driver, path, &CslStringList::new())? )
+}
+
+/// check if `value` can be stored without loss in a band of the given data type
+pub fn is_representable (data_type: GdalDataType, value: f64)->bool {
+    if data_type.is_floating() {
+        !value.is_finite() || data_type.bits() == 64 || value.abs() <= f32::MAX as f64
+    } else if data_type.is_integer() && value.fract() == 0.0 {
+        let bits = data_type.bits() as i32;
+        let (min,max) = if data_type.is_signed() { (-(2f64.powi(bits-1)), 2f64.powi(bits-1) - 1.0) } else { (0.0, 2f64.powi(bits) - 1.0) };
+        value >= min && value <= max
+    } else {
+        false
+    }
+}
+
+/// set the nodata value of all bands of the dataset. Returns an error without modifying any band if `value`
+/// is not representable in the data type of one of them
+pub fn set_no_data_all (ds: &mut Dataset, value: f64)->Result<()> {
+    let n_bands = ds.raster_count();
+    for i in 1..=n_bands {
+        let data_type = ds.rasterband(i)?.band_type();
+        if !is_representable( data_type, value) {
+            return Err( misc_error( format!("nodata value {} not representable in band {} of type {:?}", value, i, data_type)))
+        }
+    }
+
+    for i in 1..=n_bands {
+        ds.rasterband(i)?.set_no_data_value( Some(value))?;
+    }
+    Ok(())
+}
+
+/// get the nodata values of all bands of the dataset (in band order)
+pub fn no_data_values (ds: &Dataset)->Vec<Option<f64>> {
+    (1..=ds.raster_count()).map( |i| ds.rasterband(i).ok().and_then( |band| band.no_data_value())).collect()
+}
+
+/// interpolate the nodata pixels of band `band_index` (as determined by its nodata value or mask) from valid pixels
+/// within `max_dist` (in pixels). `alg` is the GDAL interpolation method ("INV_DIST" or "NEAREST")
+pub fn fill_nodata (ds: &mut Dataset, band_index: usize, max_dist: f64, smoothing_passes: u32, alg: &str) -> Result<()> {
+    fill_nodata_with( ds, band_index, None, max_dist, smoothing_passes, alg)
+}
+
+/// interpolate pixels of band `band_index` that are marked as zero in band `mask_band` of the same dataset.
+/// This follows GDALFillNodata semantics: pixels with a non-zero mask value are not modified and are used as
+/// interpolation sources, i.e. nodata pixels that should be left untouched (such as off-earth pixels in GOES-R
+/// products) should not be within `max_dist` (in pixels) of fillable pixels.
+/// `alg` is the GDAL interpolation method ("INV_DIST" or "NEAREST")
+pub fn fill_nodata_masked (ds: &mut Dataset, band_index: usize, mask_band: usize, max_dist: f64, smoothing_passes: u32, alg: &str) -> Result<()> {
+    fill_nodata_with( ds, band_index, Some(mask_band), max_dist, smoothing_passes, alg)
+}
+
+// a null mask band makes GDALFillNodata use the default mask of the band (derived from its nodata value)
+fn fill_nodata_with (ds: &mut Dataset, band_index: usize, mask_band: Option<usize>, max_dist: f64, smoothing_passes: u32, alg: &str) -> Result<()> {
+    let mut opts = CslStringList::new();
+    opts.add_string( &format!("INTERPOLATION={}", alg))?;
+
+    unsafe {
+        let c_ds = ds.c_dataset();
+        let c_band = ok_mut_non_null( gdal_sys::GDALGetRasterBand( c_ds, band_index as c_int), || format!("no band {}", band_index))?;
+        let c_mask = match mask_band {
+            Some(mask_band) => ok_mut_non_null( gdal_sys::GDALGetRasterBand( c_ds, mask_band as c_int), || format!("no mask band {}", mask_band))?,
+            None => null_mut()
+        };
+
+        let res = gdal_sys::GDALFillNodata(
+            c_band,
+            c_mask,
+            max_dist,
+            0, // deprecated option
+            smoothing_passes as c_int,
+            opts.as_ptr(),
+            None, null_mut() // progress
+        );
+        ok_ce_none( res)
+    }
+}
+
+/// burn the first layer of the vector dataset at `vector_path` (e.g. GeoJSON polygons) into a new single band raster.
+/// Pixels covered by features are set to the value of the given numeric attribute, or to 1 if there is none.
+/// Pixels not covered by any feature are 0
+pub fn rasterize_layer (vector_path: impl AsRef<Path>, attribute: Option<&str>, width: usize, height: usize,
+                        geo_transform: GeoTransform, tgt_epsg: u32, out_path: impl AsRef<Path>) -> Result<()> {
+    let vector_ds = Dataset::open( vector_path.as_ref())?;
+    if vector_ds.layer_count() == 0 {
+        return Err( misc_error( format!("no vector layer in {}", vector_path.as_ref().display())))
+    }
+
+    let mut tgt_ds = create_dataset::<f32>( out_path, width, height, 1)?;
+    tgt_ds.set_geo_transform( &geo_transform)?;
+    tgt_ds.set_spatial_ref( &SpatialRef::from_epsg( tgt_epsg)?)?;
+
+    let mut opts = CslStringList::new();
+    if let Some(attr) = attribute {
+        opts.add_string( &format!("ATTRIBUTE={}", attr))?;
+    }
+
+    let mut band_list: [c_int;1] = [1];
+    let mut burn_value: [f64;1] = [1.0]; // ignored if we use an attribute
+
+    unsafe {
+        let mut layers = [ gdal_sys::GDALDatasetGetLayer( vector_ds.c_dataset(), 0) ];
+
+        let res = gdal_sys::GDALRasterizeLayers(
+            tgt_ds.c_dataset(),
+            1, band_list.as_mut_ptr(),
+            1, layers.as_mut_ptr(),
+            None, null_mut(), // transformer (layer SRS is reprojected if it differs from the target)
+            if attribute.is_some() { null_mut() } else { burn_value.as_mut_ptr() },
+            opts.as_ptr(),
+            None, null_mut()  // progress
+        );
+        ok_ce_none( res)
+    }
+}
 
 /// syntactic sugar for creating CslStringLists. Note this panics if an invalid string (that cannot be translated into
 /// a C string) is provided.
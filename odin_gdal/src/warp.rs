@@ -19,10 +19,11 @@ use std::error::Error;
 use gdal::{DriverManager,Dataset,DatasetOptions, GeoTransform};
 use gdal::cpl::CslStringList;
 use gdal::spatial_ref::SpatialRef;
-use gdal_sys::{GDALDatasetH, GDALProgressFunc, GDALWarpOptions, CPLErr::CE_None, CPLErr};
+use gdal::vector::Geometry;
+use gdal_sys::{GDALDatasetH, GDALProgressFunc, GDALWarpOptions, OGRwkbGeometryType, CPLErr::CE_None, CPLErr};
 use libc::{c_void,c_char,c_int, c_double};
 use odin_common::geo::BoundingBox;
-use crate::{ok_non_null, ok_mut_non_null, ok_not_zero, ok_ce_none};
+use crate::{ok_non_null, ok_mut_non_null, ok_ce_none, RasterInfo};
 use crate::errors::{Result,last_gdal_error, misc_error, OdinGdalError, reset_last_gdal_error};
 
 pub struct SimpleWarpBuilder <'a> {
@@ -47,6 +48,8 @@ pub struct SimpleWarpBuilder <'a> {
 
     axis_order: c_int,
     max_error: c_double,
+
+    cutline: Option<(String,c_double)>, // WKT polygon in source SRS coordinates, blend distance in pixels
 }
 
 impl <'a> SimpleWarpBuilder<'a> {
@@ -69,6 +72,7 @@ impl <'a> SimpleWarpBuilder<'a> {
             src_srs: None,
             axis_order: 0,
             max_error: 0.0,
+            cutline: None,
         })
     }
 
@@ -130,6 +134,13 @@ impl <'a> SimpleWarpBuilder<'a> {
         self
     }
 
+    /// mask the output with a (multi-)polygon given as WKT in source SRS coordinates. Target pixels outside the
+    /// polygon are set to nodata. `blend_dist` is the distance in pixels over which we blend along the cutline
+    pub fn set_cutline (&mut self, wkt: &str, blend_dist: f64) -> &mut SimpleWarpBuilder<'a> {
+        self.cutline = Some( (wkt.to_string(), blend_dist) );
+        self
+    }
+
     // version without C shim functions
 
     pub fn exec(&self) -> Result<Dataset> {
@@ -262,6 +273,7 @@ impl <'a> SimpleWarpBuilder<'a> {
         }
     }
 
+    // note that tgt_ds is owned by the caller and gets closed when it is dropped - do not GDALClose it here
     fn chunk_and_warp (&self, tgt_ds: &Dataset) -> Result<()> {
         unsafe {
             reset_last_gdal_error();
@@ -271,7 +283,6 @@ impl <'a> SimpleWarpBuilder<'a> {
 
             let n_bands = self.src_ds.raster_count() as usize;
             if n_bands == 0 {
-                gdal_sys::GDALClose(c_tgt_ds);
                 return Err(OdinGdalError::MiscError("no raster bands in input".to_string()))
             }
 
@@ -296,6 +307,35 @@ impl <'a> SimpleWarpBuilder<'a> {
             warp_options.pfnProgress = Some(gdal_sys::GDALDummyProgress);
             //warp_options.pProgressArg = null_mut();
 
+            if let Some((ref wkt, blend_dist)) = self.cutline {
+                // GDAL expects the CUTLINE geometry in source pixel/line coordinates
+                let c_cutline = match self.get_pixel_cutline_wkt( wkt).and_then( |s| Ok(CString::new(s)?)) {
+                    Ok(c_cutline) => c_cutline,
+                    Err(e) => {
+                        gdal_sys::GDALDestroyWarpOptions(c_warp_options);
+                        return Err(e)
+                    }
+                };
+                let c_blend_dist = CString::new( blend_dist.to_string())?;
+
+                let mut c_opts = warp_options.papszWarpOptions;
+                c_opts = gdal_sys::CSLSetNameValue( c_opts, c"CUTLINE".as_ptr(), c_cutline.as_ptr());
+                c_opts = gdal_sys::CSLSetNameValue( c_opts, c"CUTLINE_BLEND_DIST".as_ptr(), c_blend_dist.as_ptr());
+                c_opts = gdal_sys::CSLSetNameValue( c_opts, c"INIT_DEST".as_ptr(), c"NO_DATA".as_ptr());
+                warp_options.papszWarpOptions = c_opts;
+
+                // pixels outside the cutline are initialized with the target nodata values
+                let c_nodata_real = gdal_sys::CPLMalloc(std::mem::size_of::<c_double>() * n_bands) as *mut c_double;
+                let c_nodata_imag = gdal_sys::CPLMalloc(std::mem::size_of::<c_double>() * n_bands) as *mut c_double;
+                for i in 0..n_bands as isize {
+                    let c_tgt_band = gdal_sys::GDALGetRasterBand(c_tgt_ds, (i+1) as c_int);
+                    *(c_nodata_real.offset(i)) = gdal_sys::GDALGetRasterNoDataValue(c_tgt_band, null_mut());
+                    *(c_nodata_imag.offset(i)) = 0.0;
+                }
+                warp_options.padfDstNoDataReal = c_nodata_real;
+                warp_options.padfDstNoDataImag = c_nodata_imag;
+            }
+
             //--- proj transformers
             let c_gen_transformer_arg= gdal_sys::GDALCreateGenImgProjTransformer(
                 self.src_ds.c_dataset(),
@@ -305,7 +345,7 @@ impl <'a> SimpleWarpBuilder<'a> {
                 0, 0.0, 0
             );
             if c_gen_transformer_arg == null_mut() {
-                gdal_sys::GDALClose(c_tgt_ds);
+                gdal_sys::GDALDestroyWarpOptions(c_warp_options);
                 return Err(last_gdal_error())
             }
 
@@ -320,7 +360,7 @@ impl <'a> SimpleWarpBuilder<'a> {
                     self.max_error);
                 if c_approx_transformer_arg == null_mut() {
                     gdal_sys::GDALDestroyGenImgProjTransformer(c_gen_transformer_arg);
-                    gdal_sys::GDALClose(c_tgt_ds);
+                    gdal_sys::GDALDestroyWarpOptions(c_warp_options);
                     return Err(last_gdal_error())
                 }
 
@@ -357,5 +397,31 @@ impl <'a> SimpleWarpBuilder<'a> {
             }
         }
     }
+
+    /// translate a WKT (multi-)polygon in source SRS coordinates into source pixel/line coordinates
+    fn get_pixel_cutline_wkt (&self, wkt: &str) -> Result<String> {
+        let ri = RasterInfo::from_dataset( self.src_ds)?;
+
+        let geom = Geometry::from_wkt( wkt)?;
+        match geom.geometry_type() {
+            OGRwkbGeometryType::wkbPolygon => Ok( format!("POLYGON{}", pixel_polygon_rings( &geom, &ri))),
+            OGRwkbGeometryType::wkbMultiPolygon => {
+                let polys: Vec<String> = (0..geom.geometry_count()).map( |i| pixel_polygon_rings( &geom.get_geometry(i), &ri)).collect();
+                Ok( format!("MULTIPOLYGON({})", polys.join(",")))
+            }
+            _ => Err( misc_error( format!("cutline is not a polygon: {}", wkt)))
+        }
+    }
 }
 
+
+fn pixel_polygon_rings (poly: &Geometry, ri: &RasterInfo) -> String {
+    let rings: Vec<String> = (0..poly.geometry_count()).map( |i| {
+        let pts: Vec<String> = poly.get_geometry(i).get_point_vec().iter().map( |(x,y,_)| {
+            let (px,py) = ri.geo_to_pixel( *x, *y);
+            format!("{} {}", px, py)
+        }).collect();
+        format!("({})", pts.join(","))
+    }).collect();
+    format!("({})", rings.join(","))
+}
@@ -0,0 +1,84 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License. You may obtain a copy
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+use odin_gdal::{Buffer, Dataset, SpatialRef, create_dataset};
+use odin_gdal::band_expr::{BandExpr, eval_band_expr};
+
+const W: usize = 3;
+const H: usize = 2;
+const NO_DATA: f64 = -9999.0;
+
+/// create a 2 band dataset with the given (row-major) values. Band 1 uses NO_DATA as its nodata value
+fn create_bands (name: &str, b1: Vec<f32>, b2: Vec<f32>) -> Dataset {
+    let path = std::env::temp_dir().join(name);
+    let mut ds = create_dataset::<f32>( &path, W, H, 2).unwrap();
+    ds.set_geo_transform( &[500000.0, 30.0, 0.0, 4000000.0, 0.0, -30.0]).unwrap();
+    ds.set_spatial_ref( &SpatialRef::from_epsg(32610).unwrap()).unwrap();
+
+    let mut band = ds.rasterband(1).unwrap();
+    band.set_no_data_value( Some(NO_DATA)).unwrap();
+    band.write( (0,0), (W,H), &mut Buffer::new( (W,H), b1)).unwrap();
+    ds.rasterband(2).unwrap().write( (0,0), (W,H), &mut Buffer::new( (W,H), b2)).unwrap();
+    ds
+}
+
+fn read_result (name: &str) -> Vec<f32> {
+    let ds = Dataset::open( std::env::temp_dir().join(name)).unwrap();
+    let band = ds.rasterband(1).unwrap();
+    assert!( band.no_data_value().unwrap().is_nan());
+    band.read_as::<f32>( (0,0), (W,H), (W,H), None).unwrap().data().to_vec()
+}
+
+fn assert_values (result: &[f32], expected: &[f32]) {
+    println!("result: {:?}", result);
+    for (r,e) in result.iter().zip( expected.iter()) {
+        if e.is_nan() { assert!( r.is_nan()) } else { assert!( (r - e).abs() < 1e-6, "{} != {}", r, e) }
+    }
+}
+
+#[test]
+fn test_normalized_difference() {
+    let nir = vec![ 0.8, 0.6, 0.5,  NO_DATA as f32, 0.0, 0.3];
+    let red = vec![ 0.2, 0.2, 0.5,  0.1,            0.0, 0.1];
+    let ds = create_bands( "odin_gdal_expr_nd_src.tif", nir, red);
+
+    eval_band_expr( &ds, "(nir - red) / (nir + red)", &[("nir",1),("red",2)], std::env::temp_dir().join("odin_gdal_expr_nd.tif")).unwrap();
+
+    let expected = [ 0.6, 0.5, 0.0,  f32::NAN /* nodata */, f32::NAN /* 0/0 */, 0.5];
+    assert_values( &read_result( "odin_gdal_expr_nd.tif"), &expected);
+}
+
+#[test]
+fn test_clamped_ratio() {
+    let b1 = vec![ 1.0, 4.0, 9.0,  -2.0, 3.0, NO_DATA as f32];
+    let b2 = vec![ 2.0, 1.0, 3.0,   1.0, 3.0, 1.0];
+    let ds = create_bands( "odin_gdal_expr_ratio_src.tif", b1, b2);
+
+    eval_band_expr( &ds, "clamp( b1 / b2, 0, 2.5) * -1", &[("b1",1),("b2",2)], std::env::temp_dir().join("odin_gdal_expr_ratio.tif")).unwrap();
+
+    let expected = [ -0.5, -2.5, -2.5,  0.0, -1.0, f32::NAN];
+    assert_values( &read_result( "odin_gdal_expr_ratio.tif"), &expected);
+}
+
+#[test]
+fn test_invalid_expressions() {
+    let names = [("b1",1),("b2",2)];
+    assert!( BandExpr::parse( "b1 + b3", &names).is_err()); // unknown band
+    assert!( BandExpr::parse( "(b1 + b2", &names).is_err()); // missing paren
+    assert!( BandExpr::parse( "min(b1)", &names).is_err()); // wrong arity
+    assert!( BandExpr::parse( "b1 % b2", &names).is_err()); // unsupported operator
+
+    let e = BandExpr::parse( "-b1 * 2 + max(b2, 1)", &names).unwrap();
+    assert_eq!( e.eval( &[3.0, 0.5]), -5.0);
+}
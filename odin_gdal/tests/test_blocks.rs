@@ -0,0 +1,43 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License. You may obtain a copy
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+use odin_gdal::{Buffer, create_dataset, for_each_block, get_vec_f64};
+
+#[test]
+fn test_block_sum() {
+    let (w, h) = (37, 23); // not a multiple of the block size so that we get partial edge blocks
+    let values: Vec<f32> = (0..w*h).map( |i| (i % 101) as f32 * 0.5).collect();
+
+    let path = std::env::temp_dir().join("odin_gdal_blocks.tif");
+    let mut ds = create_dataset::<f32>( &path, w, h, 1).unwrap();
+    ds.set_geo_transform( &[0.0, 1.0, 0.0, h as f64, 0.0, -1.0]).unwrap();
+    ds.rasterband(1).unwrap().write( (0,0), (w,h), &mut Buffer::new( (w,h), values)).unwrap();
+
+    let full_sum: f64 = get_vec_f64::<f32>( &ds, 1).unwrap().iter().sum();
+
+    let mut block_sum = 0.0f64;
+    let mut n_blocks = 0;
+    let mut n_values = 0;
+    for_each_block::<f32,_>( &ds, 1, 10, 8, |x, y, data| {
+        assert!( x % 10 == 0 && y % 8 == 0);
+        n_blocks += 1;
+        n_values += data.len();
+        block_sum += data.iter().map( |v| *v as f64).sum::<f64>();
+    }).unwrap();
+
+    println!("{n_blocks} blocks, sum: {block_sum} (full read: {full_sum})");
+    assert_eq!( n_blocks, 4 * 3);
+    assert_eq!( n_values, w * h);
+    assert!( (block_sum - full_sum).abs() < 1e-6);
+}
@@ -0,0 +1,52 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License. You may obtain a copy
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+use odin_common::geo::BoundingBox;
+use odin_gdal::{Buffer, Dataset, create_dataset, get_data_bounds_mask};
+
+const N: usize = 10;
+const NODATA: u8 = 255;
+
+/// create a NxN single band raster with valid pixels where `is_valid(x,y)` holds
+fn create_masked_ds (name: &str, is_valid: impl Fn(usize,usize)->bool)->Dataset {
+    let path = std::env::temp_dir().join(name);
+    let mut ds = create_dataset::<u8>( &path, N, N, 1).unwrap();
+    ds.set_geo_transform( &[0.0, 1.0, 0.0, N as f64, 0.0, -1.0]).unwrap();
+
+    let data: Vec<u8> = (0..N*N).map( |i| if is_valid( i % N, i / N) { 1 } else { NODATA }).collect();
+    let mut band = ds.rasterband(1).unwrap();
+    band.set_no_data_value( Some(NODATA as f64)).unwrap();
+    band.write( (0,0), (N,N), &mut Buffer::new( (N,N), data)).unwrap();
+    ds
+}
+
+#[test]
+fn test_l_shaped_bounds() {
+    // vertical bar x=2..=3, y=1..=8 plus horizontal foot y=7..=8, x=2..=7
+    let ds = create_masked_ds( "odin_gdal_l_shape.tif", |x,y| {
+        ((2..=3).contains(&x) && (1..=8).contains(&y)) || ((2..=7).contains(&x) && (7..=8).contains(&y))
+    });
+    let bbox = get_data_bounds_mask( &ds, 1).unwrap();
+    assert_eq!( bbox, BoundingBox::new( 2, 8, 7, 1));
+}
+
+#[test]
+fn test_ring_shaped_bounds() {
+    // square ring x,y in 1..=8 with a hole in 3..=6
+    let ds = create_masked_ds( "odin_gdal_ring_shape.tif", |x,y| {
+        (1..=8).contains(&x) && (1..=8).contains(&y) && !((3..=6).contains(&x) && (3..=6).contains(&y))
+    });
+    let bbox = get_data_bounds_mask( &ds, 1).unwrap();
+    assert_eq!( bbox, BoundingBox::new( 1, 8, 8, 1));
+}
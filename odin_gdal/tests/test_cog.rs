@@ -0,0 +1,38 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License. You may obtain a copy
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+use odin_gdal::{Buffer, Dataset, Metadata, SpatialRef, create_dataset, write_cog};
+
+const N: usize = 512;
+
+#[test]
+fn test_write_cog() {
+    let tmp_dir = std::env::temp_dir();
+    let src_path = tmp_dir.join("odin_gdal_cog_src.tif");
+    let out_path = tmp_dir.join("odin_gdal_cog_out.tif");
+
+    let mut src = create_dataset::<u8>( &src_path, N, N, 1).unwrap();
+    src.set_geo_transform( &[-122.0, 0.001, 0.0, 37.0, 0.0, -0.001]).unwrap();
+    src.set_spatial_ref( &SpatialRef::from_epsg(4326).unwrap()).unwrap();
+    let data: Vec<u8> = (0..N*N).map( |i| (i % 251) as u8).collect();
+    src.rasterband(1).unwrap().write( (0,0), (N,N), &mut Buffer::new( (N,N), data)).unwrap();
+
+    write_cog( &src, 32610, &out_path, 256, &[2,4]).unwrap();
+
+    let cog = Dataset::open( &out_path).unwrap();
+    let band = cog.rasterband(1).unwrap();
+    assert_eq!( band.block_size(), (256,256)); // internally tiled
+    assert_eq!( band.overview_count().unwrap(), 2);
+    assert_eq!( cog.metadata_item( "LAYOUT", "IMAGE_STRUCTURE").as_deref(), Some("COG"));
+}
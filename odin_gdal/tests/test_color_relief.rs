@@ -0,0 +1,72 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License. You may obtain a copy
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+
+use odin_gdal::{Buffer, Dataset, SpatialRef, create_dataset};
+use odin_gdal::color_relief::{color_relief, ramp_color, check_color_ramp, TRANSPARENT};
+
+const N: usize = 11;
+const NODATA: f64 = -9999.0;
+
+// three stops: green at 0m, yellow at 500m, white at 1000m
+const RAMP: [(f64,[u8;4]);3] = [
+    (   0.0, [  0, 128,   0, 255]),
+    ( 500.0, [255, 255,   0, 255]),
+    (1000.0, [255, 255, 255, 255]),
+];
+
+fn pixel (bands: &Vec<Vec<u8>>, x: usize, y: usize) -> [u8;4] {
+    [ bands[0][y*N + x], bands[1][y*N + x], bands[2][y*N + x], bands[3][y*N + x] ]
+}
+
+#[test]
+fn test_color_relief_gradient() {
+    let tmp_dir = std::env::temp_dir();
+    let dem_path = tmp_dir.join("odin_gdal_relief_dem.tif");
+    let out_path = tmp_dir.join("odin_gdal_relief_out.tif");
+
+    // linear west-east gradient from 0 to 1000m in 100m steps, with one nodata pixel
+    let mut dem = create_dataset::<f32>( &dem_path, N, N, 1).unwrap();
+    dem.set_geo_transform( &[500000.0, 30.0, 0.0, 4000000.0, 0.0, -30.0]).unwrap();
+    dem.set_spatial_ref( &SpatialRef::from_epsg(32610).unwrap()).unwrap();
+    let mut elev: Vec<f32> = (0..N*N).map( |i| (100 * (i % N)) as f32).collect();
+    elev[N*5 + 5] = NODATA as f32;
+    let mut band = dem.rasterband(1).unwrap();
+    band.set_no_data_value( Some(NODATA)).unwrap();
+    band.write( (0,0), (N,N), &mut Buffer::new( (N,N), elev)).unwrap();
+
+    color_relief( &dem, 1, &RAMP, &out_path).unwrap();
+
+    let ds = Dataset::open( &out_path).unwrap();
+    assert_eq!( ds.raster_count(), 4);
+    let bands: Vec<Vec<u8>> = (1..=4).map( |b| {
+        ds.rasterband(b).unwrap().read_as::<u8>( (0,0), (N,N), (N,N), None).unwrap().data().to_vec()
+    }).collect();
+
+    assert_eq!( pixel( &bands, 0, 0), RAMP[0].1);
+    assert_eq!( pixel( &bands, 5, 0), RAMP[1].1);
+    assert_eq!( pixel( &bands, 10, 0), RAMP[2].1);
+    assert_eq!( pixel( &bands, 2, 3), [102, 179, 0, 255]); // 200m: 40% between green and yellow
+    assert_eq!( pixel( &bands, 8, 3), [255, 255, 153, 255]); // 800m: 60% between yellow and white
+    assert_eq!( pixel( &bands, 5, 5), TRANSPARENT); // nodata
+}
+
+#[test]
+fn test_ramp_validation() {
+    assert_eq!( ramp_color( &[], 42.0), TRANSPARENT); // no panic
+    assert!( check_color_ramp( &[]).is_err());
+    assert!( check_color_ramp( &[RAMP[1], RAMP[0]]).is_err());
+    assert!( check_color_ramp( &[(f64::NAN, [0,0,0,255])]).is_err());
+    check_color_ramp( &RAMP).unwrap();
+}
@@ -0,0 +1,69 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License. You may obtain a copy
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+use gdal::vector::LayerAccess;
+use odin_gdal::{Buffer, Dataset, SpatialRef, create_dataset};
+use odin_gdal::contour::ContourBuilder;
+
+const N: usize = 10;
+const RES: f64 = 30.0; // meters
+
+/// create a DEM that rises by 1m per pixel to the east (0..N-1)
+fn create_ramp_dem (name: &str) -> Dataset {
+    let dem_path = std::env::temp_dir().join(name);
+    let mut dem = create_dataset::<f32>( &dem_path, N, N, 1).unwrap();
+    dem.set_geo_transform( &[500000.0, RES, 0.0, 4000000.0, 0.0, -RES]).unwrap();
+    dem.set_spatial_ref( &SpatialRef::from_epsg(32610).unwrap()).unwrap();
+    let elev: Vec<f32> = (0..N*N).map( |i| (i % N) as f32).collect();
+    dem.rasterband(1).unwrap().write( (0,0), (N,N), &mut Buffer::new( (N,N), elev)).unwrap();
+    dem
+}
+
+#[test]
+fn test_fixed_levels() {
+    let dem = create_ramp_dem( "odin_gdal_contour_dem.tif");
+    let out_path = std::env::temp_dir().join("odin_gdal_contour_fixed.geojson");
+    if out_path.is_file() { std::fs::remove_file( &out_path).unwrap(); }
+
+    let levels = [1.5, 4.0, 7.5]; // non-uniform
+    ContourBuilder::new( &dem, &out_path).unwrap()
+        .set_band(1)
+        .set_fixed_levels( &levels)
+        .set_attr_name("elev").unwrap()
+        .exec().unwrap();
+
+    let ds = Dataset::open( &out_path).unwrap();
+    let mut layer = ds.layer(0).unwrap();
+    let mut contour_levels: Vec<f64> = layer.features().map( |f| f.field_as_double_by_name("elev").unwrap().unwrap()).collect();
+    contour_levels.sort_by( |a,b| a.total_cmp(b));
+    contour_levels.dedup();
+    println!("contour levels: {:?}", contour_levels);
+
+    assert_eq!( contour_levels, levels.to_vec());
+}
+
+#[test]
+fn test_interval_and_fixed_levels_exclusive() {
+    let dem = create_ramp_dem( "odin_gdal_contour_excl_dem.tif");
+    let out_path = std::env::temp_dir().join("odin_gdal_contour_excl.geojson");
+    if out_path.is_file() { std::fs::remove_file( &out_path).unwrap(); }
+
+    let res = ContourBuilder::new( &dem, &out_path).unwrap()
+        .set_band(1)
+        .set_interval(2)
+        .set_fixed_levels( &[1.5, 4.0])
+        .exec();
+    assert!( res.is_err());
+    assert!( !out_path.exists()); // rejected before the output was created
+}
@@ -0,0 +1,30 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License. You may obtain a copy
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+use odin_gdal::{get_driver_name_from_filename, get_driver_for_path_dynamic};
+
+#[test]
+fn test_dynamic_driver_lookup() {
+    assert!( get_driver_name_from_filename("/tmp/data.gpkg").is_none()); // not in our static map
+
+    let driver = get_driver_for_path_dynamic("/tmp/data.gpkg").unwrap();
+    assert_eq!( driver.short_name(), "GPKG");
+
+    // second lookup comes from the cache
+    let driver = get_driver_for_path_dynamic("other.GPKG").unwrap();
+    assert_eq!( driver.short_name(), "GPKG");
+
+    // static map entries still take precedence
+    assert_eq!( get_driver_for_path_dynamic("x.tif").unwrap().short_name(), "GTiff");
+}
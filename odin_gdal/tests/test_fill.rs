@@ -0,0 +1,93 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License. You may obtain a copy
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+
+use odin_gdal::{Buffer, create_dataset, fill_nodata, fill_nodata_masked, no_data_values, set_no_data_all};
+
+const N: usize = 64;
+const NODATA: f32 = -9999.0;
+const VALUE: f32 = 10.0;
+
+fn is_fillable (x: usize, y: usize) -> bool { (10..14).contains(&x) && (10..14).contains(&y) }
+fn is_off_earth (x: usize, y: usize) -> bool { (40..50).contains(&x) && (40..50).contains(&y) }
+
+#[test]
+fn test_fill_nodata_masked() {
+    let path = std::env::temp_dir().join("odin_gdal_fill.tif");
+    let mut ds = create_dataset::<f32>( &path, N, N, 2).unwrap();
+
+    let mut data = vec![VALUE; N*N];
+    let mut mask = vec![1.0f32; N*N];
+    for y in 0..N {
+        for x in 0..N {
+            if is_fillable(x,y) || is_off_earth(x,y) { data[y*N + x] = NODATA }
+            if is_fillable(x,y) { mask[y*N + x] = 0.0 } // only these are interpolated
+        }
+    }
+    ds.rasterband(1).unwrap().write( (0,0), (N,N), &mut Buffer::new( (N,N), data)).unwrap();
+    ds.rasterband(2).unwrap().write( (0,0), (N,N), &mut Buffer::new( (N,N), mask)).unwrap();
+
+    fill_nodata_masked( &mut ds, 1, 2, 8.0, 0, "INV_DIST").unwrap();
+
+    let buf = ds.rasterband(1).unwrap().read_as::<f32>( (0,0), (N,N), (N,N), None).unwrap();
+    let data = buf.data();
+    for y in 0..N {
+        for x in 0..N {
+            let v = data[y*N + x];
+            if is_off_earth(x,y) {
+                assert_eq!( v, NODATA, "off-earth pixel {x},{y} was changed");
+            } else {
+                assert!( (v - VALUE).abs() < 1e-4, "pixel {x},{y} not filled: {v}");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_set_no_data_all() {
+    let path = std::env::temp_dir().join("odin_gdal_nodata.tif");
+    let mut ds = create_dataset::<f32>( &path, N, N, 3).unwrap();
+    assert_eq!( no_data_values( &ds), vec![None, None, None]);
+
+    set_no_data_all( &mut ds, NODATA as f64).unwrap();
+    assert_eq!( no_data_values( &ds), vec![Some(NODATA as f64); 3]);
+
+    // fill_nodata should now pick up the nodata pixels of band 1 without an explicit mask band
+    let mut data = vec![VALUE; N*N];
+    for y in 0..N {
+        for x in 0..N {
+            if is_fillable(x,y) { data[y*N + x] = NODATA }
+        }
+    }
+    ds.rasterband(1).unwrap().write( (0,0), (N,N), &mut Buffer::new( (N,N), data)).unwrap();
+
+    fill_nodata( &mut ds, 1, 8.0, 0, "INV_DIST").unwrap();
+
+    let buf = ds.rasterband(1).unwrap().read_as::<f32>( (0,0), (N,N), (N,N), None).unwrap();
+    assert!( buf.data().iter().all( |v| (v - VALUE).abs() < 1e-4));
+}
+
+#[test]
+fn test_set_no_data_not_representable() {
+    let path = std::env::temp_dir().join("odin_gdal_nodata_u8.tif");
+    let mut ds = create_dataset::<u8>( &path, 8, 8, 2).unwrap();
+
+    assert!( set_no_data_all( &mut ds, -1.0).is_err());
+    assert!( set_no_data_all( &mut ds, 256.0).is_err());
+    assert!( set_no_data_all( &mut ds, 0.5).is_err());
+    assert_eq!( no_data_values( &ds), vec![None, None]); // nothing was changed
+
+    set_no_data_all( &mut ds, 255.0).unwrap();
+    assert_eq!( no_data_values( &ds), vec![Some(255.0), Some(255.0)]);
+}
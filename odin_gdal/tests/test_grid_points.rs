@@ -0,0 +1,46 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License. You may obtain a copy
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+
+use odin_gdal::{Buffer, Dataset, GridPoint, create_dataset, find_grid_points_in_slice, par_find_grid_points_in_slice};
+
+const X_SIZE: usize = 500;
+const Y_SIZE: usize = 301; // not divisible by the number of blocks
+
+fn find_fire_pixels (i1: usize, row: &[u16], grid_points: &mut Vec<GridPoint<u16>>) {
+    for i0 in 0..row.len() {
+        let v = row[i0];
+        if v >= 10 && v <= 35 { grid_points.push( GridPoint{i0,i1,value: v}) }
+    }
+}
+
+#[test]
+fn test_par_find_grid_points() {
+    let path = std::env::temp_dir().join("odin_gdal_grid_points.tif");
+    {
+        let mut ds = create_dataset::<u16>( &path, X_SIZE, Y_SIZE, 1).unwrap();
+        let data: Vec<u16> = (0..X_SIZE*Y_SIZE).map( |i| ((i * 7919) % 97) as u16).collect();
+        ds.rasterband(1).unwrap().write( (0,0), (X_SIZE,Y_SIZE), &mut Buffer::new( (X_SIZE,Y_SIZE), data)).unwrap();
+    } // close and flush before workers re-open it
+
+    let ds = Dataset::open( &path).unwrap();
+    let serial = find_grid_points_in_slice( &ds, 1, find_fire_pixels).unwrap();
+    let parallel = par_find_grid_points_in_slice( &ds, 1, find_fire_pixels).unwrap();
+
+    assert!( !serial.is_empty());
+    assert_eq!( serial.len(), parallel.len());
+    for (s,p) in serial.iter().zip( parallel.iter()) { // same points in the same (row) order
+        assert_eq!( (s.i0, s.i1, s.value), (p.i0, p.i1, p.value));
+    }
+}
@@ -0,0 +1,51 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License. You may obtain a copy
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+use odin_gdal::{Buffer, SpatialRef, create_dataset};
+use odin_gdal::hillshade::{hillshade, HILLSHADE_NODATA};
+
+const N: usize = 8;
+const RES: f64 = 30.0; // meters
+const SLOPE: f64 = 0.5; // dz/dx of the plane, rising to the east
+
+#[test]
+fn test_hillshade_tilted_plane() {
+    let tmp_dir = std::env::temp_dir();
+    let dem_path = tmp_dir.join("odin_gdal_hillshade_dem.tif");
+    let out_path = tmp_dir.join("odin_gdal_hillshade_out.tif");
+
+    let mut dem = create_dataset::<f32>( &dem_path, N, N, 1).unwrap();
+    dem.set_geo_transform( &[500000.0, RES, 0.0, 4000000.0, 0.0, -RES]).unwrap();
+    dem.set_spatial_ref( &SpatialRef::from_epsg(32610).unwrap()).unwrap();
+    let elev: Vec<f32> = (0..N*N).map( |i| (SLOPE * RES * (i % N) as f64) as f32).collect();
+    dem.rasterband(1).unwrap().write( (0,0), (N,N), &mut Buffer::new( (N,N), elev)).unwrap();
+
+    let (azimuth, altitude) = (315.0_f64, 45.0_f64);
+    let hs = hillshade( &dem, 1, &out_path, azimuth, altitude, 1.0).unwrap();
+    let buf = hs.rasterband(1).unwrap().read_as::<u8>( (0,0), (N,N), (N,N), None).unwrap();
+    let data = buf.data();
+
+    // analytic: normal (-s,0,1)/sqrt(1+s²) dotted with light (sin(az)cos(alt), cos(az)cos(alt), sin(alt))
+    let (az, alt) = (azimuth.to_radians(), altitude.to_radians());
+    let cos_i = (alt.sin() - SLOPE * az.sin() * alt.cos()) / (1.0 + SLOPE*SLOPE).sqrt();
+    let expected = (255.0 * cos_i).round() as u8;
+    println!("expected shade: {expected}");
+
+    for y in 1..N-1 {
+        for x in 1..N-1 {
+            assert_eq!( data[y*N + x], expected);
+        }
+    }
+    assert_eq!( data[0], HILLSHADE_NODATA); // edge
+}
@@ -0,0 +1,43 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License. You may obtain a copy
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+use odin_gdal::{Buffer, create_dataset, read_band_at_resolution, select_overview};
+
+const N: usize = 256;
+
+#[test]
+fn test_read_at_resolution() {
+    let path = std::env::temp_dir().join("odin_gdal_overviews.tif");
+    let mut ds = create_dataset::<f32>( &path, N, N, 1).unwrap();
+    ds.set_geo_transform( &[0.0, 1.0, 0.0, N as f64, 0.0, -1.0]).unwrap();
+    let data: Vec<f32> = (0..N*N).map( |i| (i % N) as f32).collect();
+    ds.rasterband(1).unwrap().write( (0,0), (N,N), &mut Buffer::new( (N,N), data)).unwrap();
+
+    // no overviews yet - full band decimation
+    assert_eq!( select_overview( &ds, 1, 100, 100).unwrap(), None);
+    let buf = read_band_at_resolution( &ds, 1, 100, 100).unwrap();
+    assert_eq!( buf.shape(), (100,100));
+
+    ds.build_overviews( "AVERAGE", &[2,4,8], &[]).unwrap(); // 128, 64 and 32 pixels
+
+    // coarsest overview that is not coarser than the request
+    assert_eq!( select_overview( &ds, 1, 100, 100).unwrap(), Some(0)); // 128
+    assert_eq!( select_overview( &ds, 1, 64, 40).unwrap(), Some(1));   // 64
+    assert_eq!( select_overview( &ds, 1, 16, 16).unwrap(), Some(2));   // 32
+    assert_eq!( select_overview( &ds, 1, 200, 200).unwrap(), None);    // needs full resolution
+
+    let buf = read_band_at_resolution( &ds, 1, 64, 40).unwrap();
+    assert_eq!( buf.shape(), (64,40));
+    println!("first row: {:?}", &buf.data()[..8]);
+}
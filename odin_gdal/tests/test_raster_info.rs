@@ -0,0 +1,56 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License. You may obtain a copy
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+use odin_gdal::RasterInfo;
+
+// 200x100 raster with 30m cells and upper left corner at (500000,4200000)
+fn info () -> RasterInfo {
+    RasterInfo::new( 200, 100, [500000.0, 30.0, 0.0, 4200000.0, 0.0, -30.0]).unwrap()
+}
+
+fn assert_close (a: (f64,f64), b: (f64,f64)) {
+    assert!( (a.0 - b.0).abs() < 1e-6 && (a.1 - b.1).abs() < 1e-6, "{a:?} != {b:?}");
+}
+
+#[test]
+fn test_pixel_geo_corners() {
+    let info = info();
+
+    assert_close( info.pixel_to_geo( 0.0, 0.0), (500000.0, 4200000.0)); // upper left
+    assert_close( info.pixel_to_geo( 200.0, 100.0), (506000.0, 4197000.0)); // lower right
+    assert_close( info.geo_to_pixel( 500000.0, 4200000.0), (0.0, 0.0));
+    assert_close( info.geo_to_pixel( 506000.0, 4197000.0), (200.0, 100.0));
+
+    assert_eq!( info.geo_to_cell( 500000.0, 4200000.0), (0, 0));
+    assert_eq!( info.geo_to_cell( 505999.0, 4197001.0), (199, 99));
+    assert_eq!( info.geo_to_cell( 499999.0, 4200001.0), (-1, -1)); // outside
+}
+
+#[test]
+fn test_pixel_geo_center() {
+    let info = info();
+
+    assert_close( info.pixel_to_geo( 100.0, 50.0), (503000.0, 4198500.0));
+    assert_close( info.geo_to_pixel( 503000.0, 4198500.0), (100.0, 50.0));
+    assert_eq!( info.geo_to_cell( 503015.0, 4198485.0), (100, 50)); // center of cell (100,50)
+
+    // round trip
+    let (x,y) = info.pixel_to_geo( 123.4, 56.7);
+    assert_close( info.geo_to_pixel( x, y), (123.4, 56.7));
+}
+
+#[test]
+fn test_non_invertible() {
+    assert!( RasterInfo::new( 10, 10, [0.0, 0.0, 0.0, 0.0, 0.0, 0.0]).is_err());
+}
@@ -0,0 +1,60 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License. You may obtain a copy
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+
+use odin_gdal::{Dataset, rasterize_layer};
+
+// a 0.5x0.5 deg square in the center of the (-123,37)..(-122,38) raster area
+const POLYGON: &str = r#"{
+  "type": "FeatureCollection",
+  "features": [{
+    "type": "Feature",
+    "properties": { "frp": 42.5 },
+    "geometry": { "type": "Polygon", "coordinates": [[[-122.75,37.25],[-122.25,37.25],[-122.25,37.75],[-122.75,37.75],[-122.75,37.25]]] }
+  }]
+}"#;
+
+const N: usize = 100;
+const GT: [f64;6] = [-123.0, 1.0/N as f64, 0.0, 38.0, 0.0, -1.0/N as f64];
+
+fn rasterize (name: &str, attribute: Option<&str>) -> Vec<f32> {
+    let tmp_dir = std::env::temp_dir();
+    let vector_path = tmp_dir.join( format!("odin_gdal_{name}.geojson"));
+    let out_path = tmp_dir.join( format!("odin_gdal_{name}.tif"));
+    std::fs::write( &vector_path, POLYGON).unwrap();
+
+    rasterize_layer( &vector_path, attribute, N, N, GT, 4326, &out_path).unwrap();
+
+    let ds = Dataset::open( &out_path).unwrap();
+    ds.rasterband(1).unwrap().read_as::<f32>( (0,0), (N,N), (N,N), None).unwrap().data().to_vec()
+}
+
+fn value_at (data: &Vec<f32>, x: usize, y: usize) -> f32 { data[y*N + x] }
+
+#[test]
+fn test_rasterize_constant() {
+    let data = rasterize( "rasterize_const", None);
+    assert_eq!( value_at( &data, 50, 50), 1.0); // interior
+    assert_eq!( value_at( &data, 30, 70), 1.0);
+    assert_eq!( value_at( &data, 10, 10), 0.0); // exterior
+    assert_eq!( value_at( &data, 90, 50), 0.0);
+    assert_eq!( data.iter().filter(|v| **v == 1.0).count(), 50*50);
+}
+
+#[test]
+fn test_rasterize_attribute() {
+    let data = rasterize( "rasterize_attr", Some("frp"));
+    assert_eq!( value_at( &data, 50, 50), 42.5);
+    assert_eq!( value_at( &data, 10, 90), 0.0);
+}
@@ -0,0 +1,61 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License. You may obtain a copy
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+
+use odin_gdal::{Buffer, Sampling, create_dataset, get_values_for_positions};
+
+const N: usize = 10;
+
+// a plane z = 100 + 3x - 5y over a grid with unit cells and origin (0,0) in the upper left
+fn plane (x: f64, y: f64) -> f64 { 100.0 + 3.0*x - 5.0*y }
+
+#[test]
+fn test_bilinear_sampling() {
+    let path = std::env::temp_dir().join("odin_gdal_sampling.tif");
+    let mut ds = create_dataset::<f64>( &path, N, N, 1).unwrap();
+    ds.set_geo_transform( &[0.0, 1.0, 0.0, 0.0, 0.0, -1.0]).unwrap();
+
+    // cell values are the plane values at the cell centers
+    let data: Vec<f64> = (0..N*N).map( |i| plane( (i % N) as f64 + 0.5, -((i / N) as f64 + 0.5))).collect();
+    ds.rasterband(1).unwrap().write( (0,0), (N,N), &mut Buffer::new( (N,N), data)).unwrap();
+
+    let pos = [(2.3, -4.7)];
+    let exact = plane( pos[0].0, pos[0].1);
+
+    let bilinear = get_values_for_positions( &ds, 1, &pos, Sampling::Bilinear).unwrap();
+    assert!( (bilinear[0] - exact).abs() < 1e-9, "bilinear {} != {}", bilinear[0], exact);
+
+    let nearest = get_values_for_positions( &ds, 1, &pos, Sampling::Nearest).unwrap();
+    assert_eq!( nearest[0], plane( 2.5, -4.5));
+    assert!( (nearest[0] - exact).abs() > 0.1);
+
+    let outside = get_values_for_positions( &ds, 1, &[(-1.0, 1.0)], Sampling::Bilinear).unwrap();
+    assert!( outside[0].is_nan());
+}
+
+#[test]
+fn test_bilinear_nodata_fallback() {
+    let path = std::env::temp_dir().join("odin_gdal_sampling_nodata.tif");
+    let mut ds = create_dataset::<f64>( &path, N, N, 1).unwrap();
+    ds.set_geo_transform( &[0.0, 1.0, 0.0, 0.0, 0.0, -1.0]).unwrap();
+
+    let mut data: Vec<f64> = (0..N*N).map( |i| plane( (i % N) as f64 + 0.5, -((i / N) as f64 + 0.5))).collect();
+    data[5*N + 1] = -9999.0; // pixel (2.3,4.7) -> 2x2 window of cells x: 1..=2, y: 4..=5
+    let mut band = ds.rasterband(1).unwrap();
+    band.set_no_data_value( Some(-9999.0)).unwrap();
+    band.write( (0,0), (N,N), &mut Buffer::new( (N,N), data)).unwrap();
+
+    let v = get_values_for_positions( &ds, 1, &[(2.3, -4.7)], Sampling::Bilinear).unwrap();
+    assert_eq!( v[0], plane( 2.5, -4.5)); // fallback to nearest
+}
@@ -0,0 +1,47 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License. You may obtain a copy
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+use odin_gdal::{Buffer, band_statistics, create_dataset};
+
+const NODATA: f32 = -9999.0;
+
+#[test]
+fn test_band_statistics() {
+    let (w, h) = (7, 5);
+    let values: Vec<f32> = (0..w*h).map( |i| if i % 6 == 0 { NODATA } else { (i as f32 * 0.37).sin() * 100.0 }).collect();
+
+    let path = std::env::temp_dir().join("odin_gdal_stats.tif");
+    let mut ds = create_dataset::<f32>( &path, w, h, 1).unwrap();
+    ds.set_geo_transform( &[0.0, 1.0, 0.0, h as f64, 0.0, -1.0]).unwrap();
+    let mut band = ds.rasterband(1).unwrap();
+    band.set_no_data_value( Some(NODATA as f64)).unwrap();
+    band.write( (0,0), (w,h), &mut Buffer::new( (w,h), values.clone())).unwrap();
+
+    // manual pass
+    let valid: Vec<f64> = values.iter().filter( |v| **v != NODATA).map( |v| *v as f64).collect();
+    let n = valid.len() as f64;
+    let mean = valid.iter().sum::<f64>() / n;
+    let std_dev = (valid.iter().map( |v| (v - mean).powi(2)).sum::<f64>() / n).sqrt();
+    let min = valid.iter().cloned().fold( f64::MAX, f64::min);
+    let max = valid.iter().cloned().fold( f64::MIN, f64::max);
+
+    let stats = band_statistics( &ds, 1, false).unwrap();
+    println!("{stats:?}");
+
+    let eps = 1e-4;
+    assert!( (stats.min - min).abs() < eps);
+    assert!( (stats.max - max).abs() < eps);
+    assert!( (stats.mean - mean).abs() < eps);
+    assert!( (stats.std_dev - std_dev).abs() < eps);
+}
@@ -0,0 +1,126 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License. You may obtain a copy
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+
+use std::sync::Arc;
+use odin_gdal::{SpatialRef, transform_bounds_2d, srs_epsg_4326, srs_utm_10_n, srs_utm_n};
+use odin_gdal::{TransformCache, TransformOptions, global_transform_cache, transform_latlon_to_utm_bounds};
+
+/// max growth of round trip bounds (in degrees). Each transform_bounds_2d step returns the enclosing rectangle of the
+/// curved (densified) edges, which for a ~1x1 deg bbox close to the UTM 10N central meridian is well below this
+const ROUND_TRIP_TOLERANCE: f64 = 0.02;
+
+/// max difference (in degrees) between transforms that only differ in axis mapping strategy of the geographic SRS
+const AXIS_ORDER_TOLERANCE: f64 = 1e-6;
+
+// a bbox in northern CA (lon/lat)
+const CA_BBOX: (f64,f64,f64,f64) = (-123.0, 38.0, -122.0, 39.0);
+
+#[test]
+fn test_round_trip_4326_utm() {
+    let geo = srs_epsg_4326();
+    let utm = srs_utm_10_n();
+    let (west,south,east,north) = CA_BBOX;
+
+    let (x_min,y_min,x_max,y_max) = transform_bounds_2d( &geo, &utm, west, south, east, north, None).unwrap();
+    println!("utm: {x_min:.1}, {y_min:.1}, {x_max:.1}, {y_max:.1}");
+    assert!( x_min > 300_000.0 && x_max < 700_000.0); // easting
+    assert!( y_min > 4_000_000.0 && y_max < 4_400_000.0); // northing
+
+    let (w,s,e,n) = transform_bounds_2d( &utm, &geo, x_min, y_min, x_max, y_max, None).unwrap();
+    println!("geo: {w:.6}, {s:.6}, {e:.6}, {n:.6}");
+
+    // round trip bounds have to enclose the original ones, and only grow by the edge curvature
+    assert!( w <= west && s <= south && e >= east && n >= north);
+    assert!( (w - west).abs() < ROUND_TRIP_TOLERANCE);
+    assert!( (s - south).abs() < ROUND_TRIP_TOLERANCE);
+    assert!( (e - east).abs() < ROUND_TRIP_TOLERANCE);
+    assert!( (n - north).abs() < ROUND_TRIP_TOLERANCE);
+}
+
+#[test]
+fn test_axis_order_symmetry() {
+    let geo = srs_epsg_4326(); // authority compliant (lat,lon) axis order
+    let geo_trad = SpatialRef::from_definition( "OGC:CRS84").unwrap(); // same datum but (lon,lat) axis order
+    let utm = srs_utm_10_n();
+    let (west,south,east,north) = CA_BBOX;
+
+    // forward: results have to be the same regardless of source axis mapping
+    let a = transform_bounds_2d( &geo, &utm, west, south, east, north, None).unwrap();
+    let b = transform_bounds_2d( &geo_trad, &utm, west, south, east, north, None).unwrap();
+    assert!( (a.0 - b.0).abs() < 1e-3 && (a.1 - b.1).abs() < 1e-3 && (a.2 - b.2).abs() < 1e-3 && (a.3 - b.3).abs() < 1e-3);
+
+    // inverse: results have to be the same regardless of target axis mapping, and in (lon,lat) order
+    let c = transform_bounds_2d( &utm, &geo, a.0, a.1, a.2, a.3, None).unwrap();
+    let d = transform_bounds_2d( &utm, &geo_trad, a.0, a.1, a.2, a.3, None).unwrap();
+    assert!( (c.0 - d.0).abs() < AXIS_ORDER_TOLERANCE);
+    assert!( (c.1 - d.1).abs() < AXIS_ORDER_TOLERANCE);
+    assert!( (c.2 - d.2).abs() < AXIS_ORDER_TOLERANCE);
+    assert!( (c.3 - d.3).abs() < AXIS_ORDER_TOLERANCE);
+    assert!( c.0 < -122.0 && c.1 > 37.0); // lon/lat order
+}
+
+#[test]
+fn test_transform_cache() {
+    let cache = TransformCache::new();
+    let opts = TransformOptions::default();
+
+    let t1 = cache.get( 4326, 32610, &opts).unwrap();
+    let t2 = cache.get( 4326, 32610, &opts).unwrap();
+    assert!( Arc::ptr_eq( &t1, &t2)); // same SRS pair and options -> same transform object
+    assert_eq!( cache.len(), 1);
+
+    let t3 = cache.get( 32610, 4326, &opts).unwrap(); // inverse is a different transform
+    assert!( !Arc::ptr_eq( &t1, &t3));
+    let t4 = cache.get( 4326, 32610, &TransformOptions { desired_accuracy: None, ballpark_allowed: true }).unwrap();
+    assert!( !Arc::ptr_eq( &t1, &t4));
+    assert_eq!( cache.len(), 3);
+
+    // repeated transforms through the shared object give the same results
+    let (lat,lon) = (38.5, -122.5); // epsg:4326 is lat,lon
+    let p1 = t1.transform_point_2d( lat, lon).unwrap();
+    for _ in 0..100 {
+        let p2 = t2.transform_point_2d( lat, lon).unwrap();
+        assert_eq!( p1, p2);
+    }
+    assert!( p1.0 > 300_000.0 && p1.0 < 700_000.0);
+
+    cache.clear();
+    assert_eq!( cache.len(), 0);
+}
+
+#[test]
+fn test_global_transform_cache() {
+    let (west,south,east,north) = CA_BBOX;
+    let a = transform_latlon_to_utm_bounds( west, south, east, north, false, Some(10), false, None, false).unwrap();
+    assert!( global_transform_cache().contains( 4326, 32610, &TransformOptions::default()));
+
+    let b = transform_latlon_to_utm_bounds( west, south, east, north, false, Some(10), false, None, false).unwrap();
+    assert_eq!( a, b);
+}
+
+#[test]
+fn test_bounds_transform_cache() {
+    let geo = srs_epsg_4326();
+    let utm = srs_utm_n( 11).unwrap(); // not used by other tests
+    let a = transform_bounds_2d( &geo, &utm, -119.0, 37.0, -118.0, 38.0, None).unwrap();
+    assert!( global_transform_cache().contains( 4326, 32611, &TransformOptions::default()));
+
+    // SRS that are not plain EPSG definitions (here with (lon,lat) axis order) still work, but are not cached
+    let geo_trad = SpatialRef::from_definition( "OGC:CRS84").unwrap();
+    let b = transform_bounds_2d( &geo_trad, &utm, -119.0, 37.0, -118.0, 38.0, None).unwrap();
+    assert!( (a.0 - b.0).abs() < 1e-3 && (a.3 - b.3).abs() < 1e-3);
+
+    assert_eq!( TransformCache::default().len(), 0);
+}
@@ -0,0 +1,41 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License. You may obtain a copy
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+use odin_gdal::{transform_latlon_to_utm_bounds, errors::OdinGdalError};
+
+#[test]
+fn test_wide_bounds() {
+    // 10° wide bbox covering UTM zones 10 and 11
+    let res = transform_latlon_to_utm_bounds( -125.0, 35.0, -115.0, 40.0, false, None, false, None, false);
+    match res {
+        Err(OdinGdalError::MiscError(msg)) => {
+            println!("{msg}");
+            assert!( msg.contains("UTM zones 10..11"));
+        }
+        other => panic!("expected straddle error, got {other:?}")
+    }
+
+    // explicit override still projects
+    assert!( transform_latlon_to_utm_bounds( -125.0, 35.0, -115.0, 40.0, false, None, false, None, true).is_ok());
+    // .. as does a larger max zone span
+    assert!( transform_latlon_to_utm_bounds( -125.0, 35.0, -115.0, 40.0, false, None, false, Some(2), false).is_ok());
+}
+
+#[test]
+fn test_narrow_bounds() {
+    let (west, south, east, north, zone) = transform_latlon_to_utm_bounds( -122.0, 37.0, -121.0, 38.0, false, None, false, None, false).unwrap();
+    println!("zone {zone}: {west},{south} - {east},{north}");
+    assert_eq!( zone, 10);
+    assert!( west < east && south < north);
+}
@@ -0,0 +1,77 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License. You may obtain a copy
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+use std::path::{Path,PathBuf};
+use odin_gdal::{Buffer, Dataset, ResampleAlg, SpatialRef, build_vrt, create_dataset};
+
+const N: usize = 10;
+const RES: f64 = 30.0; // meters
+
+/// create a NxN single band UTM tile with its upper left corner at (x0,y0)
+fn create_tile (name: &str, x0: f64, y0: f64, value: f32) -> PathBuf {
+    let path = std::env::temp_dir().join(name);
+    let mut ds = create_dataset::<f32>( &path, N, N, 1).unwrap();
+    ds.set_geo_transform( &[x0, RES, 0.0, y0, 0.0, -RES]).unwrap();
+    ds.set_spatial_ref( &SpatialRef::from_epsg(32610).unwrap()).unwrap();
+    ds.rasterband(1).unwrap().write( (0,0), (N,N), &mut Buffer::new( (N,N), vec![value; N*N])).unwrap();
+    path
+}
+
+#[test]
+fn test_build_vrt() {
+    let west = create_tile( "odin_gdal_vrt_west.tif", 500000.0, 4000000.0, 1.0);
+    let east = create_tile( "odin_gdal_vrt_east.tif", 500000.0 + N as f64 * RES, 4000000.0, 2.0);
+    let out_path = std::env::temp_dir().join("odin_gdal_mosaic.vrt");
+
+    build_vrt( &[west, east], &out_path, ResampleAlg::NearestNeighbour, None).unwrap();
+
+    let vrt = Dataset::open( &out_path).unwrap();
+    println!("VRT raster size: {:?}", vrt.raster_size());
+    assert_eq!( vrt.raster_size(), (2*N, N));
+
+    let band = vrt.rasterband(1).unwrap();
+    let row = band.read_as::<f32>( (0,0), (2*N,1), (2*N,1), None).unwrap();
+    assert_eq!( row.data()[0], 1.0);
+    assert_eq!( row.data()[2*N-1], 2.0);
+}
+
+#[test]
+fn test_build_reprojected_vrt() {
+    let west = create_tile( "odin_gdal_vrt_4326_west.tif", 500000.0, 4000000.0, 1.0);
+    let east = create_tile( "odin_gdal_vrt_4326_east.tif", 500000.0 + N as f64 * RES, 4000000.0, 2.0);
+    let out_path = std::env::temp_dir().join("odin_gdal_mosaic_4326.vrt");
+
+    build_vrt( &[west, east], &out_path, ResampleAlg::NearestNeighbour, Some(4326)).unwrap();
+
+    let vrt = Dataset::open( &out_path).unwrap();
+    assert_eq!( vrt.spatial_ref().unwrap().auth_code().unwrap(), 4326);
+
+    // the tiles start at the UTM 10N central meridian (-123 deg) and span 600m (~0.0067 deg) to the east
+    let gt = vrt.geo_transform().unwrap();
+    let (width,height) = vrt.raster_size();
+    let east_lon = gt[0] + gt[1] * width as f64;
+    let south_lat = gt[3] + gt[5] * height as f64;
+    println!("VRT bounds: {:.5}, {:.5}, {:.5}, {:.5} ({}x{})", gt[0], south_lat, east_lon, gt[3], width, height);
+
+    assert!( (gt[0] - -123.0).abs() < 1e-3);
+    assert!( (east_lon - -122.9933).abs() < 1e-3);
+    assert!( gt[3] > 36.1 && gt[3] < 36.2); // northing 4000km
+    assert!( south_lat < gt[3] && (gt[3] - south_lat) < 0.004); // 300m
+
+    // values of the source tiles end up west and east of the center
+    let band = vrt.rasterband(1).unwrap();
+    let row = band.read_as::<f32>( (0,height/2), (width,1), (width,1), None).unwrap();
+    assert_eq!( row.data()[width/4], 1.0);
+    assert_eq!( row.data()[3*width/4], 2.0);
+}
@@ -0,0 +1,67 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License. You may obtain a copy
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+use std::path::PathBuf;
+use odin_gdal::{Dataset, DriverManager, SpatialRef, Buffer};
+use odin_gdal::warp::SimpleWarpBuilder;
+
+const N: usize = 10;
+
+/// create a 10x10 single band GeoTIFF covering lon 0..10, lat 0..10 with all pixels set to 100
+fn create_src_tiff (path: &PathBuf)->Dataset {
+    let driver = DriverManager::get_driver_by_name("GTiff").unwrap();
+    let mut ds = driver.create_with_band_type::<u8,_>( path, N, N, 1).unwrap();
+    ds.set_geo_transform( &[0.0, 1.0, 0.0, N as f64, 0.0, -1.0]).unwrap();
+    ds.set_spatial_ref( &SpatialRef::from_epsg(4326).unwrap()).unwrap();
+
+    let mut band = ds.rasterband(1).unwrap();
+    band.set_no_data_value( Some(0.0)).unwrap();
+    let mut buf = Buffer::new( (N,N), vec![100u8; N*N]);
+    band.write( (0,0), (N,N), &mut buf).unwrap();
+    ds.flush_cache().unwrap();
+
+    ds
+}
+
+#[test]
+fn test_warp_cutline() {
+    let tmp_dir = std::env::temp_dir();
+    let src_path = tmp_dir.join("odin_gdal_cutline_src.tif");
+    let tgt_path = tmp_dir.join("odin_gdal_cutline_tgt.tif");
+
+    let src_ds = create_src_tiff( &src_path);
+    let srs = SpatialRef::from_epsg(4326).unwrap();
+
+    let mut warp = SimpleWarpBuilder::new( &src_ds, &tgt_path).unwrap();
+    warp.set_tgt_srs( &srs);
+    warp.set_tgt_extent( 0.0, 0.0, N as f64, N as f64);
+    warp.set_tgt_size( N as i32, N as i32);
+    warp.set_tgt_format( "GTiff").unwrap();
+    warp.set_cutline( "POLYGON((1 1, 9 1, 5 9, 1 1))", 0.0);
+    let tgt_ds = warp.exec().unwrap();
+
+    let band = tgt_ds.rasterband(1).unwrap();
+    let buf = band.read_as::<u8>( (0,0), (N,N), (N,N), None).unwrap();
+    let data = buf.data();
+    let px = |x: usize, y: usize| data[y*N + x];
+
+    // corners are outside of the triangle
+    assert_eq!( px(0,0), 0);
+    assert_eq!( px(N-1,0), 0);
+    assert_eq!( px(0,N-1), 0);
+    assert_eq!( px(N-1,N-1), 0);
+
+    // interior (lon 5.5, lat 3.5) is populated
+    assert_eq!( px(5,6), 100);
+}
@@ -14,7 +14,9 @@
 
 //! actors for odin_goesr data
 
+use chrono::{DateTime,Utc};
 use odin_actor::prelude::*;
+use odin_common::geo::GeoBoundingBox;
 use crate::*; 
 
 #[derive(Serialize,Deserialize,Debug)]
@@ -25,12 +27,17 @@ pub struct GoesrImportActorConfig {
 /// external message to request action execution with the current HotspotStore
 #[derive(Debug)] pub struct ExecSnapshotAction(pub DynDataRefAction<GoesrHotspotStore>);
 
+/// external query message to retrieve the stored hotspots within a geographic region, optionally only from
+/// hotspot sets that are newer than `since`
+#[derive(Debug)] pub struct GetHotspotsInRegion { pub bbox: GeoBoundingBox, pub since: Option<DateTime<Utc>> }
+pub type HotspotRegionQuery = Query<GetHotspotsInRegion,Vec<GoesrHotspot>>;
+
 // internal messages sent by the GoesRDataImporter
 #[derive(Debug)] pub struct Update(pub(crate) GoesrHotspotSet);
 #[derive(Debug)] pub struct Initialize(pub(crate) Vec<GoesrHotspotSet>);
 #[derive(Debug)] pub struct ImportError(pub(crate) OdinGoesrError);
 
-define_actor_msg_set! { pub GoesrHotspotImportActorMsg = ExecSnapshotAction | Initialize | Update | ImportError }
+define_actor_msg_set! { pub GoesrHotspotImportActorMsg = ExecSnapshotAction | Query<GetHotspotsInRegion,Vec<GoesrHotspot>> | Initialize | Update | ImportError }
 
 /// user part of the GoesR import actor
 /// this basically provides a message interface around an encapsulated, async updated HotspotStore
@@ -64,6 +71,11 @@ impl <T,I,U> GoesrHotspotActor<T,I,U>
         self.update_action.execute(new_hotspots).await;
         Ok(())
     }
+
+    pub async fn handle_region_query (&self, query: Query<GetHotspotsInRegion,Vec<GoesrHotspot>>) -> Result<()> {
+        let hotspots = self.hotspot_store.hotspots_in_region( &query.question.bbox, query.question.since);
+        Ok( query.respond( hotspots).await? )
+    }
 }
  
 impl_actor! { match msg for Actor< GoesrHotspotActor<T,I,U>, GoesrHotspotImportActorMsg> 
@@ -76,6 +88,8 @@ impl_actor! { match msg for Actor< GoesrHotspotActor<T,I,U>, GoesrHotspotImportA
 
     ExecSnapshotAction => cont! { msg.0.execute( &self.hotspot_store).await; }
 
+    Query<GetHotspotsInRegion,Vec<GoesrHotspot>> => cont! { self.handle_region_query(msg).await; }
+
     Initialize => cont! { self.init(msg.0).await; }
 
     Update => cont! { self.update(msg.0).await; }
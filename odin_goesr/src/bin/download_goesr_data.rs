@@ -26,7 +26,7 @@ use chrono::{DateTime,Utc};
 
 use odin_build;
 use odin_common::{define_cli,fs::ensure_writable_dir};
-use odin_common::s3::{S3Object,create_s3_client, get_s3_objects, get_last_s3_object};
+use odin_common::s3::{S3Object,S3ListingCache, get_s3_objects, get_last_s3_object};
 use odin_common::schedule::{get_hourly_schedule,Compaction,get_next_hourly_event_dtg};
 use odin_goesr::{load_config,get_goesr_data, get_most_recent_objects, get_objects_since, no_object_error, OdinGoesrError, Result, LiveGoesrHotspotImporterConfig};
 
@@ -41,25 +41,28 @@ async fn main()->Result<()> {
     let cache_dir = odin_build::cache_dir().join("goesr");
     ensure_writable_dir(&cache_dir)?;
 
-    let client = create_s3_client( config.s3_region.clone()).await?;
-    let bucket = &config.bucket;
-    let sat_id = config.sat_id;
-    let source = Arc::new(config.source.clone());
+    let client = config.s3.create_client().await?;
+    let satellite = config.satellite;
+    let bucket = satellite.bucket();
+    let source = Arc::new(config.product.source().to_string());
     let n_objs = config.init_files;
     let mut last_obj: Option<S3Object> = None;
+    let mut listing_cache = S3ListingCache::new( config.max_cached_prefixes);
 
-    println!("retrieving GOES-{} datasets for product {}\n(terminate with Ctrl-C)", sat_id, source);
+    println!("retrieving GOES-{} datasets for product {}\n(terminate with Ctrl-C)", satellite.platform_id(), source);
 
     //--- initial download
     println!("\n----------- initial download of {} objects started at {}", n_objs, Utc::now());
-    let mut objs = get_most_recent_objects( &client, &config.bucket, &source, Duration::from_hours(3), Utc::now()).await?;
-    if objs.len() < 12 { return Err(no_object_error("not enough initial objects")) }
+    let lookback = Duration::from_hours(3);
+    let mut objs = get_most_recent_objects( &client, &mut listing_cache, bucket, &source, lookback, Utc::now()).await?;
+    let min_objs = (lookback.as_secs() / config.product.scan_interval().as_secs() / 3) as usize; // at least a third of the nominal scans
+    if objs.len() < min_objs { return Err(no_object_error("not enough initial objects")) }
 
     let hourly_schedule = get_hourly_schedule(&objs, Some(Compaction::BoundedRightEdge(3)));
     let mut init_objs = if objs.len() > config.init_files { objs.split_off( objs.len()-config.init_files) } else { objs };
 
     for obj in &init_objs {
-        let gdata = get_goesr_data( &client, obj, &cache_dir, bucket, source.clone(), sat_id).await?;
+        let gdata = get_goesr_data( &client, obj, &cache_dir, satellite, source.clone()).await?;
         println!("downloaded initial dataset {:?}", gdata.file);
     }
     last_obj = init_objs.pop();
@@ -73,10 +76,10 @@ async fn main()->Result<()> {
         println!("----------- {}: next at {} (sleep for {:?})", dt_cycle, dt_next, sleep_dur);
         sleep( sleep_dur).await;
 
-        let mut update_objs = get_objects_since( &client, &config.bucket, &source, &last_obj, dt_cycle, Utc::now()).await?;
+        let mut update_objs = get_objects_since( &client, &mut listing_cache, bucket, &source, &last_obj, dt_cycle, Utc::now()).await?;
         println!("downloading {} objects...", update_objs.len());
         for obj in &update_objs {
-            let gdata = get_goesr_data( &client, obj, &cache_dir, bucket, source.clone(), sat_id).await?;
+            let gdata = get_goesr_data( &client, obj, &cache_dir, satellite, source.clone()).await?;
             println!("downloaded update dataset  {:?}", gdata.file);
         }
         last_obj = update_objs.pop().or( last_obj);
@@ -34,7 +34,7 @@ fn main() {
                 sat_id: file_info.sat_id,
                 file: path.to_path_buf(),
                 source: Arc::new(format!("{}-{}-{}", file_info.instrument, file_info.level, file_info.product)),
-                date: file_info.create_time
+                date: file_info.create_or_start_time()
             };
 
             let hs = read_goesr_data( &gdata).unwrap();
@@ -16,7 +16,7 @@ use odin_common::{*, geo::LatLon, ranges::LinearRange};
 use odin_gdal::{Dataset, GdalValueType, GridPoint, Metadata, MetadataEntry}; // gdal re-exports
 use serde::Serialize;
 
-use crate::{OdinGoesrError,Result};
+use crate::{OdinGoesrError,Result,Satellite};
 
 
 #[derive(Debug,Clone, Copy, Serialize)]
@@ -27,28 +27,70 @@ pub struct GoesrBoundingBox {
     pub se: LatLon
 }
 
-pub fn get_bounds<T> (proj: &GoesrProjection, x_range: &LinearRange<f64>, y_range: &LinearRange<f64>, p: &GridPoint<T>)->GoesrBoundingBox 
-    where T: GdalValueType
-{
-    let x_inc = x_range.inc() / 2.0;
-    let y_inc = y_range.inc() / 2.0;
+impl GoesrBoundingBox {
+    /// check if given position is within the quadrilateral spanned by our corners. Note this uses a simple
+    /// crossing test in lon/lat space and hence does not support boxes that cross the antimeridian
+    pub fn contains (&self, p: &LatLon)->bool {
+        let corners = [self.ne, self.nw, self.sw, self.se];
+        let mut is_inside = false;
+
+        let mut j = corners.len() - 1;
+        for i in 0..corners.len() {
+            let (ci, cj) = (&corners[i], &corners[j]);
+            if (ci.lat_deg > p.lat_deg) != (cj.lat_deg > p.lat_deg) {
+                let lon = ci.lon_deg + (p.lat_deg - ci.lat_deg) * (cj.lon_deg - ci.lon_deg) / (cj.lat_deg - ci.lat_deg);
+                if p.lon_deg < lon { is_inside = !is_inside }
+            }
+            j = i;
+        }
+        is_inside
+    }
+}
 
-    let x = x_range.at( p.i0);
-    let y = y_range.at( p.i1);
+/// the fixed grid cell of a pixel: its grid indices plus the instrument scan (E/W) and elevation (N/S) angles of
+/// the pixel center and the angular pixel size (all angles in radians, `dy` is negative since rows go north to south)
+#[derive(Debug,Clone,Copy,Default,PartialEq,Serialize)]
+pub struct GoesrGridCell {
+    pub i0: usize,
+    pub i1: usize,
+    pub x: f64,
+    pub y: f64,
+    pub dx: f64,
+    pub dy: f64
+}
 
-    let xw = x - x_inc;
-    let xe = x + x_inc;
-    let yn = y - y_inc;  // y_inc negative
-    let ys = y + y_inc;
+impl GoesrGridCell {
+    pub fn new<T> (x_range: &LinearRange<f64>, y_range: &LinearRange<f64>, p: &GridPoint<T>)->Self where T: GdalValueType {
+        GoesrGridCell { i0: p.i0, i1: p.i1, x: x_range.at( p.i0), y: y_range.at( p.i1), dx: x_range.inc(), dy: y_range.inc() }
+    }
 
-    let nw = proj.lat_lon_from_instrument_angles( xw, yn);
-    let ne = proj.lat_lon_from_instrument_angles( xe, yn);
-    let sw = proj.lat_lon_from_instrument_angles( xw, ys);
-    let se = proj.lat_lon_from_instrument_angles( xe, ys);
+    /// the geodetic positions of the cell corners in [nw, ne, se, sw] order. Since the fixed grid is defined in
+    /// instrument angles this is a general quadrilateral on the ground that gets more elongated towards the limb
+    pub fn corners (&self, proj: &GoesrProjection)->[LatLon;4] {
+        let xw = self.x - self.dx / 2.0;
+        let xe = self.x + self.dx / 2.0;
+        let yn = self.y - self.dy / 2.0;  // dy negative
+        let ys = self.y + self.dy / 2.0;
+
+        [ proj.lat_lon_from_instrument_angles( xw, yn),
+          proj.lat_lon_from_instrument_angles( xe, yn),
+          proj.lat_lon_from_instrument_angles( xe, ys),
+          proj.lat_lon_from_instrument_angles( xw, ys) ]
+    }
+}
 
+pub fn get_bounds<T> (proj: &GoesrProjection, x_range: &LinearRange<f64>, y_range: &LinearRange<f64>, p: &GridPoint<T>)->GoesrBoundingBox 
+    where T: GdalValueType
+{
+    let [nw,ne,se,sw] = GoesrGridCell::new( x_range, y_range, p).corners( proj);
     GoesrBoundingBox{ne,nw,sw,se}
 }
 
+// nominal GOES-R fixed grid projection parameters (same for all satellites), see PUG-L2+-vol5 pg. 23
+const PERSPECTIVE_POINT_HEIGHT: f64 = 35786023.0;
+const GRS80_SEMI_MAJOR: f64 = 6378137.0;
+const GRS80_SEMI_MINOR: f64 = 6356752.31414;
+
 /// structure that supports instrument scan/elevation angle to/from geodetic position conversion
 /// this is partly based on metadata included in the data set (NetCDF) files, of which we need at least
 /// the satellite specific `longitude_of_projection_origin` and `perspective_point_height`.
@@ -67,8 +109,8 @@ impl GoesrProjection {
         let mut f_inv: f64 = 298.257222096;
         let mut lon0: f64 = f64::NAN;
         let mut pph: f64 = f64::NAN;
-        let mut r_eq: f64 = 6378137.0;
-        let mut r_pol: f64 = 6356752.31414;
+        let mut r_eq: f64 = GRS80_SEMI_MAJOR;
+        let mut r_pol: f64 = GRS80_SEMI_MINOR;
 
         for MetadataEntry { domain:_, key, value } in ds.metadata() {
             if key.ends_with("#inverse_flattening") { f_inv = value.parse()? }
@@ -79,11 +121,21 @@ impl GoesrProjection {
         }
         if (lon0.is_nan() || pph.is_nan()) { return Err( OdinGoesrError::DatasetError("missing projection metadata".into())) }
 
+        Ok( Self::from_parameters( lon0, pph, r_eq, r_pol) )
+    }
+
+    /// create projection from nominal (GRS80) parameters of the given satellite, e.g. if we don't have a data set yet
+    pub fn for_satellite (satellite: Satellite)->Self {
+        Self::from_parameters( satellite.longitude_of_projection_origin().to_radians(), PERSPECTIVE_POINT_HEIGHT, GRS80_SEMI_MAJOR, GRS80_SEMI_MINOR)
+    }
+
+    /// lon0 is in radians, all other values in meters
+    fn from_parameters (lon0: f64, pph: f64, r_eq: f64, r_pol: f64)->Self {
         let r2:f64 = pow2( r_eq / r_pol);
         let h: f64 = pph + r_eq;
         let c: f64 = pow2(h) - pow2(r_eq);
 
-        Ok( GoesrProjection { h, r2, lon0, c } )
+        GoesrProjection { h, r2, lon0, c }
     }
 
     pub fn lat_lon_from_instrument_angles (&self, ew_scan: f64, ns_elevation: f64)->LatLon {
@@ -16,16 +16,16 @@
 
 #[doc = include_str!("../doc/odin_goesr.md")]
 
-use std::{f32::NAN, fmt::{Debug,Display}, fs::File, io::Write, ops::Deref, path::{Path,PathBuf}, sync::Arc, time::Duration};
-use std::collections::VecDeque;
+use std::{collections::HashMap, f32::NAN, fmt::{Debug,Display}, fs::File, io::Write, ops::Deref, path::{Path,PathBuf}, sync::Arc, time::Duration};
 use serde::{Deserialize,Serialize};
-use odin_common::{datetime::Dated, geo::LatLon};
+use odin_common::{datetime::Dated, geo::{BoundingBox,GeoBoundingBox,LatLon,MEAN_EARTH_RADIUS,haversine_distance}, collections::TimeRingStore};
 use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, Timelike, Utc};
-use uom::si::{area::square_meter, f32::Time, length::meter, power::milliwatt, thermodynamic_temperature::kelvin};
+use uom::si::{area::square_meter, f32::Time, length::meter, power::{milliwatt,megawatt}, thermodynamic_temperature::{kelvin,degree_celsius}};
 use uom::si::f32::{Power,ThermodynamicTemperature, Area, Length};
 use futures::Future;
 use regex::Regex;
 use lazy_static::lazy_static;
+use ndarray::Array2;
 
 use odin_build::{define_load_asset, define_load_config};
 use odin_actor::ActorHandle;
@@ -33,7 +33,7 @@ use odin_actor::prelude::*;
 use odin_actor::error;
 use odin_common::{if_let};
 use odin_common::{*,fs::remove_old_files,datetime::full_hour,ranges::LinearRange};
-use odin_common::s3::{S3Client,S3Object,create_s3_client,get_s3_objects,download_s3_object};
+use odin_common::s3::{S3Client,S3Object,S3ObjectLister,S3ListingCache,create_s3_client,create_s3_client_with,get_s3_objects,download_s3_object};
 use odin_gdal::{Dataset, Metadata, MetadataEntry, GdalValueType}; // gdal re-exports
 use odin_gdal::gdal::{DatasetOptions,GdalOpenFlags};
 use odin_gdal::{GridPoint, find_grid_points_in_slice, get_grid_point_values, get_linear_range, nc_dataset, quiet_nc_dataset};
@@ -47,11 +47,14 @@ pub use actor::*;
 pub mod live_importer;
 pub use live_importer::*;
 
+pub mod replay_importer;
+pub use replay_importer::*;
+
 pub mod goesr_service;
 pub use goesr_service::*;
 
 mod geo;
-use geo::{GoesrBoundingBox,GoesrProjection,get_bounds};
+pub use geo::{GoesrBoundingBox,GoesrGridCell,GoesrProjection,get_bounds};
 
 define_load_config!{}
 define_load_asset!{}
@@ -89,6 +92,83 @@ const MASK_TEMP_HIGH_PROB: u16          = 33;  // temporally_filtered_high_proba
 const MASK_TEMP_MED_PROB: u16           = 34;  // temporally_filtered_medium_probability_fire_pixel
 const MASK_TEMP_LOW_PROB: u16           = 35;  // temporally_filtered_low_probability_fire_pixel
 
+/// fire pixel category as decoded from a raw mask band value (see [`classify_mask`])
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Serialize,Deserialize)]
+pub enum FirePixelClass {
+    Good,
+    Saturated,
+    CloudContaminated,
+    HighProbability,
+    MediumProbability,
+    LowProbability,
+    TemporallyFilteredGood,
+    TemporallyFilteredSaturated,
+    TemporallyFilteredCloudContaminated,
+    TemporallyFilteredHighProbability,
+    TemporallyFilteredMediumProbability,
+    TemporallyFilteredLowProbability,
+    Unknown
+}
+
+impl FirePixelClass {
+    pub fn is_fire (&self)->bool { *self != FirePixelClass::Unknown }
+
+    pub fn is_good (&self)->bool {
+        matches!( self, FirePixelClass::Good | FirePixelClass::TemporallyFilteredGood)
+    }
+    pub fn is_high_probability (&self)->bool {
+        matches!( self, FirePixelClass::HighProbability | FirePixelClass::TemporallyFilteredHighProbability)
+    }
+    pub fn is_medium_probability (&self)->bool {
+        matches!( self, FirePixelClass::MediumProbability | FirePixelClass::TemporallyFilteredMediumProbability)
+    }
+    pub fn is_low_probability (&self)->bool {
+        matches!( self, FirePixelClass::LowProbability | FirePixelClass::TemporallyFilteredLowProbability)
+    }
+    pub fn is_temporally_filtered (&self)->bool {
+        use FirePixelClass::*;
+        matches!( self, TemporallyFilteredGood | TemporallyFilteredSaturated | TemporallyFilteredCloudContaminated |
+                        TemporallyFilteredHighProbability | TemporallyFilteredMediumProbability | TemporallyFilteredLowProbability)
+    }
+
+    /// ordinal fire confidence of this class (higher is more confident). Temporally filtered classes rank the same
+    /// as their instantaneous counterparts
+    pub fn confidence_rank (&self)->u8 {
+        use FirePixelClass::*;
+        match self {
+            Good | Saturated | TemporallyFilteredGood | TemporallyFilteredSaturated => 5,
+            HighProbability | TemporallyFilteredHighProbability => 4,
+            MediumProbability | TemporallyFilteredMediumProbability => 3,
+            LowProbability | TemporallyFilteredLowProbability => 2,
+            CloudContaminated | TemporallyFilteredCloudContaminated => 1,
+            Unknown => 0
+        }
+    }
+
+    pub fn meets_confidence (&self, min_class: FirePixelClass)->bool {
+        self.confidence_rank() >= min_class.confidence_rank()
+    }
+}
+
+/// decode a raw value of the "Mask" band into its fire pixel class. Values that are not fire pixels (e.g. cloud or
+/// water) map to `FirePixelClass::Unknown`
+pub fn classify_mask (mask: u16)->FirePixelClass {
+    match mask {
+        MASK_GOOD => FirePixelClass::Good,
+        MASK_SATURATED => FirePixelClass::Saturated,
+        MASK_CLOUD_CONTAMINATED => FirePixelClass::CloudContaminated,
+        MASK_HIGH_PROB => FirePixelClass::HighProbability,
+        MASK_MED_PROB => FirePixelClass::MediumProbability,
+        MASK_LOW_PROB => FirePixelClass::LowProbability,
+        MASK_TEMP_GOOD => FirePixelClass::TemporallyFilteredGood,
+        MASK_TEMP_SATURATED => FirePixelClass::TemporallyFilteredSaturated,
+        MASK_TEMP_COULD_CONTAMINATED => FirePixelClass::TemporallyFilteredCloudContaminated,
+        MASK_TEMP_HIGH_PROB => FirePixelClass::TemporallyFilteredHighProbability,
+        MASK_TEMP_MED_PROB => FirePixelClass::TemporallyFilteredMediumProbability,
+        MASK_TEMP_LOW_PROB => FirePixelClass::TemporallyFilteredLowProbability,
+        _ => FirePixelClass::Unknown
+    }
+}
 
 
 #[derive(Debug,Clone, Serialize)]
@@ -105,7 +185,9 @@ pub struct GoesrHotspot {
     pub dqf: u8,
     pub mask: u16,
     pub source: Arc<String>, // don't duplicate
-    pub pixel_size: Length
+    pub pixel_size: Length,
+    #[serde(skip)]
+    pub grid_cell: Option<GoesrGridCell> // fixed grid location, only set if the hotspot was read from a data set
 }
 
 impl GoesrHotspot {
@@ -124,16 +206,67 @@ impl GoesrHotspot {
             mask: mask,
             // product info
             source: data.source.clone(),
-            pixel_size: Length::new::<meter>(2000.0)
+            pixel_size: Length::new::<meter>(2000.0),
+            grid_cell: None
           }
     }
 
-    pub fn is_good_pixel (&self)->bool { (self.mask == MASK_GOOD) || (self.mask == MASK_TEMP_GOOD) }
-    pub fn is_high_probability_pixel (&self)->bool { (self.mask == MASK_HIGH_PROB) || (self.mask == MASK_TEMP_HIGH_PROB) }
-    pub fn is_medium_probability_pixel (&self)->bool { (self.mask == MASK_MED_PROB) || (self.mask == MASK_TEMP_MED_PROB) }
-    pub fn is_low_probability_pixel (&self)->bool { (self.mask == MASK_LOW_PROB) || (self.mask == MASK_TEMP_LOW_PROB) }
-    pub fn is_temporally_filtered (&self)->bool {
-        (self.mask == MASK_TEMP_GOOD) || (self.mask == MASK_TEMP_HIGH_PROB) || (self.mask == MASK_TEMP_MED_PROB) || (self.mask == MASK_TEMP_LOW_PROB)
+    pub fn with_grid_cell (mut self, grid_cell: GoesrGridCell)->Self {
+        self.grid_cell = Some(grid_cell);
+        self
+    }
+
+    /// the ground footprint of our pixel as [nw, ne, se, sw] corners, computed from the fixed grid cell and the
+    /// projection of the satellite that produced it. Unlike `bounds` this does not need to be stored (or sent) and can
+    /// be recomputed for any projection. Returns `None` if there is no grid cell (see `with_grid_cell`)
+    pub fn footprint_polygon (&self, projection: &GoesrProjection)->Option<[LatLon;4]> {
+        self.grid_cell.map( |cell| cell.corners( projection))
+    }
+
+    pub fn bright_kelvin (&self)->f32 { self.bright.get::<kelvin>() }
+    pub fn bright_celsius (&self)->f32 { self.bright.get::<degree_celsius>() }
+    pub fn frp_megawatts (&self)->f32 { self.frp.get::<megawatt>() }
+
+    /// check if both brightness temperature and FRP are at or above the given thresholds
+    pub fn exceeds (&self, min_bright_k: f32, min_frp_mw: f32)->bool {
+        self.bright_kelvin() >= min_bright_k && self.frp_megawatts() >= min_frp_mw
+    }
+
+    pub fn pixel_class (&self)->FirePixelClass { classify_mask( self.mask) }
+
+    pub fn is_good_pixel (&self)->bool { self.pixel_class().is_good() }
+    pub fn is_high_probability_pixel (&self)->bool { self.pixel_class().is_high_probability() }
+    pub fn is_medium_probability_pixel (&self)->bool { self.pixel_class().is_medium_probability() }
+    pub fn is_low_probability_pixel (&self)->bool { self.pixel_class().is_low_probability() }
+    pub fn is_temporally_filtered (&self)->bool { self.pixel_class().is_temporally_filtered() }
+
+    /// textual fire confidence derived from our mask value
+    pub fn confidence (&self)->&'static str {
+        if self.is_good_pixel() { "good" }
+        else if self.is_high_probability_pixel() { "high" }
+        else if self.is_medium_probability_pixel() { "medium" }
+        else if self.is_low_probability_pixel() { "low" }
+        else { "unknown" }
+    }
+}
+
+/// the class of fire pixel masks we keep when ingesting GoesR data. Instantaneous masks (MASK_GOOD..MASK_LOW_PROB)
+/// are available faster but are noisier than the temporally filtered ones (MASK_TEMP_GOOD..MASK_TEMP_LOW_PROB)
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Default,Serialize,Deserialize)]
+pub enum GoesrMaskClass {
+    #[default]
+    All,
+    Instantaneous,
+    TemporallyFiltered
+}
+
+impl GoesrMaskClass {
+    pub fn includes (&self, h: &GoesrHotspot)->bool {
+        match self {
+            GoesrMaskClass::All => true,
+            GoesrMaskClass::Instantaneous => !h.is_temporally_filtered(),
+            GoesrMaskClass::TemporallyFiltered => h.is_temporally_filtered()
+        }
     }
 }
 
@@ -152,69 +285,140 @@ pub struct GoesrHotspotSet {
     pub n_low: usize
 }
 
+impl Dated for GoesrHotspotSet {
+    fn date (&self)->DateTime<Utc> { self.date }
+}
+
 impl GoesrHotspotSet {
     pub fn new(data: &GoesrData, hotspot_vec: Vec<GoesrHotspot>) -> Self {
+        let mut hs = GoesrHotspotSet {
+            date: data.date.clone(),
+            sat_id: data.sat_id,
+            source: data.source.clone(),
+            hotspots: hotspot_vec,
+            n_good: 0, n_high: 0, n_medium: 0, n_low: 0
+        };
+        hs.update_stats();
+        hs
+    }
+
+    fn update_stats (&mut self) {
         let mut n_good = 0;
         let mut n_high = 0;
         let mut n_medium = 0;
         let mut n_low = 0;
-        for h in &hotspot_vec {
+        for h in &self.hotspots {
             if h.is_good_pixel() { n_good += 1; }
             if h.is_high_probability_pixel() { n_high += 1; } 
             else if h.is_medium_probability_pixel() { n_medium += 1; } 
             else if h.is_low_probability_pixel() { n_low += 1; }
         }
 
-        GoesrHotspotSet {
-            date: data.date.clone(),
-            sat_id: data.sat_id,
-            source: data.source.clone(),
-            hotspots: hotspot_vec,
-            n_good, n_high, n_medium, n_low
+        self.n_good = n_good;
+        self.n_high = n_high;
+        self.n_medium = n_medium;
+        self.n_low = n_low;
+    }
+
+    /// drop all hotspots that are not of the given mask class and update the stats accordingly
+    pub fn retain_mask_class (&mut self, mask_class: GoesrMaskClass) {
+        if mask_class != GoesrMaskClass::All {
+            self.hotspots.retain( |h| mask_class.includes(h));
+            self.update_stats();
         }
     }
+
+    /// drop all hotspots with a lower confidence than `min_class`. Note this does not update the stats, which
+    /// still report the n_good/n_high/n_medium/n_low counts of the unfiltered set
+    pub fn retain_min_class (&mut self, min_class: FirePixelClass) {
+        self.hotspots.retain( |h| h.pixel_class().meets_confidence( min_class));
+    }
+
     pub fn to_json_pretty (&self)->Result<String> {
         Ok(serde_json::to_string_pretty( &self )?)
     }
     pub fn to_json (&self)->Result<String> {
         Ok(serde_json::to_string( &self )?)
     }
+
+    /// return this set as a GeoJSON FeatureCollection string (see [`write_geojson_to`](Self::write_geojson_to))
+    pub fn to_geojson (&self)->Result<String> {
+        let mut buf: Vec<u8> = Vec::new();
+        self.write_geojson_to( &mut buf)?;
+        String::from_utf8( buf).map_err( misc_error) // serde_json only writes valid UTF-8
+    }
+
+    /// write this set as a GeoJSON FeatureCollection of Point features, using the serialized hotspots as feature properties.
+    /// Features are written one at a time so that we do not need an intermediate string for large (e.g. full disk) sets
+    pub fn write_geojson_to (&self, w: &mut impl Write)->Result<()> {
+        write!( w, r#"{{"type":"FeatureCollection","features":["#)?;
+        for (i,h) in self.hotspots.iter().enumerate() {
+            if i > 0 { write!( w, ",")? }
+            write!( w, r#"{{"type":"Feature","geometry":{{"type":"Point","coordinates":[{},{}]}},"properties":"#, 
+                    h.position.lon_deg, h.position.lat_deg)?;
+            serde_json::to_writer( &mut *w, h)?;
+            write!( w, "}}")?;
+        }
+        write!( w, "]}}")?;
+        Ok(())
+    }
+}
+
+/// iterate over all hotspots of the given sets whose position satisfies `contains`
+fn hotspots_within<'a,I,F> (sets: I, contains: F) -> impl Iterator<Item=&'a GoesrHotspot> 
+    where I: Iterator<Item=&'a GoesrHotspotSet>, F: Fn(&LatLon)->bool
+{
+    sets.flat_map( |hs| hs.hotspots.iter()).filter( move |h| contains( &h.position))
 }
 
 /// data structure to keep the max_capacity last GoesrHotspotSet items, with newest one first
 #[derive(Debug,Clone, Serialize)]
 pub struct GoesrHotspotStore {
-    hotspots: VecDeque<GoesrHotspotSet>, 
-    max_capacity: usize
+    hotspots: TimeRingStore<GoesrHotspotSet>
 }
 
 impl GoesrHotspotStore {
     pub fn new(capacity: usize) -> Self {
         GoesrHotspotStore {
-            hotspots:VecDeque::with_capacity(capacity),
-            max_capacity:capacity
+            hotspots: TimeRingStore::with_max_len(capacity)
         }
     }
+
+    /// sort in new hotspot set, replacing a stored one with the same date (e.g. from re-downloading the same file)
     pub fn update_hotspots(&mut self, new_hotspots: GoesrHotspotSet) -> () {
-        // if vec is not max add in - assume update is from newer date
-        if self.hotspots.len() < self.max_capacity {
-            self.hotspots.push_front(new_hotspots);
-        } else {
-            // remove last, add newest
-            self.hotspots.pop_back();
-            self.hotspots.push_front(new_hotspots);
-        }
+        self.hotspots.insert_dedup( new_hotspots, |hs| hs.date);
     }
 
     pub fn initialize_hotspots(&mut self, init_hotspots: Vec<GoesrHotspotSet>) -> () {
         for hs in init_hotspots {
-            self.hotspots.push_front(hs);
+            self.update_hotspots(hs);
         }
     }
 
     /// note this iterates old-to-new, i.e. the newest entry comes last
     pub fn iter_old_to_new<'a> (&'a self) -> impl Iterator<Item=&'a GoesrHotspotSet> {
-        self.hotspots.iter().rev()
+        self.hotspots.iter_old_to_new()
+    }
+
+    /// return all stored sets that are newer than `since`, iterating old-to-new. This is used to catch up
+    /// clients that reconnect with the date of the last set they have seen
+    pub fn sets_since<'a> (&'a self, since: DateTime<Utc>) -> impl Iterator<Item=&'a GoesrHotspotSet> {
+        self.hotspots.iter_old_to_new().skip_while( move |hs| hs.date <= since)
+    }
+
+    /// return all hotspots of stored sets whose position is within the given bounding box (newest sets first)
+    pub fn hotspots_in_bbox (&self, bbox: &GoesrBoundingBox) -> Vec<&GoesrHotspot> {
+        hotspots_within( self.hotspots.iter(), |p| bbox.contains(p)).collect()
+    }
+
+    /// return copies of all hotspots within the given geographic region from stored sets that are newer than `since`
+    /// (if provided). Hotspots are ordered newest sets first. Regions with `west > east` span the anti-meridian
+    pub fn hotspots_in_region (&self, bbox: &GeoBoundingBox, since: Option<DateTime<Utc>>) -> Vec<GoesrHotspot> {
+        let sets: Vec<&GoesrHotspotSet> = match since {
+            Some(since) => self.sets_since( since).collect(),
+            None => self.iter_old_to_new().collect()
+        };
+        hotspots_within( sets.into_iter().rev(), |p| bbox.contains(p)).cloned().collect()
     }
 
     pub fn to_json_pretty (&self)->Result<String> {
@@ -228,6 +432,7 @@ impl GoesrHotspotStore {
 
 lazy_static! {
     static ref FILENAME_RE: Regex = Regex::new(r#"(?:.*/)?(.*)_([^-]*)-([^-]*)-([^-]+)-(.*)_G(.*)_s(.*)_e(.*)_c(.*)\.(.*)"#).unwrap();
+    static ref LENIENT_FILENAME_RE: Regex = Regex::new(r#"(?:.*/)?([^_/]*)_([^-]*)-([^-]*)-([^-]+)-([^_]*)_G(\d+)_s(\d+)(?:_e(\d+))?(?:_c(\d+))?\.(\w+)$"#).unwrap();
     static ref DTG_RE: Regex = Regex::new(r#"(\d\d\d\d)(\d\d\d)(\d\d)(\d\d)(\d\d)(\d)"#).unwrap();
 }
 
@@ -245,6 +450,9 @@ lazy_static! {
 ///           s : tenths of second (0-9)
 /// 
 /// example: `OR_ABI-L2-FDCC-M6_G16_s20241380556172_e20241380558545_c20241380559122.nc`
+/// 
+/// Some reprocessed products omit the end and/or create times, or use a different extension (e.g. `.nc4`). For those
+/// we only require the fields up to the start time
 #[derive(Debug)]
 pub struct GoesrFileInfo {
     pub sys_env: String, // e.g. "OR": operational realtime
@@ -252,13 +460,21 @@ pub struct GoesrFileInfo {
     pub level: String, // e.g. "L2"
     pub product: String, // e.g. FDCC
     pub mode: String, // e.g. "M6"
-    pub sat_id: u32, // SATCAT, e.g. 41866 
+    pub sat_id: u32, // platform number as in the filename, e.g. 18 for "_G18" (see [`Satellite::from_platform_id`])
     pub start_time: DateTime<Utc>,
-    pub end_time: DateTime<Utc>,
-    pub create_time: DateTime<Utc>,
+    pub end_time: Option<DateTime<Utc>>,
+    pub create_time: Option<DateTime<Utc>>,
 }
 
-/// parse GoesrFileInfo from given pathname
+impl GoesrFileInfo {
+    /// the best approximation we have for when the data became available
+    pub fn create_or_start_time (&self)->DateTime<Utc> {
+        self.create_time.or( self.end_time).unwrap_or( self.start_time)
+    }
+}
+
+/// parse GoesrFileInfo from given pathname. If the filename does not follow the standard schema we fall back to
+/// [`parse_lenient_filename`]
 pub fn parse_filename (path: impl AsRef<Path>)->Option<GoesrFileInfo> {
     let path: &Path = path.as_ref();
     let filename = path.file_name()?.to_str()?;
@@ -275,6 +491,28 @@ pub fn parse_filename (path: impl AsRef<Path>)->Option<GoesrFileInfo> {
         Some(start_time) = parse_goesr_dtg( &cap[7]),
         Some(end_time) = parse_goesr_dtg( &cap[8]),
         Some(create_time) = parse_goesr_dtg(&cap[9]) => {
+            return Some( GoesrFileInfo{sys_env,instrument,level,product,mode,sat_id,start_time,end_time:Some(end_time),create_time:Some(create_time)} )
+        }
+    }
+    parse_lenient_filename( filename)
+}
+
+/// parse GoesrFileInfo from filenames that might lack end and/or create times or use a non-standard extension
+pub fn parse_lenient_filename (path: impl AsRef<Path>)->Option<GoesrFileInfo> {
+    let path: &Path = path.as_ref();
+    let filename = path.file_name()?.to_str()?;
+
+    if_let! {
+        Some(cap) = LENIENT_FILENAME_RE.captures(filename),
+        sys_env = cap[1].to_string(),
+        instrument = cap[2].to_string(),
+        level = cap[3].to_string(),
+        product = cap[4].to_string(),
+        mode = cap[5].to_string(),
+        Ok(sat_id) = cap[6].parse::<u32>(),
+        Some(start_time) = parse_goesr_dtg( &cap[7]) => {
+            let end_time = cap.get(8).and_then( |m| parse_goesr_dtg( m.as_str()));
+            let create_time = cap.get(9).and_then( |m| parse_goesr_dtg( m.as_str()));
             return Some( GoesrFileInfo{sys_env,instrument,level,product,mode,sat_id,start_time,end_time,create_time} )
         }
     }
@@ -305,18 +543,200 @@ pub fn parse_goesr_create_dtg (path: impl AsRef<Path>)->Option<DateTime<Utc>> {
     filename.rfind("_c").and_then(|idx| parse_goesr_dtg(&filename[idx+2..]))
 }
 
+/// report missing scan windows in a series of GoesR files. Scans are supposed to start every `expected_interval`,
+/// we consider it a gap if two consecutive scan starts are more than 1.5 intervals apart (scan start times jitter).
+/// Gaps are returned as (end of last scan before gap, start of first scan after gap) in chronological order. If the
+/// end time of the last scan is unknown we use its start time
+pub fn find_scan_gaps (infos: &[GoesrFileInfo], expected_interval: Duration)->Vec<(DateTime<Utc>,DateTime<Utc>)> {
+    let mut gaps = Vec::new();
+    let max_dt = expected_interval.mul_f64(1.5);
+
+    let mut sorted: Vec<&GoesrFileInfo> = infos.iter().collect();
+    sorted.sort_by_key( |i| i.start_time);
+
+    for w in sorted.windows(2) {
+        let (prev, next) = (w[0], w[1]);
+        if let Ok(dt) = (next.start_time - prev.start_time).to_std() {
+            if dt > max_dt {
+                gaps.push( (prev.end_time.unwrap_or( prev.start_time), next.start_time))
+            }
+        }
+    }
+
+    gaps
+}
+
+/// the GOES-R fire detection and characterization (FDC) products we can import hotspots from.
+/// They all share the same variables (Mask, Power, Area, Temp, DQF) and differ in scan sector and interval
+#[derive(Serialize,Deserialize,Debug,Clone,Copy,PartialEq,Eq,Default)]
+pub enum GoesrProduct {
+    /// CONUS sector, 5min scan interval
+    #[default]
+    #[serde(rename="FDCC")]
+    Fdcc,
+
+    /// full disk, 10min scan interval
+    #[serde(rename="FDCF")]
+    Fdcf,
+
+    /// mesoscale sectors (filenames use FDCM1 and FDCM2), 1min scan interval
+    #[serde(rename="FDCM")]
+    Fdcm,
+}
+
+impl GoesrProduct {
+    /// get product from the product field of a GOES-R filename (see [`GoesrFileInfo`])
+    pub fn from_product (product: &str)->Option<Self> {
+        match product {
+            "FDCC" => Some(GoesrProduct::Fdcc),
+            "FDCF" => Some(GoesrProduct::Fdcf),
+            p if p.starts_with("FDCM") => Some(GoesrProduct::Fdcm),
+            _ => None
+        }
+    }
+
+    /// the source string that is used as the top level of S3 object prefixes, e.g. "ABI-L2-FDCC"
+    pub fn source (&self)->&'static str {
+        match self {
+            GoesrProduct::Fdcc => "ABI-L2-FDCC",
+            GoesrProduct::Fdcf => "ABI-L2-FDCF",
+            GoesrProduct::Fdcm => "ABI-L2-FDCM",
+        }
+    }
+
+    /// nominal interval between scans (in mode 6)
+    pub fn scan_interval (&self)->Duration {
+        match self {
+            GoesrProduct::Fdcc => Duration::from_secs(300),
+            GoesrProduct::Fdcf => Duration::from_secs(600),
+            GoesrProduct::Fdcm => Duration::from_secs(60),
+        }
+    }
+
+    /// the S3 object prefix for data of this product that was scanned in the hour of `dt`
+    pub fn s3_prefix (&self, dt: DateTime<Utc>)->String {
+        get_prefix( dt, self.source())
+    }
+}
+
+/// the GOES-R satellites we can import data from. GOES-18 is in the GOES-West position (137.2°W), GOES-16 was and
+/// GOES-19 is in the GOES-East position (75.2°W). Each of them has its own S3 bucket and fixed grid projection
+#[derive(Serialize,Deserialize,Debug,Clone,Copy,PartialEq,Eq)]
+pub enum Satellite {
+    #[serde(rename="GOES16")]
+    Goes16,
+    #[serde(rename="GOES18")]
+    Goes18,
+    #[serde(rename="GOES19")]
+    Goes19,
+}
+
+impl Satellite {
+    /// the satellite for the given platform number as it appears in GOES-R filenames (e.g. 18 for `_G18`)
+    pub fn from_platform_id (id: u32)->Option<Self> {
+        match id {
+            16 => Some(Satellite::Goes16),
+            18 => Some(Satellite::Goes18),
+            19 => Some(Satellite::Goes19),
+            _ => None
+        }
+    }
+
+    /// platform number as used in GOES-R filenames
+    pub fn platform_id (&self)->u32 {
+        match self {
+            Satellite::Goes16 => 16,
+            Satellite::Goes18 => 18,
+            Satellite::Goes19 => 19,
+        }
+    }
+
+    /// SATCAT number
+    pub fn sat_id (&self)->u32 {
+        match self {
+            Satellite::Goes16 => 41866,
+            Satellite::Goes18 => 51850,
+            Satellite::Goes19 => 60133,
+        }
+    }
+
+    /// the AWS S3 bucket (us-east-1) that holds the products of this satellite
+    pub fn bucket (&self)->&'static str {
+        match self {
+            Satellite::Goes16 => "noaa-goes16",
+            Satellite::Goes18 => "noaa-goes18",
+            Satellite::Goes19 => "noaa-goes19",
+        }
+    }
+
+    pub fn is_west (&self)->bool { *self == Satellite::Goes18 }
+
+    /// nominal sub-satellite longitude in degrees
+    pub fn longitude_of_projection_origin (&self)->f64 {
+        if self.is_west() { -137.2 } else { -75.2 }
+    }
+
+    pub fn projection (&self)->GoesrProjection {
+        GoesrProjection::for_satellite( *self)
+    }
+
+    /// check if the GOES-R file (or S3 object key) with the given name was produced by this satellite
+    pub fn check_file (&self, path: impl AsRef<Path>)->Result<()> {
+        let path = path.as_ref();
+        let info = parse_filename( path).ok_or_else( || filename_error( path.display()))?;
+        if info.sat_id == self.platform_id() {
+            Ok(())
+        } else {
+            Err( misc_error( format!("file {} is not from GOES-{}", path.display(), self.platform_id())))
+        }
+    }
+}
+
 /* #endregion GOES-R filename encoding */
 
 /* #region S3 support *************************************************************************************************/
 
+/// S3 client configuration for GOES-R data access. The NOAA buckets are public, hence the default is to use
+/// anonymous (unsigned) requests in us-east-1. Set `endpoint` to use a S3 compatible mirror
+#[derive(Serialize,Deserialize,Debug,Clone,PartialEq)]
+#[serde(default)]
+pub struct GoesrS3Config {
+    pub region: String,
+    pub anonymous: bool,
+    pub endpoint: Option<String>,
+}
+
+impl Default for GoesrS3Config {
+    fn default()->Self {
+        GoesrS3Config { region: "us-east-1".to_string(), anonymous: true, endpoint: None }
+    }
+}
+
+impl GoesrS3Config {
+    pub async fn create_client (&self)->Result<S3Client> {
+        Ok( create_s3_client_with( self.region.clone(), self.anonymous, self.endpoint.as_deref()).await? )
+    }
+}
+
 /// the S3 object prefix (some sort of a path) for GoesR. Built from year, day-of-year and hour
 fn get_prefix (dt: DateTime<Utc>, source: &str)->String {
     format!("{}/{}/{:03}/{:02}/", source, dt.year(), dt.ordinal(), dt.hour())
 }
 
+/// how long after the end of an hour we consider its S3 prefix to be complete (immutable). This accounts for the
+/// latency between scan start (which determines the prefix) and object upload
+const PREFIX_COMPLETION_MINUTES: i64 = 10;
+
+/// is the S3 prefix for the hour of `dt` complete at `now`, i.e. can we cache its listing
+pub fn is_completed_hour (dt: DateTime<Utc>, now: DateTime<Utc>)->bool {
+    now >= full_hour(&dt) + TimeDelta::hours(1) + TimeDelta::minutes(PREFIX_COMPLETION_MINUTES)
+}
+
 /// return all objects within the given duration, in ascending time order (newest last)
-/// Use this for getting initial data
-pub async fn get_most_recent_objects (client: &S3Client, bucket: &str, source: &str, dur: Duration, now: DateTime<Utc>) -> Result<Vec<S3Object>> {
+/// Use this for getting initial data. Listings of completed hours are served from `cache` if possible
+pub async fn get_most_recent_objects<L> (lister: &L, cache: &mut S3ListingCache, bucket: &str, source: &str, dur: Duration, now: DateTime<Utc>) -> Result<Vec<S3Object>>
+    where L: S3ObjectLister
+{
     let dt_start = now - dur;
     let hours = dur.as_secs() as i64/ 3600;
     let mut objects: Vec<S3Object> = Vec::with_capacity( 12 * (hours+1) as usize); // assuming update interval is 5min
@@ -324,7 +744,7 @@ pub async fn get_most_recent_objects (client: &S3Client, bucket: &str, source: &
     for h in (0..=hours).rev() {
         let dt = now - TimeDelta::hours(h);
         let prefix = get_prefix( dt, source);
-        let mut objs = get_s3_objects( client, bucket, &prefix, None).await?;
+        let objs = cache.list_objects( lister, bucket, &prefix, None, is_completed_hour( dt, now)).await?;
 
         for o in objs {
             if o.is_newer(dt_start)  {
@@ -333,12 +753,24 @@ pub async fn get_most_recent_objects (client: &S3Client, bucket: &str, source: &
         }
     }
 
+    sort_and_dedup_objects( &mut objects);
     Ok(objects)
 }
 
+/// sort objects in ascending order of the create time encoded in their keys (falling back to the modification date
+/// if the key can't be parsed) and remove duplicate keys, which can occur at hour boundaries
+fn sort_and_dedup_objects (objects: &mut Vec<S3Object>) {
+    let create_time = |o: &S3Object| o.key().and_then( parse_goesr_create_dtg).unwrap_or_else( || o.date());
+
+    objects.sort_by_cached_key( |o| (create_time(o), o.key().map( |k| k.to_string())));
+    objects.dedup_by( |a,b| a.key() == b.key());
+}
+
 /// return all objects since the given last one, in ascending time order (newest last)
-/// Use this for getting updates
-pub async fn get_objects_since_last (client: &S3Client, bucket: &str, source: &str, last_obj: &S3Object, now: DateTime<Utc>)  -> Result<Vec<S3Object>> {
+/// Use this for getting updates. Listings of completed hours are served from `cache` if possible
+pub async fn get_objects_since_last<L> (lister: &L, cache: &mut S3ListingCache, bucket: &str, source: &str, last_obj: &S3Object, now: DateTime<Utc>)  -> Result<Vec<S3Object>> 
+    where L: S3ObjectLister
+{
     let key = last_obj.key().ok_or(OdinGoesrError::NoObjectKeyError())?;
     let dt_start = parse_goesr_create_dtg(key).ok_or(OdinGoesrError::NoObjectDateError())?;
     let hours = (full_hour(&now) - full_hour(&dt_start)).num_hours();
@@ -349,7 +781,7 @@ pub async fn get_objects_since_last (client: &S3Client, bucket: &str, source: &s
         let prefix = get_prefix( dt, source);
         let marker = if h == hours { Some(key) } else { None };
 
-        let mut objs = get_s3_objects( client, bucket, &prefix, marker).await?;
+        let objs = cache.list_objects( lister, bucket, &prefix, marker, is_completed_hour( dt, now)).await?;
         for o in objs {
             if o.is_newer(dt_start) && o.is_older_or_equal(now) {
                 objects.push(o)
@@ -361,21 +793,28 @@ pub async fn get_objects_since_last (client: &S3Client, bucket: &str, source: &s
 }
 
 // get all S3Objects either from last downloaded one or as a fallback since the provided DateTime<Utc>
-pub async fn get_objects_since (client: &S3Client, bucket: &str, source: &str, last_obj: &Option<S3Object>, dt: DateTime<Utc>, now: DateTime<Utc>)->Result<Vec<S3Object>> {
+pub async fn get_objects_since<L> (lister: &L, cache: &mut S3ListingCache, bucket: &str, source: &str, last_obj: &Option<S3Object>, dt: DateTime<Utc>, now: DateTime<Utc>)->Result<Vec<S3Object>> 
+    where L: S3ObjectLister
+{
     if let Some(last_obj) = last_obj {
-        get_objects_since_last( &client, bucket, &source, &last_obj, now).await
+        get_objects_since_last( lister, cache, bucket, &source, &last_obj, now).await
     } else {
-        get_most_recent_objects( &client, bucket, &source, (now - dt).to_std()?, now).await
+        get_most_recent_objects( lister, cache, bucket, &source, (now - dt).to_std()?, now).await
     }
 }
 
-pub async fn download_and_read_objects (client: &S3Client, bucket: &str, source: &Arc<String>, sat_id: u32, data_dir: &PathBuf, objs: &Vec<S3Object>) -> Result<Vec<GoesrHotspotSet>> {
+pub async fn download_and_read_objects (client: &S3Client, satellite: Satellite, source: &Arc<String>, mask_class: GoesrMaskClass, 
+                                        min_class: Option<FirePixelClass>, data_dir: &PathBuf, objs: &Vec<S3Object>) -> Result<Vec<GoesrHotspotSet>> {
     let mut hotspots: Vec<GoesrHotspotSet> = Vec::with_capacity(objs.len());
 
     for obj in objs {
-        let gdata = get_goesr_data( client, obj, data_dir, bucket, source.clone(), sat_id).await?;
+        let gdata = get_goesr_data( client, obj, data_dir, satellite, source.clone()).await?;
         match read_goesr_data( &gdata) {
-            Ok(hs) => hotspots.push(hs),
+            Ok(mut hs) => {
+                hs.retain_mask_class( mask_class);
+                if let Some(min_class) = min_class { hs.retain_min_class( min_class) }
+                hotspots.push(hs)
+            }
             Err(e) => warn!("error parsing GOES-R data: {e:?}")
         }
     }
@@ -383,11 +822,15 @@ pub async fn download_and_read_objects (client: &S3Client, bucket: &str, source:
     Ok( hotspots )
 }
 
-pub async fn get_goesr_data (client: &S3Client, obj: &S3Object, path: &PathBuf, bucket: &str, source: Arc<String>, sat_id: u32) -> Result<GoesrData>{
+/// download the given object from the bucket of `satellite`. Returns an error if the object is not from this satellite
+pub async fn get_goesr_data (client: &S3Client, obj: &S3Object, path: &PathBuf, satellite: Satellite, source: Arc<String>) -> Result<GoesrData>{
+    let key = obj.key().ok_or(OdinGoesrError::NoObjectKeyError())?;
+    satellite.check_file( key)?;
+
     if obj.is_dated() {
         let date = obj.date();
-        let file = download_s3_object(client, bucket, obj, path).await?;
-        let data = GoesrData{sat_id, file, source, date};
+        let file = download_s3_object(client, satellite.bucket(), obj, path).await?;
+        let data = GoesrData{sat_id: satellite.sat_id(), file, source, date};
         Ok(data)
     } else {
         Err( OdinGoesrError::NoObjectDateError())
@@ -402,7 +845,7 @@ pub async fn get_goesr_data (client: &S3Client, obj: &S3Object, path: &PathBuf,
 fn find_fire_pixels_in_slice (i1: usize, row: &[u16], grid_points: &mut Vec<GridPoint<u16>>) {
     for i0 in 0..row.len() {
         let mask = row[i0];
-        if mask >= 10 && mask <= 35 {
+        if classify_mask( mask).is_fire() {
             grid_points.push( GridPoint{i0,i1,value: mask})
         }
     }
@@ -424,10 +867,13 @@ pub fn read_goesr_data (data: &GoesrData) -> Result<GoesrHotspotSet> {
     let mut hotspots: Vec<GoesrHotspot> = Vec::with_capacity(hs.len());
     for (i,p) in hs.iter().enumerate() {
         let center = proj.lat_lon_from_instrument_angles(x_range.at(p.i0), y_range.at(p.i1));
-        let bounds = get_bounds( &proj, &x_range, &y_range, &p);
+        let grid_cell = GoesrGridCell::new( &x_range, &y_range, &p);
+        let [nw,ne,se,sw] = grid_cell.corners( &proj);
+        let bounds = GoesrBoundingBox{ne,nw,sw,se};
 
         if !temp[i].is_nan() {
-            let hotspot = GoesrHotspot::new( data, p.value, temp[i] as u16, power[i], dqf[i], area[i] as u16, bounds, center);
+            let hotspot = GoesrHotspot::new( data, p.value, temp[i] as u16, power[i], dqf[i], area[i] as u16, bounds, center)
+                .with_grid_cell( grid_cell);
             hotspots.push( hotspot)
         }
     }
@@ -436,3 +882,128 @@ pub fn read_goesr_data (data: &GoesrData) -> Result<GoesrHotspotSet> {
 }
 
 /* #endregion hotspot parsing */
+
+/* #region hotspot rasterization *******************************************************************************************/
+
+/// sum up hotspot FRP values (as read from the data set) per cell of a `ny` x `nx` grid covering the given bbox (in degrees).
+/// Row 0 is the northern edge. Hotspots outside of the bbox are skipped. Empty grids and degenerate (zero width or
+/// height) bboxes result in an all-zero grid
+pub fn accumulate_frp (hotspots: &[GoesrHotspot], bbox: &BoundingBox<f64>, nx: usize, ny: usize) -> Array2<f32> {
+    let mut grid = Array2::<f32>::zeros( (ny, nx));
+    if nx == 0 || ny == 0 || !(bbox.east > bbox.west && bbox.north > bbox.south) { return grid }
+
+    let dx = (bbox.east - bbox.west) / nx as f64;
+    let dy = (bbox.north - bbox.south) / ny as f64;
+
+    for h in hotspots {
+        let lat = h.position.lat_deg;
+        let lon = h.position.lon_deg;
+
+        if lon >= bbox.west && lon <= bbox.east && lat >= bbox.south && lat <= bbox.north {
+            let ix = (((lon - bbox.west) / dx) as usize).min(nx-1); // eastern edge goes into the last column
+            let iy = (((bbox.north - lat) / dy) as usize).min(ny-1); // southern edge goes into the last row
+            grid[[iy,ix]] += h.frp.get::<milliwatt>();
+        }
+    }
+
+    grid
+}
+
+/* #endregion hotspot rasterization */
+
+/* #region hotspot clustering *********************************************************************************************/
+
+/// aggregate of spatially adjacent hotspots
+#[derive(Debug,Clone, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct HotspotCluster {
+    pub count: usize,
+    pub total_frp: Power,
+    pub bounds: BoundingBox<f64>, // in degrees
+    pub centroid: LatLon
+}
+
+impl HotspotCluster {
+    fn from_hotspots (hotspots: &[&GoesrHotspot])->Self {
+        let mut frp = 0.0f32;
+        let mut bounds = BoundingBox::new( f64::MAX, f64::MAX, f64::MIN, f64::MIN);
+        let mut lat_sum = 0.0;
+        let mut lon_sum = 0.0;
+
+        for h in hotspots {
+            let LatLon{lat_deg,lon_deg} = h.position;
+            frp += h.frp.get::<milliwatt>();
+            bounds.west = bounds.west.min( lon_deg);
+            bounds.east = bounds.east.max( lon_deg);
+            bounds.south = bounds.south.min( lat_deg);
+            bounds.north = bounds.north.max( lat_deg);
+            lat_sum += lat_deg;
+            lon_sum += lon_deg;
+        }
+
+        let n = hotspots.len() as f64;
+        HotspotCluster {
+            count: hotspots.len(),
+            total_frp: Power::new::<milliwatt>(frp),
+            bounds,
+            centroid: LatLon::from_degrees( lat_sum / n, lon_sum / n)
+        }
+    }
+}
+
+/// group hotspots into clusters using single-link clustering, i.e. two hotspots end up in the same cluster if there is
+/// a chain of hotspots between them with each link being no longer than `max_distance_m`. Candidate pairs are taken
+/// from a lat/lon grid with cells of at least `max_distance_m` so that we don't have to compare all hotspots.
+/// Note this does not handle clusters that cross the antimeridian. Clusters are returned in order of their first hotspot
+pub fn cluster_hotspots (hotspots: &[GoesrHotspot], max_distance_m: f64) -> Vec<HotspotCluster> {
+    if hotspots.is_empty() { return Vec::new() }
+
+    let max_lat = hotspots.iter().map( |h| h.position.lat_deg.abs()).fold( 0.0, f64::max).min( 89.0);
+    let d_lat = max_distance_m.max(1.0) / MEAN_EARTH_RADIUS.to_radians(); // cell height in degrees
+    let d_lon = d_lat / max_lat.to_radians().cos(); // longitude degrees shrink towards the poles
+    let cell = |h: &GoesrHotspot| ((h.position.lat_deg / d_lat).floor() as i64, (h.position.lon_deg / d_lon).floor() as i64);
+
+    let mut grid: HashMap<(i64,i64),Vec<usize>> = HashMap::new();
+    for (i,h) in hotspots.iter().enumerate() {
+        grid.entry( cell(h)).or_default().push(i);
+    }
+
+    //--- union-find over hotspot indices
+    let mut parent: Vec<usize> = (0..hotspots.len()).collect();
+    fn root (parent: &mut Vec<usize>, mut i: usize)->usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]]; // path halving
+            i = parent[i];
+        }
+        i
+    }
+
+    for (i,h) in hotspots.iter().enumerate() {
+        let (ci,cj) = cell(h);
+        for di in -1..=1 {
+            for dj in -1..=1 {
+                if let Some(candidates) = grid.get( &(ci+di, cj+dj)) {
+                    for &j in candidates.iter().filter( |&&j| j > i) {
+                        if haversine_distance( &h.position, &hotspots[j].position) <= max_distance_m {
+                            let (ri,rj) = (root( &mut parent, i), root( &mut parent, j));
+                            if ri != rj { parent[ri.max(rj)] = ri.min(rj) }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    //--- collect members (roots are the smallest index of each cluster, hence this preserves first-hotspot order)
+    let mut members: Vec<Vec<&GoesrHotspot>> = Vec::new();
+    let mut cluster_idx: HashMap<usize,usize> = HashMap::new();
+    for (i,h) in hotspots.iter().enumerate() {
+        let r = root( &mut parent, i);
+        let idx = *cluster_idx.entry(r).or_insert_with( || { members.push( Vec::new()); members.len()-1 });
+        members[idx].push(h);
+    }
+
+    members.iter().map( |m| HotspotCluster::from_hotspots( m)).collect()
+}
+
+/* #endregion hotspot clustering */
@@ -15,24 +15,33 @@
 use crate::*;
 use odin_actor::ObjSafeFuture;
 use odin_common::fs::ensure_writable_dir;
-use odin_common::s3::{create_s3_client, get_s3_objects, get_last_s3_object};
+use odin_common::s3::{get_s3_objects, get_last_s3_object};
 use odin_common::schedule::{get_hourly_schedule,Compaction,get_next_hourly_event_dtg};
 use std::{path::Path,time::Instant};
 
-/// configuration for live GoesR FDCC hotspot import
+/// configuration for live GoesR FDC hotspot import
 #[derive(Serialize,Deserialize,Debug,Clone)]
 pub struct LiveGoesrHotspotImporterConfig {
-    pub sat_id: u32,  // SATCAT # (e.g. 51850 for GOES-18)
-    pub s3_region: String, // e.g. "us-east-1"
-    pub bucket: String, // e.g. "noaa-goes18"
-    pub source: String, // e.g. "ABI-L2-FDCC"
+    pub satellite: Satellite, // GOES16, GOES18 (West) or GOES19 (East), determines bucket and projection
+    #[serde(default)]
+    pub s3: GoesrS3Config, // defaults to anonymous access in us-east-1
+    #[serde(default)]
+    pub product: GoesrProduct, // FDCC (CONUS), FDCF (full disk) or FDCM (mesoscale)
     pub keep_files: bool,
     pub init_files: usize, // number of most recent data files to retrieve on initialization
     pub cleanup_interval: Duration,
     pub max_age: Duration,
+    #[serde(default)]
+    pub mask_class: GoesrMaskClass, // which fire pixel masks to keep (All, Instantaneous or TemporallyFiltered)
+    #[serde(default)]
+    pub min_class: Option<FirePixelClass>, // minimum fire pixel confidence to keep (stats still include all pixels)
+    #[serde(default="default_max_cached_prefixes")]
+    pub max_cached_prefixes: usize, // how many listings of completed hours we keep (0 disables caching)
 }
 
-/// the structure representing objects to collect and announce availability of live GoesR FDCC fire product data (hotspots)
+fn default_max_cached_prefixes()->usize { 8 }
+
+/// the structure representing objects to collect and announce availability of live GoesR FDC fire product data (hotspots)
 /// 
 /// (REQ) instance should check availability of new data sets on a guaranteed time interval
 /// (REQ) instance should not miss any available data set once initialized 
@@ -57,7 +66,7 @@ impl LiveGoesrHotspotImporter {
     async fn initialize  (&mut self, hself: ActorHandle<GoesrHotspotImportActorMsg>) -> Result<()> { 
         let config = &self.config;
         let init_files = config.init_files;
-        let s3_client = create_s3_client( config.s3_region.clone()).await?;
+        let s3_client = config.s3.create_client().await?;
 
         self.import_task = Some( self.spawn_import_task( s3_client, hself)? );
         self.file_cleanup_task = Some( self.spawn_file_cleanup_task()? );
@@ -68,7 +77,7 @@ impl LiveGoesrHotspotImporter {
         let data_dir = self.cache_dir.clone();
         let config = self.config.clone();
 
-        Ok( spawn( &format!("goes-{}-data-acquisition", self.config.sat_id), async move {
+        Ok( spawn( &format!("goes-{}-data-acquisition", self.config.satellite.platform_id()), async move {
                 run_data_acquisition( hself, config, data_dir, client).await
             })?.abort_handle()
         )
@@ -79,7 +88,7 @@ impl LiveGoesrHotspotImporter {
         let cleanup_interval = self.config.cleanup_interval;
         let max_age = self.config.max_age;
 
-        Ok( spawn( &format!("goes-{}-file-cleanup", self.config.sat_id), async move {
+        Ok( spawn( &format!("goes-{}-file-cleanup", self.config.satellite.platform_id()), async move {
                 run_file_cleanup( cache_dir, cleanup_interval, max_age).await
             })?.abort_handle()
         )
@@ -100,20 +109,25 @@ impl GoesrHotspotImporter for LiveGoesrHotspotImporter {
 
 async fn run_data_acquisition (hself: ActorHandle<GoesrHotspotImportActorMsg>, config: LiveGoesrHotspotImporterConfig, cache_dir: Arc<PathBuf>, client: S3Client)->Result<()> 
 {
-    let source = Arc::new( config.source); // no need to keep gazillions of copies
-    let bucket = &config.bucket;
-    let sat_id = config.sat_id;
+    let source = Arc::new( config.product.source().to_string()); // no need to keep gazillions of copies
+    let satellite = config.satellite;
+    let bucket = satellite.bucket();
+    let mask_class = config.mask_class;
+    let min_class = config.min_class;
     let mut last_obj: Option<S3Object> = None;
+    let mut listing_cache = S3ListingCache::new( config.max_cached_prefixes);
 
     //--- get 3h most recent object entries so that we can build a schedule
-    let mut objs = get_most_recent_objects( &client, &config.bucket, &source, Duration::from_hours(3), Utc::now()).await?;
-    if objs.len() < 12 { return Err(no_object_error("not enough initial objects")) }
+    let lookback = Duration::from_hours(3);
+    let mut objs = get_most_recent_objects( &client, &mut listing_cache, bucket, &source, lookback, Utc::now()).await?;
+    let min_objs = (lookback.as_secs() / config.product.scan_interval().as_secs() / 3) as usize; // at least a third of the nominal scans
+    if objs.len() < min_objs { return Err(no_object_error("not enough initial objects")) }
 
     let hourly_schedule = get_hourly_schedule(&objs, Some(Compaction::BoundedRightEdge(3)));
     let mut init_objs = if objs.len() > config.init_files { objs.split_off( objs.len()-config.init_files) } else { objs };
 
     //--- now get the initial files and send an Initialize msg with the hotspots read from them
-    let hotspots = download_and_read_objects( &client, bucket, &source, sat_id, &cache_dir, &init_objs).await?;
+    let hotspots = download_and_read_objects( &client, satellite, &source, mask_class, min_class, &cache_dir, &init_objs).await?;
     last_obj = init_objs.pop();
     hself.send_msg( Initialize(hotspots) ).await;
 
@@ -123,10 +137,10 @@ async fn run_data_acquisition (hself: ActorHandle<GoesrHotspotImportActorMsg>, c
         let dt_next = get_next_hourly_event_dtg( dt_cycle, &hourly_schedule);
         sleep( (dt_next - dt_cycle).to_std()?).await;
 
-        let mut update_objs = get_objects_since( &client, &config.bucket, &source, &last_obj, dt_cycle, Utc::now()).await?;
+        let mut update_objs = get_objects_since( &client, &mut listing_cache, bucket, &source, &last_obj, dt_cycle, Utc::now()).await?;
         // here we could dynamically re-compute/adapt the hourly_schedule if we repeatedly get multiple objects
 
-        let mut hotspots = download_and_read_objects( &client, bucket, &source, sat_id, &cache_dir, &update_objs).await?;
+        let mut hotspots = download_and_read_objects( &client, satellite, &source, mask_class, min_class, &cache_dir, &update_objs).await?;
         last_obj = update_objs.pop().or( last_obj);
 
         for hs in hotspots {
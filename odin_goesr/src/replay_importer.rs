@@ -0,0 +1,111 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of 
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License"); 
+ * you may not use this file except in compliance with the License. You may obtain a copy 
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+use crate::*;
+use std::path::Path;
+
+/// configuration for replaying previously downloaded GoesR FDC files from a local directory
+#[derive(Serialize,Deserialize,Debug,Clone)]
+pub struct ReplayGoesrHotspotImporterConfig {
+    pub satellite: Satellite, // files from other satellites are ignored
+    #[serde(default)]
+    pub product: GoesrProduct,
+    pub data_dir: PathBuf, // directory with the *.nc files to replay
+    pub cadence: Duration, // simulated time between consecutive hotspot sets
+    #[serde(default)]
+    pub mask_class: GoesrMaskClass,
+    #[serde(default)]
+    pub min_class: Option<FirePixelClass>,
+}
+
+/// a [`GoesrHotspotImporter`] that replays the GoesR files of a local directory in order of their creation time,
+/// sending one [`GoesrHotspotSet`] per file with the configured cadence. This is mostly used for testing and post-incident
+/// analysis. The reader function can be replaced (e.g. to replay files that are not NetCDF)
+pub struct ReplayGoesrHotspotImporter<R=fn(&GoesrData)->Result<GoesrHotspotSet>>
+    where R: Fn(&GoesrData)->Result<GoesrHotspotSet> + Clone + Send + Sync + 'static
+{
+    config: ReplayGoesrHotspotImporterConfig,
+    reader: R,
+    import_task: Option<AbortHandle>,
+}
+
+impl ReplayGoesrHotspotImporter {
+    pub fn new (config: ReplayGoesrHotspotImporterConfig)->Self {
+        Self::with_reader( config, read_goesr_data)
+    }
+}
+
+impl<R> ReplayGoesrHotspotImporter<R> where R: Fn(&GoesrData)->Result<GoesrHotspotSet> + Clone + Send + Sync + 'static {
+    pub fn with_reader (config: ReplayGoesrHotspotImporterConfig, reader: R)->Self {
+        ReplayGoesrHotspotImporter { config, reader, import_task: None }
+    }
+}
+
+impl<R> GoesrHotspotImporter for ReplayGoesrHotspotImporter<R> where R: Fn(&GoesrData)->Result<GoesrHotspotSet> + Clone + Send + Sync + 'static {
+    async fn start (&mut self, hself: ActorHandle<GoesrHotspotImportActorMsg>) -> Result<()> {
+        let config = self.config.clone();
+        let reader = self.reader.clone();
+
+        self.import_task = Some( spawn( &format!("goes-{}-replay", config.satellite.platform_id()), async move {
+                run_replay( hself, config, reader).await
+            })?.abort_handle()
+        );
+        Ok(())
+    }
+
+    fn terminate (&mut self) {
+        if let Some(task) = &self.import_task { task.abort() }
+    }
+}
+
+/// get the GoesR `product` files of `satellite` in `dir` together with their creation time, sorted oldest first.
+/// Files without a parseable creation time are skipped
+pub fn get_replay_files (dir: impl AsRef<Path>, satellite: Satellite, product: GoesrProduct)->Result<Vec<(PathBuf,DateTime<Utc>)>> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir( dir.as_ref())? {
+        let path = entry?.path();
+        if path.is_file() && path.extension().map_or( false, |ext| ext == "nc") && satellite.check_file( &path).is_ok()
+                && parse_filename( &path).and_then( |fi| GoesrProduct::from_product( &fi.product)) == Some(product) {
+            if let Some(date) = parse_goesr_create_dtg( &path) {
+                files.push( (path, date))
+            }
+        }
+    }
+    files.sort_by_key( |(_,date)| *date);
+    Ok(files)
+}
+
+async fn run_replay<R> (hself: ActorHandle<GoesrHotspotImportActorMsg>, config: ReplayGoesrHotspotImporterConfig, reader: R)->Result<()>
+    where R: Fn(&GoesrData)->Result<GoesrHotspotSet>
+{
+    let source = Arc::new( config.product.source().to_string());
+    let files = get_replay_files( &config.data_dir, config.satellite, config.product)?;
+    hself.send_msg( Initialize( Vec::new())).await?;
+
+    for (i, (file, date)) in files.into_iter().enumerate() {
+        if i > 0 { sleep( config.cadence).await; }
+
+        let data = GoesrData { sat_id: config.satellite.sat_id(), file, source: source.clone(), date };
+        match reader( &data) {
+            Ok(mut hs) => {
+                hs.retain_mask_class( config.mask_class);
+                if let Some(min_class) = config.min_class { hs.retain_min_class( min_class) }
+                hself.send_msg( Update(hs)).await?;
+            }
+            Err(e) => warn!("error reading GOES-R data from {:?}: {e:?}", data.file)
+        }
+    }
+
+    Ok(())
+}
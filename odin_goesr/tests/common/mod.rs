@@ -0,0 +1,44 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License. You may obtain a copy
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+// fixtures shared between test crates - not every test uses all of them
+#![allow(unused)]
+
+use std::path::PathBuf;
+use odin_common::geo::LatLon;
+use odin_goesr::{GoesrBoundingBox, GoesrData, GoesrHotspot};
+
+/// a hotspot at the given position with a small square bounding box
+pub fn hotspot (data: &GoesrData, lat_deg: f64, lon_deg: f64, mask: u16, frp: f32)->GoesrHotspot {
+    let d = 0.01;
+    let bounds = GoesrBoundingBox {
+        ne: LatLon::from_degrees( lat_deg + d, lon_deg + d),
+        nw: LatLon::from_degrees( lat_deg + d, lon_deg - d),
+        sw: LatLon::from_degrees( lat_deg - d, lon_deg - d),
+        se: LatLon::from_degrees( lat_deg - d, lon_deg + d),
+    };
+    GoesrHotspot::new( data, mask, 320, frp, 0, 4000, bounds, LatLon::from_degrees( lat_deg, lon_deg))
+}
+
+/// a fresh temp dir `name` that contains empty files with the given names
+pub fn replay_dir (name: &str, files: &[&str])->PathBuf {
+    let dir = std::env::temp_dir().join(name);
+    if dir.is_dir() { std::fs::remove_dir_all( &dir).unwrap() }
+    std::fs::create_dir_all( &dir).unwrap();
+
+    for file in files {
+        std::fs::write( dir.join(file), b"").unwrap();
+    }
+    dir
+}
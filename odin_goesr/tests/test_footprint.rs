@@ -0,0 +1,87 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License. You may obtain a copy
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+use std::{path::PathBuf, sync::Arc};
+use chrono::{DateTime,Utc};
+use odin_common::geo::{LatLon,haversine_distance};
+use odin_goesr::{GoesrBoundingBox, GoesrData, GoesrGridCell, GoesrHotspot, Satellite};
+
+const PIXEL_ANGLE: f64 = 0.000056; // radians of the 2km ABI fire products
+
+fn hotspot (x: f64, y: f64)->GoesrHotspot {
+    let data = GoesrData {
+        sat_id: 60133,
+        file: PathBuf::from("OR_ABI-L2-FDCF-M6_G19_s20250010000205_e20250010009513_c20250010010060.nc"),
+        source: Arc::new("ABI-L2-FDCF".to_string()),
+        date: DateTime::<Utc>::from_timestamp( 1735689600, 0).unwrap()
+    };
+    let p = LatLon::from_degrees( 0.0, 0.0); // not used for footprints
+    let bounds = GoesrBoundingBox { ne: p, nw: p, sw: p, se: p };
+    let grid_cell = GoesrGridCell { i0: 0, i1: 0, x, y, dx: PIXEL_ANGLE, dy: -PIXEL_ANGLE };
+
+    GoesrHotspot::new( &data, 10, 320, 1.0e9, 0, 4000, bounds, p).with_grid_cell( grid_cell)
+}
+
+/// (east-west, north-south) extent of the footprint in meters
+fn footprint_extent (corners: &[LatLon;4])->(f64,f64) {
+    let [nw,ne,se,sw] = corners;
+    let ew = (haversine_distance( nw, ne) + haversine_distance( sw, se)) / 2.0;
+    let ns = (haversine_distance( nw, sw) + haversine_distance( ne, se)) / 2.0;
+    (ew, ns)
+}
+
+#[test]
+fn test_footprint_nadir_vs_limb() {
+    let proj = Satellite::Goes19.projection();
+
+    let nadir = hotspot( 0.0, 0.0).footprint_polygon( &proj).unwrap();
+    let (nadir_ew, nadir_ns) = footprint_extent( &nadir);
+    println!("nadir footprint: {nadir:?} -> {nadir_ew:.0}m x {nadir_ns:.0}m");
+
+    // nadir pixels are close to 2km squares centered at the sub-satellite point
+    assert!( (nadir_ew - 2000.0).abs() < 100.0);
+    assert!( (nadir_ns - 2000.0).abs() < 100.0);
+    assert!( nadir[0].lat_deg > 0.0 && nadir[0].lon_deg < -75.2);
+    assert!( nadir[2].lat_deg < 0.0 && nadir[2].lon_deg > -75.2);
+
+    // close to the eastern limb (which is at about 0.1518 rad) pixels get stretched in scan direction
+    let limb = hotspot( 0.145, 0.0).footprint_polygon( &proj).unwrap();
+    let (limb_ew, limb_ns) = footprint_extent( &limb);
+    println!("limb footprint: {limb:?} -> {limb_ew:.0}m x {limb_ns:.0}m");
+
+    assert!( limb_ew > 2.0 * nadir_ew);
+    assert!( limb_ew / limb_ns > 2.0);
+    assert!( limb_ns > nadir_ns);
+}
+
+#[test]
+fn test_footprint_matches_bounds() {
+    let proj = Satellite::Goes19.projection();
+    let h = hotspot( 0.05, 0.08);
+    let [nw,ne,se,sw] = h.footprint_polygon( &proj).unwrap();
+    let cell = h.grid_cell.unwrap();
+
+    assert_eq!( nw, proj.lat_lon_from_instrument_angles( cell.x - PIXEL_ANGLE/2.0, cell.y + PIXEL_ANGLE/2.0));
+    assert_eq!( se, proj.lat_lon_from_instrument_angles( cell.x + PIXEL_ANGLE/2.0, cell.y - PIXEL_ANGLE/2.0));
+    assert!( ne.lon_deg > nw.lon_deg && sw.lat_deg < nw.lat_deg);
+}
+
+#[test]
+fn test_footprint_without_grid_cell() {
+    let proj = Satellite::Goes19.projection();
+    let mut h = hotspot( 0.05, 0.08);
+    h.grid_cell = None; // not read from a data set
+
+    assert!( h.footprint_polygon( &proj).is_none());
+}
@@ -0,0 +1,283 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License. You may obtain a copy
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+use std::{path::PathBuf, sync::Arc};
+use chrono::{DateTime,Utc};
+use uom::si::power::milliwatt;
+use odin_common::geo::{BoundingBox,LatLon,haversine_distance};
+use odin_goesr::{GoesrData, GoesrHotspot, GoesrHotspotSet, GoesrHotspotStore, GoesrBoundingBox, GoesrMaskClass, FirePixelClass, accumulate_frp, classify_mask, cluster_hotspots};
+
+mod common;
+use common::hotspot;
+
+fn goesr_data (secs: i64)->GoesrData {
+    GoesrData {
+        sat_id: 51850,
+        file: PathBuf::from("OR_ABI-L2-FDCC-M6_G18_s20241380556172_e20241380558545_c20241380559122.nc"),
+        source: Arc::new("ABI-L2-FDCC".to_string()),
+        date: DateTime::<Utc>::from_timestamp(secs, 0).unwrap()
+    }
+}
+
+fn hotspot_set (secs: i64, positions: &[(f64,f64)])->GoesrHotspotSet {
+    let data = goesr_data(secs);
+    let hotspots = positions.iter().map( |(lat,lon)| hotspot( &data, *lat, *lon, 13, 1.5e9)).collect();
+    GoesrHotspotSet::new( &data, hotspots)
+}
+
+#[test]
+fn test_write_geojson() {
+    let hs = hotspot_set( 1_700_000_000, &[(37.0,-121.0), (37.1,-121.2), (38.5,-120.0)]);
+
+    let mut buf: Vec<u8> = Vec::new();
+    hs.write_geojson_to( &mut buf).unwrap();
+
+    let v: serde_json::Value = serde_json::from_slice( &buf).unwrap();
+    assert_eq!( v["type"], "FeatureCollection");
+
+    let features = v["features"].as_array().unwrap();
+    assert_eq!( features.len(), hs.hotspots.len());
+    assert_eq!( features[1]["geometry"]["coordinates"][0].as_f64().unwrap(), -121.2);
+    assert_eq!( features[1]["geometry"]["coordinates"][1].as_f64().unwrap(), 37.1);
+}
+
+#[test]
+fn test_to_geojson() {
+    let hs = hotspot_set( 1_700_000_000, &[(37.0,-121.0), (37.1,-121.2)]);
+
+    let v: serde_json::Value = serde_json::from_str( &hs.to_geojson().unwrap()).unwrap();
+    assert_eq!( v["type"], "FeatureCollection");
+
+    let features = v["features"].as_array().unwrap();
+    assert_eq!( features.len(), hs.hotspots.len());
+
+    let props = &features[0]["properties"];
+    assert!( (props["frp"].as_f64().unwrap() - 1.5e6).abs() < 1.0); // serialized in W, GoesrHotspot::new takes mW (modulo f32 conversion)
+    assert_eq!( props["mask"], 13);
+    assert_eq!( features[1]["geometry"]["coordinates"][0].as_f64().unwrap(), -121.2);
+}
+
+fn mixed_mask_set ()->GoesrHotspotSet {
+    let data = goesr_data( 1_700_000_000);
+    let hotspots = vec![
+        hotspot( &data, 37.0, -121.0, 10, 1.0e9), // good
+        hotspot( &data, 37.1, -121.1, 13, 1.0e9), // high probability
+        hotspot( &data, 37.2, -121.2, 15, 1.0e9), // low probability
+        hotspot( &data, 37.3, -121.3, 30, 1.0e9), // temporally filtered good
+        hotspot( &data, 37.4, -121.4, 34, 1.0e9), // temporally filtered medium probability
+    ];
+    GoesrHotspotSet::new( &data, hotspots)
+}
+
+#[test]
+fn test_retain_instantaneous() {
+    let mut hs = mixed_mask_set();
+    assert_eq!( (hs.n_good, hs.n_high, hs.n_medium, hs.n_low), (2, 1, 1, 1));
+
+    hs.retain_mask_class( GoesrMaskClass::Instantaneous);
+    assert_eq!( hs.hotspots.len(), 3);
+    assert!( hs.hotspots.iter().all( |h| !h.is_temporally_filtered()));
+    assert_eq!( (hs.n_good, hs.n_high, hs.n_medium, hs.n_low), (1, 1, 0, 1));
+}
+
+#[test]
+fn test_retain_temporally_filtered() {
+    let mut hs = mixed_mask_set();
+
+    hs.retain_mask_class( GoesrMaskClass::TemporallyFiltered);
+    assert_eq!( hs.hotspots.len(), 2);
+    assert!( hs.hotspots.iter().all( |h| h.is_temporally_filtered()));
+    assert_eq!( (hs.n_good, hs.n_high, hs.n_medium, hs.n_low), (1, 0, 1, 0));
+}
+
+#[test]
+fn test_accumulate_frp() {
+    let data = goesr_data( 1_700_000_000);
+    let hotspots = vec![
+        hotspot( &data, 37.9, -121.9, 13, 10.0), // NW cell
+        hotspot( &data, 37.8, -121.6, 13, 5.0),  // NW cell
+        hotspot( &data, 36.1, -120.1, 13, 7.0),  // SE cell
+        hotspot( &data, 40.0, -121.0, 13, 100.0), // outside
+    ];
+    let bbox = BoundingBox::new( -122.0, 36.0, -120.0, 38.0);
+
+    let grid = accumulate_frp( &hotspots, &bbox, 2, 2);
+    assert_eq!( grid.dim(), (2,2));
+    assert_eq!( grid[[0,0]], 15.0);
+    assert_eq!( grid[[0,1]], 0.0);
+    assert_eq!( grid[[1,0]], 0.0);
+    assert_eq!( grid[[1,1]], 7.0);
+
+    // degenerate grids and bboxes don't panic
+    assert_eq!( accumulate_frp( &hotspots, &bbox, 0, 2).dim(), (2,0));
+    assert_eq!( accumulate_frp( &hotspots, &bbox, 2, 0).dim(), (0,2));
+    let line = BoundingBox::new( -121.9, 36.0, -121.9, 38.0);
+    assert!( accumulate_frp( &hotspots, &line, 2, 2).iter().all( |v| *v == 0.0));
+}
+
+fn populated_store ()->GoesrHotspotStore {
+    let mut store = GoesrHotspotStore::new(3);
+    store.initialize_hotspots( vec![
+        hotspot_set( 1_700_000_000, &[(37.0,-121.0)]), // dropped because of capacity
+        hotspot_set( 1_700_000_300, &[(37.1,-121.1), (40.0,-110.0)]),
+        hotspot_set( 1_700_000_900, &[(37.2,-121.2)]), // out of order on purpose
+        hotspot_set( 1_700_000_600, &[(37.3,-121.3), (41.0,-111.0)]),
+    ]);
+    store
+}
+
+#[test]
+fn test_sets_since() {
+    let store = populated_store();
+    let dt = |secs| DateTime::<Utc>::from_timestamp( secs, 0).unwrap();
+
+    let dates: Vec<DateTime<Utc>> = store.sets_since( dt(1_700_000_300)).map( |hs| hs.date).collect();
+    assert_eq!( dates, vec![ dt(1_700_000_600), dt(1_700_000_900)]);
+
+    assert_eq!( store.sets_since( dt(1_600_000_000)).count(), 3);
+    assert_eq!( store.sets_since( dt(1_700_000_900)).count(), 0);
+}
+
+#[test]
+fn test_hotspots_in_bbox() {
+    let store = populated_store();
+    let bbox = GoesrBoundingBox {
+        ne: LatLon::from_degrees( 38.0, -120.0),
+        nw: LatLon::from_degrees( 38.0, -122.0),
+        sw: LatLon::from_degrees( 36.0, -122.0),
+        se: LatLon::from_degrees( 36.0, -120.0),
+    };
+
+    let hotspots = store.hotspots_in_bbox( &bbox);
+    let lats: Vec<f64> = hotspots.iter().map( |h| h.position.lat_deg).collect();
+    assert_eq!( lats, vec![37.2, 37.3, 37.1]); // newest set first, dropped set not included
+
+    let far_away = GoesrBoundingBox {
+        ne: LatLon::from_degrees( 11.0, 11.0),
+        nw: LatLon::from_degrees( 11.0, 10.0),
+        sw: LatLon::from_degrees( 10.0, 10.0),
+        se: LatLon::from_degrees( 10.0, 11.0),
+    };
+    assert!( store.hotspots_in_bbox( &far_away).is_empty());
+}
+
+fn blob (data: &GoesrData, lat_deg: f64, lon_deg: f64)->Vec<GoesrHotspot> {
+    let mut hotspots = Vec::new();
+    for i in 0..3 {
+        for j in 0..3 { // 3x3 pixels ~1km apart
+            hotspots.push( hotspot( data, lat_deg + i as f64 * 0.01, lon_deg + j as f64 * 0.01, 13, 1.0e6));
+        }
+    }
+    hotspots
+}
+
+#[test]
+fn test_cluster_hotspots() {
+    let data = goesr_data( 1_700_000_000);
+    let blob_a = blob( &data, 37.0, -121.0);
+    let blob_b = blob( &data, 37.5, -120.5);
+    let hotspots: Vec<GoesrHotspot> = blob_a.iter().chain( blob_b.iter()).cloned().collect();
+
+    let clusters = cluster_hotspots( &hotspots, 3_000.0);
+    assert_eq!( clusters.len(), 2);
+    assert_eq!( clusters[0].count, 9);
+    assert_eq!( clusters[1].count, 9);
+    assert!( (clusters[0].total_frp.get::<milliwatt>() - 9.0e6).abs() < 1.0);
+    assert!( (clusters[0].centroid.lat_deg - 37.01).abs() < 1e-9);
+    assert!( (clusters[0].centroid.lon_deg + 120.99).abs() < 1e-9);
+    assert_eq!( clusters[1].bounds.south, 37.5);
+    assert_eq!( clusters[1].bounds.west, -120.5);
+
+    // raising the threshold to the closest inter-blob distance merges both blobs
+    let d_min = blob_a.iter()
+        .flat_map( |a| blob_b.iter().map( |b| haversine_distance( &a.position, &b.position)))
+        .fold( f64::MAX, f64::min);
+    println!("closest inter-blob distance: {d_min:.0}m");
+
+    assert_eq!( cluster_hotspots( &hotspots, d_min - 1.0).len(), 2);
+    let clusters = cluster_hotspots( &hotspots, d_min);
+    assert_eq!( clusters.len(), 1);
+    assert_eq!( clusters[0].count, 18);
+}
+
+#[test]
+fn test_classify_mask() {
+    use FirePixelClass::*;
+    let expected = [
+        (10, Good), (11, Saturated), (12, CloudContaminated), (13, HighProbability), (14, MediumProbability), (15, LowProbability),
+        (30, TemporallyFilteredGood), (31, TemporallyFilteredSaturated), (32, TemporallyFilteredCloudContaminated),
+        (33, TemporallyFilteredHighProbability), (34, TemporallyFilteredMediumProbability), (35, TemporallyFilteredLowProbability),
+    ];
+    for (mask,class) in expected {
+        assert_eq!( classify_mask(mask), class, "mask {mask}");
+        assert!( class.is_fire());
+        assert_eq!( class.is_temporally_filtered(), mask >= 30);
+    }
+
+    for mask in (0..10).chain( 16..30).chain( [36, 40, 50, 100, 255, u16::MAX]) {
+        assert_eq!( classify_mask(mask), Unknown, "mask {mask}");
+    }
+
+    let data = goesr_data( 1_700_000_000);
+    let h = hotspot( &data, 37.0, -121.0, 34, 1.0e6);
+    assert_eq!( h.pixel_class(), TemporallyFilteredMediumProbability);
+    assert!( h.is_medium_probability_pixel() && h.is_temporally_filtered() && !h.is_good_pixel());
+}
+
+#[test]
+fn test_retain_min_class() {
+    let data = goesr_data( 1_700_000_000);
+    let mask_band: Vec<u16> = (0..40).collect(); // synthetic mask row that contains each fire pixel class plus non-fire values
+
+    let hotspots: Vec<GoesrHotspot> = mask_band.iter().enumerate()
+        .filter( |(_,mask)| classify_mask(**mask).is_fire())
+        .map( |(i,mask)| hotspot( &data, 37.0, -121.0 + i as f64 * 0.02, *mask, 1.0e6))
+        .collect();
+    assert_eq!( hotspots.len(), 12);
+
+    let mut hs = GoesrHotspotSet::new( &data, hotspots);
+    let stats = (hs.n_good, hs.n_high, hs.n_medium, hs.n_low);
+    assert_eq!( stats, (2, 2, 2, 2));
+
+    hs.retain_min_class( FirePixelClass::MediumProbability);
+    let masks: Vec<u16> = hs.hotspots.iter().map( |h| h.mask).collect();
+    assert_eq!( masks, vec![10, 11, 13, 14, 30, 31, 33, 34]); // low probability and cloud contaminated pixels are gone
+    assert!( hs.hotspots.iter().all( |h| h.pixel_class().meets_confidence( FirePixelClass::MediumProbability)));
+
+    // stats still reflect the unfiltered set
+    assert_eq!( (hs.n_good, hs.n_high, hs.n_medium, hs.n_low), stats);
+}
+
+#[test]
+fn test_unit_conversions() {
+    let data = goesr_data( 1_700_000_000);
+    let h = hotspot( &data, 37.0, -121.0, 10, 1.5e9); // 320K, 1.5e9 mW
+
+    assert!( (h.bright_kelvin() - 320.0).abs() < 1e-3);
+    assert!( (h.bright_celsius() - 46.85).abs() < 1e-3);
+    assert!( (h.frp_megawatts() - 1.5).abs() < 1e-5);
+}
+
+#[test]
+fn test_exceeds_thresholds() {
+    let data = goesr_data( 1_700_000_000);
+    let h = hotspot( &data, 37.0, -121.0, 10, 1.5e9);
+    let (bright_k, frp_mw) = (h.bright_kelvin(), h.frp_megawatts());
+
+    assert!( h.exceeds( bright_k, frp_mw)); // thresholds are inclusive
+    assert!( h.exceeds( 300.0, 1.0));
+    assert!( !h.exceeds( bright_k + 0.1, frp_mw));
+    assert!( !h.exceeds( bright_k, frp_mw + 0.01));
+    assert!( !h.exceeds( 400.0, 0.0));
+}
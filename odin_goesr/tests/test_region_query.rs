@@ -0,0 +1,99 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License. You may obtain a copy
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+#![allow(unused)]
+
+use std::{path::PathBuf, sync::Arc, time::Duration};
+use chrono::{DateTime,Utc};
+use odin_actor::prelude::*;
+use odin_common::geo::GeoBoundingBox;
+use odin_goesr::{parse_goesr_create_dtg, GetHotspotsInRegion, GoesrData, GoesrHotspot, GoesrHotspotActor, GoesrHotspotSet,
+    GoesrImportActorConfig, GoesrMaskClass, GoesrProduct, ReplayGoesrHotspotImporter, ReplayGoesrHotspotImporterConfig, Satellite};
+
+mod common;
+use common::{hotspot, replay_dir};
+
+const NEWER: &str = "OR_ABI-L2-FDCC-M6_G18_s20241380556172_e20241380558545_c20241380559122.nc";
+const OLDER: &str = "OR_ABI-L2-FDCC-M6_G18_s20241380551172_e20241380553545_c20241380554122.nc";
+
+const CA_POS: [(f64,f64);2] = [(37.0,-121.0), (37.2,-121.3)]; // northern California
+const OR_POS: [(f64,f64);1] = [(44.0,-120.5)];                // central Oregon
+
+// the files are empty - each one yields hotspots in both regions
+fn read_two_regions (data: &GoesrData)->odin_goesr::Result<GoesrHotspotSet> {
+    let hotspots = CA_POS.iter().chain( OR_POS.iter()).map( |(lat,lon)| hotspot( data, *lat, *lon, 13, 1.0e9)).collect();
+    Ok( GoesrHotspotSet::new( data, hotspots))
+}
+
+type QueryResult = odin_actor::errors::Result<Vec<GoesrHotspot>>;
+
+fn region_query (bbox: &GeoBoundingBox, since: Option<DateTime<Utc>>)->GetHotspotsInRegion {
+    GetHotspotsInRegion { bbox: *bbox, since }
+}
+
+#[tokio::test]
+async fn test_region_query()->odin_goesr::Result<()> {
+    let config = ReplayGoesrHotspotImporterConfig {
+        satellite: Satellite::Goes18,
+        product: GoesrProduct::Fdcc,
+        data_dir: replay_dir( "odin_goesr_region_query", &[NEWER, OLDER]),
+        cadence: Duration::from_millis(20),
+        mask_class: GoesrMaskClass::All,
+        min_class: None
+    };
+
+    let mut actor_system = ActorSystem::new("test");
+    let hgoesr = spawn_actor!( actor_system, "goesr", GoesrHotspotActor::new(
+        GoesrImportActorConfig { max_records: 10 },
+        ReplayGoesrHotspotImporter::with_reader( config, read_two_regions),
+        no_dataref_action(),
+        no_data_action()
+    ))?;
+
+    // queries have to run while the actor system (and hence our goesr actor) is still running
+    let hsys = actor_system.clone_handle();
+    let queries = spawn( "queries", async move {
+        sleep( millis(200)).await; // give the importer time to replay both files
+
+        let ca_bbox = GeoBoundingBox::from_wsen_degrees( &[-122.0, 36.5, -120.5, 38.0]);
+        let nv_bbox = GeoBoundingBox::from_wsen_degrees( &[-119.0, 38.0, -115.0, 42.0]);
+
+        let all: QueryResult = timeout_query_ref( &hgoesr, region_query( &ca_bbox, None), secs(1)).await;
+        let newer: QueryResult = timeout_query_ref( &hgoesr, region_query( &ca_bbox, parse_goesr_create_dtg( OLDER)), secs(1)).await;
+        let outside: QueryResult = timeout_query_ref( &hgoesr, region_query( &nv_bbox, None), secs(1)).await;
+
+        hsys.request_termination( secs(1)).await.ok();
+        (all, newer, outside)
+    })?;
+
+    actor_system.timeout_start_all( millis(20)).await?;
+    actor_system.process_requests().await?;
+
+    let (all, newer, outside) = queries.await.unwrap();
+
+    let hotspots: Vec<GoesrHotspot> = all?;
+    println!("CA hotspots: {:?}", hotspots.iter().map(|h| (h.date, h.position)).collect::<Vec<_>>());
+    assert_eq!( hotspots.len(), 2 * CA_POS.len()); // from both sets
+    assert!( hotspots.iter().all( |h| h.position.lat_deg < 38.0));
+
+    // only the newer set
+    let hotspots: Vec<GoesrHotspot> = newer?;
+    assert_eq!( hotspots.len(), CA_POS.len());
+    assert!( hotspots.iter().all( |h| Some(h.date) == parse_goesr_create_dtg( NEWER)));
+
+    // nothing outside of our regions
+    let hotspots: Vec<GoesrHotspot> = outside?;
+    assert!( hotspots.is_empty());
+
+    Ok(())
+}
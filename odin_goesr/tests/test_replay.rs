@@ -0,0 +1,76 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License. You may obtain a copy
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+use std::{path::PathBuf, sync::{Arc,Mutex}, time::Duration};
+use chrono::{DateTime,Utc};
+use odin_actor::prelude::*;
+use odin_action::data_action;
+use odin_goesr::{get_replay_files, parse_goesr_create_dtg, GoesrData, GoesrHotspotActor, GoesrHotspotSet, GoesrImportActorConfig, GoesrMaskClass,
+    GoesrProduct, ReplayGoesrHotspotImporter, ReplayGoesrHotspotImporterConfig, Satellite};
+
+mod common;
+
+const NEWER: &str = "OR_ABI-L2-FDCC-M6_G18_s20241380556172_e20241380558545_c20241380559122.nc";
+const OLDER: &str = "OR_ABI-L2-FDCC-M6_G18_s20241380551172_e20241380553545_c20241380554122.nc";
+const OTHER_SAT: &str = "OR_ABI-L2-FDCC-M6_G16_s20241380546172_e20241380548545_c20241380549122.nc";
+const OTHER_PRODUCT: &str = "OR_ABI-L2-FDCF-M6_G18_s20241380550205_e20241380559513_c20241380600060.nc";
+
+fn replay_dir ()->PathBuf {
+    common::replay_dir( "odin_goesr_replay", &[NEWER, OLDER, OTHER_SAT, OTHER_PRODUCT, "README.txt"]) // newer first so that directory order does not matter
+}
+
+// the files are empty, we only need the GoesrData we were called with
+fn read_empty (data: &GoesrData)->odin_goesr::Result<GoesrHotspotSet> {
+    Ok( GoesrHotspotSet::new( data, Vec::new()))
+}
+
+#[tokio::test]
+async fn test_replay_importer()->odin_goesr::Result<()> {
+    let dir = replay_dir();
+    let dates: Vec<DateTime<Utc>> = get_replay_files( &dir, Satellite::Goes18, GoesrProduct::Fdcc)?.into_iter().map( |(_,date)| date).collect();
+    assert_eq!( dates, vec![ parse_goesr_create_dtg(OLDER).unwrap(), parse_goesr_create_dtg(NEWER).unwrap() ]);
+
+    let fdcf_files: Vec<PathBuf> = get_replay_files( &dir, Satellite::Goes18, GoesrProduct::Fdcf)?.into_iter().map( |(path,_)| path).collect();
+    assert_eq!( fdcf_files, vec![ dir.join( OTHER_PRODUCT) ]);
+
+    let config = ReplayGoesrHotspotImporterConfig {
+        satellite: Satellite::Goes18,
+        product: GoesrProduct::Fdcc,
+        data_dir: dir,
+        cadence: Duration::from_millis(50),
+        mask_class: GoesrMaskClass::All,
+        min_class: None
+    };
+
+    let received = Arc::new( Mutex::new( Vec::<DateTime<Utc>>::new()));
+
+    let mut actor_system = ActorSystem::new("test");
+    spawn_actor!( actor_system, "goesr", GoesrHotspotActor::new(
+        GoesrImportActorConfig { max_records: 10 },
+        ReplayGoesrHotspotImporter::with_reader( config, read_empty),
+        no_dataref_action(),
+        data_action!( let received: Arc<Mutex<Vec<DateTime<Utc>>>> = received.clone() => |hs: GoesrHotspotSet| {
+            received.lock().unwrap().push( hs.date);
+            Ok(())
+        })
+    ))?;
+
+    actor_system.timeout_start_all( millis(20)).await?;
+    actor_system.process_requests_for( millis(400)).await?;
+
+    let received = received.lock().unwrap();
+    println!("received sets: {:?}", received);
+    assert_eq!( *received, dates); // both sets, oldest first
+    Ok(())
+}
@@ -0,0 +1,39 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License. You may obtain a copy
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+use odin_common::s3::get_s3_objects;
+use odin_goesr::{GoesrS3Config, Satellite};
+
+#[test]
+fn test_default_config() {
+    let config = GoesrS3Config::default();
+    assert_eq!( config.region, "us-east-1");
+    assert!( config.anonymous);
+    assert!( config.endpoint.is_none());
+
+    let config: GoesrS3Config = ron::from_str( "GoesrS3Config( endpoint: Some(\"http://localhost:9000\") )").unwrap();
+    assert!( config.anonymous); // omitted fields use the defaults
+    assert_eq!( config.endpoint.as_deref(), Some("http://localhost:9000"));
+}
+
+/// this lists a known public prefix without credentials and hence needs network access. Run with
+/// `cargo test --features s3_integration`
+#[cfg(feature="s3_integration")]
+#[tokio::test]
+async fn test_anonymous_listing() {
+    let client = GoesrS3Config::default().create_client().await.unwrap();
+    let objs = get_s3_objects( &client, Satellite::Goes18.bucket(), "ABI-L2-FDCC/2024/001/00/", None).await.unwrap();
+    println!("{} objects", objs.len());
+    assert!( !objs.is_empty());
+}
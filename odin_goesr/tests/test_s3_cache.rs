@@ -0,0 +1,147 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License. You may obtain a copy
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+use std::{collections::HashMap, future::Future, sync::Mutex, time::Duration};
+use chrono::{DateTime, Datelike, TimeDelta, TimeZone, Timelike, Utc};
+use odin_common::s3::{self, S3Object, S3ObjectLister, S3ListingCache};
+use odin_goesr::{get_most_recent_objects, is_completed_hour, parse_goesr_create_dtg};
+
+const BUCKET: &str = "noaa-goes18";
+const SOURCE: &str = "ABI-L2-FDCC";
+
+/// mock lister that counts list calls per prefix and returns 5min scans for the hour encoded in the prefix
+struct MockLister {
+    calls: Mutex<HashMap<String,usize>>,
+}
+
+impl MockLister {
+    fn new ()->Self { MockLister { calls: Mutex::new( HashMap::new()) } }
+
+    fn calls (&self, dt: DateTime<Utc>)->usize {
+        *self.calls.lock().unwrap().get( &prefix(dt)).unwrap_or(&0)
+    }
+
+    fn total_calls (&self)->usize {
+        self.calls.lock().unwrap().values().sum()
+    }
+}
+
+fn prefix (dt: DateTime<Utc>)->String {
+    format!("{}/{}/{:03}/{:02}/", SOURCE, dt.year(), dt.ordinal(), dt.hour())
+}
+
+fn dtg (dt: DateTime<Utc>)->String {
+    format!("{}{:03}{:02}{:02}{:02}0", dt.year(), dt.ordinal(), dt.hour(), dt.minute(), dt.second())
+}
+
+fn scan_objects (prefix: &str)->Vec<S3Object> {
+    let parts: Vec<&str> = prefix.split('/').collect();
+    let (year, doy, hour): (i32,u32,u32) = (parts[1].parse().unwrap(), parts[2].parse().unwrap(), parts[3].parse().unwrap());
+    let dt_hour = Utc.with_ymd_and_hms( year, 1, 1, hour, 0, 0).unwrap() + TimeDelta::days( doy as i64 - 1);
+
+    (0..12).map( |i| {
+        let dt_s = dt_hour + TimeDelta::seconds( i*300 + 60);
+        let dt_e = dt_s + TimeDelta::seconds(150);
+        let dt_c = dt_e + TimeDelta::seconds(30);
+        let key = format!("{prefix}OR_ABI-L2-FDCC-M6_G18_s{}_e{}_c{}.nc", dtg(dt_s), dtg(dt_e), dtg(dt_c));
+        S3Object::new( key, dt_c + TimeDelta::seconds(30))
+    }).collect()
+}
+
+impl S3ObjectLister for MockLister {
+    fn list_objects (&self, _bucket: &str, prefix: &str, prev_key: Option<&str>) -> impl Future<Output=s3::Result<Vec<S3Object>>> + Send {
+        *self.calls.lock().unwrap().entry( prefix.to_string()).or_insert(0) += 1;
+        let objs: Vec<S3Object> = scan_objects( prefix).into_iter()
+            .filter( |o| prev_key.map_or( true, |marker| o.key().unwrap() > marker))
+            .collect();
+        async move { Ok(objs) }
+    }
+}
+
+#[test]
+fn test_completed_hour() {
+    let dt = Utc.with_ymd_and_hms( 2024, 5, 17, 11, 55, 0).unwrap();
+    assert!( !is_completed_hour( dt, Utc.with_ymd_and_hms( 2024, 5, 17, 12, 5, 0).unwrap())); // uploads still pending
+    assert!( is_completed_hour( dt, Utc.with_ymd_and_hms( 2024, 5, 17, 12, 30, 0).unwrap()));
+}
+
+#[tokio::test]
+async fn test_listing_cache() {
+    let lister = MockLister::new();
+    let mut cache = S3ListingCache::new(8);
+    let lookback = Duration::from_secs( 3*3600);
+
+    let now = Utc.with_ymd_and_hms( 2024, 5, 17, 12, 30, 0).unwrap();
+    let objs1 = get_most_recent_objects( &lister, &mut cache, BUCKET, SOURCE, lookback, now).await.unwrap();
+
+    let now = now + TimeDelta::minutes(5);
+    let objs2 = get_most_recent_objects( &lister, &mut cache, BUCKET, SOURCE, lookback, now).await.unwrap();
+    println!("poll 1: {} objects, poll 2: {} objects, list calls: {:?}", objs1.len(), objs2.len(), lister.calls.lock().unwrap());
+
+    assert!( !objs1.is_empty());
+    assert_eq!( objs1.len(), objs2.len()); // mock returns the full hour so the lookback window just shifts by one scan
+
+    for h in 1..=3 { // completed hours are listed exactly once
+        assert_eq!( lister.calls( now - TimeDelta::hours(h)), 1);
+    }
+    assert_eq!( lister.calls( now), 2); // the current hour is re-listed on each poll
+    assert_eq!( lister.total_calls(), 5);
+    assert_eq!( cache.len(), 3);
+}
+
+#[tokio::test]
+async fn test_listing_cache_eviction() {
+    let lister = MockLister::new();
+    let mut cache = S3ListingCache::new(1);
+    let lookback = Duration::from_secs( 3*3600);
+    let now = Utc.with_ymd_and_hms( 2024, 5, 17, 12, 30, 0).unwrap();
+
+    get_most_recent_objects( &lister, &mut cache, BUCKET, SOURCE, lookback, now).await.unwrap();
+    get_most_recent_objects( &lister, &mut cache, BUCKET, SOURCE, lookback, now).await.unwrap();
+
+    assert_eq!( cache.len(), 1);
+    for h in 1..=3 { // each completed hour evicts the previous one so all of them get re-listed
+        assert_eq!( lister.calls( now - TimeDelta::hours(h)), 2);
+    }
+}
+
+/// mock lister that returns hour listings in reverse order, each including the first scan of the next hour
+struct OverlapLister;
+
+impl S3ObjectLister for OverlapLister {
+    fn list_objects (&self, _bucket: &str, prefix: &str, _prev_key: Option<&str>) -> impl Future<Output=s3::Result<Vec<S3Object>>> + Send {
+        let parts: Vec<&str> = prefix.split('/').collect();
+        let next_hour = Utc.with_ymd_and_hms( parts[1].parse().unwrap(), 1, 1, parts[3].parse().unwrap(), 0, 0).unwrap() 
+                        + TimeDelta::days( parts[2].parse::<i64>().unwrap() - 1) + TimeDelta::hours(1);
+
+        let mut objs = scan_objects( prefix);
+        objs.push( scan_objects( &self::prefix( next_hour)).remove(0));
+        objs.reverse();
+        async move { Ok(objs) }
+    }
+}
+
+#[tokio::test]
+async fn test_sorted_dedup_objects() {
+    let mut cache = S3ListingCache::new(0);
+    let now = Utc.with_ymd_and_hms( 2024, 5, 17, 12, 30, 0).unwrap();
+    let objs = get_most_recent_objects( &OverlapLister, &mut cache, BUCKET, SOURCE, Duration::from_secs( 3*3600), now).await.unwrap();
+
+    let keys: Vec<&str> = objs.iter().map( |o| o.key().unwrap()).collect();
+    let dates: Vec<DateTime<Utc>> = keys.iter().map( |k| parse_goesr_create_dtg(k).unwrap()).collect();
+    println!("{} objects from {} to {}", objs.len(), dates[0], dates[dates.len()-1]);
+
+    assert!( dates.windows(2).all( |w| w[0] < w[1])); // strictly ascending, i.e. also no duplicates
+    assert_eq!( objs.len(), 6 + 3*12 + 1); // 09:30..09:59, hours 10..=12 (the mock lists full hours) and the first scan of 13:00
+}
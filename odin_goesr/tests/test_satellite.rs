@@ -0,0 +1,60 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License. You may obtain a copy
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+use odin_common::ranges::LinearRange;
+use odin_gdal::GridPoint;
+use odin_goesr::{get_bounds, Satellite};
+
+// CONUS fixed grid angles (radians) of the GOES-R ABI 2km product
+fn conus_ranges ()->(LinearRange<f64>,LinearRange<f64>) {
+    (LinearRange::new( -0.101332, 0.000056, 2500), LinearRange::new( 0.128212, -0.000056, 1500))
+}
+
+#[test]
+fn test_west_east_bounds() {
+    let (x_range, y_range) = conus_ranges();
+    let p = GridPoint{ i0: 1250, i1: 750, value: 13u16 };
+
+    let west = get_bounds( &Satellite::Goes18.projection(), &x_range, &y_range, &p);
+    let east = get_bounds( &Satellite::Goes19.projection(), &x_range, &y_range, &p);
+    println!("west: {west:?}\neast: {east:?}");
+
+    // same instrument angles but different sub-satellite longitudes
+    assert!( (west.nw.lon_deg - east.nw.lon_deg).abs() > 50.0);
+    assert!( (west.nw.lon_deg - east.nw.lon_deg - (-137.2 + 75.2)).abs() < 1e-6);
+    assert!( (west.nw.lat_deg - east.nw.lat_deg).abs() < 1e-9); // latitude does not depend on lon0
+
+    // East positions are the same for the decommissioned GOES-16 and its successor
+    let east16 = get_bounds( &Satellite::Goes16.projection(), &x_range, &y_range, &p);
+    assert_eq!( east16.se.lon_deg, east.se.lon_deg);
+}
+
+#[test]
+fn test_satellite_ids() {
+    for sat in [Satellite::Goes16, Satellite::Goes18, Satellite::Goes19] {
+        assert_eq!( Satellite::from_platform_id( sat.platform_id()), Some(sat));
+        assert!( sat.bucket().ends_with( &sat.platform_id().to_string()));
+    }
+    assert_eq!( Satellite::Goes18.sat_id(), 51850);
+    assert!( Satellite::Goes18.is_west() && !Satellite::Goes19.is_west());
+    assert_eq!( Satellite::from_platform_id(17), None);
+}
+
+#[test]
+fn test_check_file() {
+    let key = "ABI-L2-FDCC/2024/138/05/OR_ABI-L2-FDCC-M6_G18_s20241380556172_e20241380558545_c20241380559122.nc";
+    assert!( Satellite::Goes18.check_file( key).is_ok());
+    assert!( Satellite::Goes16.check_file( key).is_err());
+    assert!( Satellite::Goes18.check_file( "not_a_goesr_file.nc").is_err());
+}
@@ -0,0 +1,96 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License. You may obtain a copy
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+use std::time::Duration;
+use odin_goesr::{parse_filename, parse_goesr_dtg, find_scan_gaps, GoesrFileInfo, GoesrProduct};
+
+// 5min CONUS scans with the 06:06 scan missing (listed out of order on purpose)
+const FILENAMES: [&str; 4] = [
+    "OR_ABI-L2-FDCC-M6_G18_s20241380556172_e20241380558545_c20241380559122.nc",
+    "OR_ABI-L2-FDCC-M6_G18_s20241380551172_e20241380553545_c20241380554122.nc",
+    "OR_ABI-L2-FDCC-M6_G18_s20241380611172_e20241380613545_c20241380614122.nc",
+    "OR_ABI-L2-FDCC-M6_G18_s20241380601172_e20241380603545_c20241380604122.nc",
+];
+
+fn file_infos ()->Vec<GoesrFileInfo> {
+    FILENAMES.iter().map( |f| parse_filename(f).unwrap()).collect()
+}
+
+#[test]
+fn test_scan_gap() {
+    let infos = file_infos();
+    let gaps = find_scan_gaps( &infos, Duration::from_secs(300));
+    println!("gaps: {gaps:?}");
+
+    assert_eq!( gaps.len(), 1);
+    assert_eq!( gaps[0].0, parse_goesr_dtg("20241380603545").unwrap()); // end of the 06:01 scan
+    assert_eq!( gaps[0].1, parse_goesr_dtg("20241380611172").unwrap()); // start of the 06:11 scan
+}
+
+#[test]
+fn test_no_scan_gap() {
+    let infos: Vec<GoesrFileInfo> = file_infos().into_iter().filter( |i| i.start_time < parse_goesr_dtg("20241380610000").unwrap()).collect();
+    assert_eq!( infos.len(), 3);
+    assert!( find_scan_gaps( &infos, Duration::from_secs(300)).is_empty());
+}
+
+#[test]
+fn test_full_disk_product() {
+    let info = parse_filename( "OR_ABI-L2-FDCF-M6_G18_s20241380550210_e20241380559518_c20241380600108.nc").unwrap();
+    assert_eq!( info.product, "FDCF");
+
+    let product = GoesrProduct::from_product( &info.product).unwrap();
+    assert_eq!( product, GoesrProduct::Fdcf);
+    assert_eq!( product.source(), "ABI-L2-FDCF");
+    assert_eq!( product.scan_interval(), Duration::from_secs(600));
+    assert_eq!( product.s3_prefix( info.start_time), "ABI-L2-FDCF/2024/138/05/");
+
+    let info = parse_filename( "OR_ABI-L2-FDCM1-M6_G18_s20241380556172_e20241380556230_c20241380556291.nc").unwrap();
+    assert_eq!( GoesrProduct::from_product( &info.product), Some(GoesrProduct::Fdcm));
+    assert_eq!( GoesrProduct::from_product( "ACHAC"), None); // not a fire product
+}
+
+#[test]
+fn test_parse_standard_filename() {
+    let info = parse_filename( "ABI-L2-FDCC/2024/138/05/OR_ABI-L2-FDCC-M6_G18_s20241380556172_e20241380558545_c20241380559122.nc").unwrap();
+    assert_eq!( (info.sys_env.as_str(), info.instrument.as_str(), info.level.as_str(), info.product.as_str(), info.mode.as_str()), 
+                ("OR", "ABI", "L2", "FDCC", "M6"));
+    assert_eq!( info.sat_id, 18);
+    assert_eq!( info.start_time, parse_goesr_dtg("20241380556172").unwrap());
+    assert_eq!( info.end_time, parse_goesr_dtg("20241380558545"));
+    assert_eq!( info.create_time, parse_goesr_dtg("20241380559122"));
+}
+
+#[test]
+fn test_parse_filename_without_end_time() {
+    let info = parse_filename( "OR_ABI-L2-FDCF-M6_G16_s20241380550205_c20241380559473.nc").unwrap();
+    assert_eq!( info.product, "FDCF");
+    assert_eq!( info.sat_id, 16);
+    assert_eq!( info.start_time, parse_goesr_dtg("20241380550205").unwrap());
+    assert!( info.end_time.is_none());
+    assert_eq!( info.create_time, parse_goesr_dtg("20241380559473"));
+    assert_eq!( info.create_or_start_time(), parse_goesr_dtg("20241380559473").unwrap());
+}
+
+#[test]
+fn test_parse_nc4_filename() {
+    let info = parse_filename( "OR_ABI-L2-FDCC-M6_G18_s20241380556172.nc4").unwrap();
+    assert_eq!( info.product, "FDCC");
+    assert_eq!( info.start_time, parse_goesr_dtg("20241380556172").unwrap());
+    assert!( info.end_time.is_none());
+    assert!( info.create_time.is_none());
+    assert_eq!( info.create_or_start_time(), info.start_time);
+
+    assert!( parse_filename( "not_a_goesr_file.nc").is_none());
+}
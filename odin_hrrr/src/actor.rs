@@ -18,7 +18,7 @@ use chrono::{DateTime,Utc,Timelike};
 
 use odin_actor::prelude::*;
 use odin_actor::{error,debug,warn,info};
-use odin_common::{datetime::full_hour, fs::{remove_old_files, FileAvailable}};
+use odin_common::{datetime::full_hour, fs::{remove_old_files, FileAvailable}, schedule::AdaptiveInterval, sim_clock::{Clock,RealClock}};
 
 use crate::{errors::*, get_next_base_step, is_extended_forecast, queue_available_forecasts, DownloadCmd, HrrrConfig, HrrrDataSetConfig, HrrrDataSetRequest, HrrrFileAvailable, HrrrFileRequest};
 use crate::{spawn_download_task, hrrr_cache_dir, schedule::{HrrrSchedules, get_statistic_schedules}};
@@ -47,12 +47,16 @@ pub struct HrrrActor {
     // all set during start
     schedules: HrrrSchedules,
     timer: Option<AbortHandle>,
+    check_interval: AdaptiveInterval,
+
+    clock: Arc<dyn Clock>,
 }
 
 impl HrrrActor {
     pub fn new <A> (config: HrrrConfig, schedules: HrrrSchedules, file_avail_action: A)->Self 
         where A: DataAction<HrrrFileAvailable> + 'static
     {
+        let check_interval = AdaptiveInterval::new( config.check_interval, config.max_check_interval.unwrap_or( config.check_interval));
         let config = Arc::new(config);
         let cache_dir = hrrr_cache_dir();
         let (download_task,tx) = spawn_download_task( config.clone(), cache_dir, file_avail_action).unwrap();
@@ -68,13 +72,23 @@ impl HrrrActor {
             step: 0,
 
             schedules,
-            timer: None
+            timer: None,
+            check_interval,
+
+            clock: Arc::new(RealClock),
         }
     }
 
+    /// use the provided clock (e.g. a `SimClock` for replays) instead of wall time to determine forecast availability
+    pub fn with_clock (mut self, clock: Arc<dyn Clock>)->Self {
+        self.base = clock.now();
+        self.clock = clock;
+        self
+    }
+
     async fn add_dataset (&mut self, ds: Arc<HrrrDataSetRequest>) {
         if !self.datasets.contains( &ds) {
-            queue_available_forecasts( &self.tx, ds.clone(), &self.schedules).await;
+            queue_available_forecasts( &self.tx, ds.clone(), &self.schedules, self.clock.as_ref()).await;
 
             self.datasets.insert( ds);
 
@@ -85,18 +99,22 @@ impl HrrrActor {
     }
 
     fn set_base_step (&mut self) {
-        let now = Utc::now();
-        let (base,step) = get_next_base_step( &self.schedules, &now);
-
-        //println!("@@ start at {} + {}", base, step);
-
-        self.base = base;
-        self.step = step;
+        let now = self.clock.now();
+        match get_next_base_step( &self.schedules, &now) {
+            Ok((base,step)) => {
+                self.base = base;
+                self.step = step;
+            }
+            Err(e) => error!("failed to get next forecast step: {}", e)
+        }
     }
 
-    async fn check_step (&mut self) {
-        if !self.datasets.is_empty() {
-            let now = Utc::now();
+    /// queue all forecast steps that should be available by now and return the number of queued steps
+    async fn check_step (&mut self)->usize {
+        let mut n_steps = 0;
+
+        if !self.datasets.is_empty() && !self.schedules.has_empty_schedule() {
+            let now = self.clock.now();
             let mut sched = self.schedules.schedule_for(&self.base);
 
             while (now - self.base).num_minutes() as u32 >= sched[self.step] {
@@ -105,6 +123,7 @@ impl HrrrActor {
                     self.tx.send( cmd).await;
                 }
                 self.step += 1;
+                n_steps += 1;
 
                 if self.step >= sched.len() { // next cycle
                     self.base = self.base + Duration::from_secs(3600);
@@ -113,6 +132,7 @@ impl HrrrActor {
                 }
             }
         }
+        n_steps
     }
 
     fn remove_dataset (&mut self, ds: Arc<HrrrDataSetRequest>) {
@@ -131,7 +151,7 @@ impl_actor! { match msg for Actor<HrrrActor,HrrrActorMsg> as
         self.add_dataset(msg.0).await;
 
         if self.datasets.len() == 1 { // first request, start timer
-            if let Ok(timer) = self.start_repeat_timer( 1, self.config.check_interval, false) {
+            if let Ok(timer) = self.start_oneshot_timer( 1, self.check_interval.current()) {
                 self.timer = Some(timer);
             } else { error!("failed to start timer") }
         }
@@ -147,7 +167,13 @@ impl_actor! { match msg for Actor<HrrrActor,HrrrActorMsg> as
         }
     }
     _Timer_ => cont! { 
-        self.check_step().await
+        let n_steps = self.check_step().await;
+        if !self.datasets.is_empty() { // re-arm with an interval that reflects how many steps we got
+            let interval = self.check_interval.update( n_steps);
+            if let Ok(timer) = self.start_oneshot_timer( 1, interval) {
+                self.timer = Some(timer);
+            } else { error!("failed to re-arm timer") }
+        }
     }
     _Terminate_ => stop! { 
         self.terminate();
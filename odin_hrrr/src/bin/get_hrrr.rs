@@ -13,7 +13,7 @@
  */
 
 use std::sync::Arc;
-use odin_common::define_cli;
+use odin_common::{define_cli, sim_clock::RealClock};
 use odin_build;
 use odin_action::data_action;
 use odin_hrrr::{
@@ -49,5 +49,5 @@ async fn main ()->Result<()> {
         Ok(())
     });
 
-    run_downloads(conf, dsrs, schedules, ARGS.periodic, file_avail_action).await
+    run_downloads(conf, dsrs, schedules, ARGS.periodic, file_avail_action, &RealClock).await
 }
@@ -0,0 +1,107 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of 
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License"); 
+ * you may not use this file except in compliance with the License. You may obtain a copy 
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+//! a lightweight persistent index of downloaded HRRR files so that restarts don't have to probe the filesystem
+//! (or the server) for each requested file
+
+use std::{collections::HashMap, path::{Path,PathBuf}, time::Duration};
+use chrono::{DateTime,Utc};
+use serde::{Deserialize,Serialize};
+use odin_actor::warn;
+
+use crate::{errors::*, HrrrDataSetRequest};
+
+const INDEX_FILENAME: &str = "index.json";
+
+#[derive(Debug,Clone,Serialize,Deserialize)]
+pub struct CacheEntry {
+    pub filename: String,
+    pub size: u64,
+    pub mtime: DateTime<Utc>,
+}
+
+/// map of (region, base, step, query-hash) keys to downloaded files in a cache dir, which is stored as `<cache_dir>/index.json`
+#[derive(Debug)]
+pub struct CacheIndex {
+    cache_dir: PathBuf,
+    entries: HashMap<String,CacheEntry>,
+}
+
+impl CacheIndex {
+    /// load index from the given cache dir. A missing or unreadable index results in an empty one
+    pub fn load (cache_dir: &Path)->Self {
+        let path = cache_dir.join( INDEX_FILENAME);
+        let entries = if path.is_file() {
+            match std::fs::read_to_string( &path).map_err(OdinHrrrError::from).and_then( |s| serde_json::from_str(&s).map_err(op_failed)) {
+                Ok(entries) => entries,
+                Err(e) => { warn!("ignoring corrupted cache index {:?}: {}", path, e); HashMap::new() }
+            }
+        } else {
+            HashMap::new()
+        };
+
+        CacheIndex { cache_dir: cache_dir.to_path_buf(), entries }
+    }
+
+    /// note the query hash is persisted and hence has to be stable across builds, which rules out std's DefaultHasher
+    pub fn key (region: &str, ds: &HrrrDataSetRequest, base: &DateTime<Utc>, step: usize)->String {
+        format!("{}/{}/{}/{:016x}", region, base.timestamp(), step, fnv1a_64( ds.canonical.as_bytes()))
+    }
+
+    pub fn len (&self)->usize { self.entries.len() }
+
+    /// get the path of a cached file for `key` if it is not older than `max_age` at (wall clock) time `now`. Entries
+    /// of files that have been removed in the meantime are dropped
+    pub fn get (&mut self, key: &str, max_age: Duration, now: DateTime<Utc>)->Option<PathBuf> {
+        let path = self.entries.get(key).filter( |e| is_fresh( e, max_age, now)).map( |e| self.cache_dir.join( &e.filename))?;
+        if path.is_file() {
+            Some(path)
+        } else {
+            self.entries.remove(key);
+            None
+        }
+    }
+
+    /// record a downloaded file that is stored in our cache dir
+    pub fn insert (&mut self, key: String, path: &Path)->Result<()> {
+        let meta = std::fs::metadata( path)?;
+        let filename = path.file_name().and_then( |f| f.to_str()).ok_or( op_failed( format!("invalid cache file {:?}", path)))?;
+        let entry = CacheEntry { filename: filename.to_string(), size: meta.len(), mtime: meta.modified()?.into() };
+
+        self.entries.insert( key, entry);
+        Ok(())
+    }
+
+    /// remove all entries older than `max_age` at (wall clock) time `now`, which should be the same as used for `remove_old_files`
+    pub fn remove_stale (&mut self, max_age: Duration, now: DateTime<Utc>) {
+        self.entries.retain( |_,e| is_fresh( e, max_age, now))
+    }
+
+    /// store index in our cache dir. We write to a temp file first to avoid leaving a partially written index
+    pub fn save (&self)->Result<()> {
+        let path = self.cache_dir.join( INDEX_FILENAME);
+        let tmp_path = path.with_extension( "json.tmp");
+        std::fs::write( &tmp_path, serde_json::to_string( &self.entries).map_err(op_failed)?)?;
+        std::fs::rename( &tmp_path, &path)?;
+        Ok(())
+    }
+}
+
+fn is_fresh (e: &CacheEntry, max_age: Duration, now: DateTime<Utc>)->bool {
+    (now - e.mtime).to_std().map_or( false, |age| age < max_age) // negative age means we can't trust the entry
+}
+
+/// 64 bit FNV-1a hash, which (unlike std's DefaultHasher) is guaranteed to be the same across Rust versions and platforms
+fn fnv1a_64 (bytes: &[u8])->u64 {
+    bytes.iter().fold( 0xcbf29ce484222325, |h,b| (h ^ *b as u64).wrapping_mul( 0x100000001b3))
+}
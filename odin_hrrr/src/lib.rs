@@ -16,20 +16,24 @@
 #[doc = include_str!("../doc/odin_hrrr.md")]
 
 use std::{
-    str::FromStr, path::{Path,PathBuf}, fmt::Write as FmtWrite, io::Write as IoWrite, fmt::Display, time::SystemTime, 
-    sync::Arc, hash::{Hash,DefaultHasher,Hasher}
+    str::FromStr, path::{Path,PathBuf}, fmt::Write as FmtWrite, io::{Write as IoWrite, Read, Seek, SeekFrom}, fmt::Display, time::SystemTime, 
+    sync::{Arc,Mutex}, hash::{Hash,DefaultHasher,Hasher}, future::Future, pin::pin, collections::HashSet
 };
+use futures::stream::StreamExt;
 use schedule::HrrrSchedules;
 use serde::{Deserialize,Serialize};
 use structopt::StructOpt;
 use chrono::{DateTime,Datelike,Timelike,Utc,SecondsFormat};
 use reqwest;
 use regex::Regex;
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
 use tempfile;
 use tokio::{time::{Duration,Sleep}};
+use tokio_util::sync::CancellationToken;
 
 use odin_common::{
-    angle::{LatAngle,LonAngle}, datetime::{elapsed_minutes_since,full_hour}, fs::{ensure_writable_dir, remove_old_files}, geo::GeoBoundingBox, strings::{mk_string,to_sorted_string_vec}
+    angle::{LatAngle,LonAngle}, datetime::{elapsed_minutes_since,full_hour}, fs::{ensure_writable_dir, remove_old_files_except}, geo::GeoBoundingBox, 
+    sim_clock::Clock, strings::{mk_string,to_sorted_string_vec}
 };
 use odin_actor::prelude::*;
 use odin_actor::AbortHandle;
@@ -44,6 +48,9 @@ pub mod schedule;
 mod errors;
 pub use errors::*;
 
+mod cache;
+pub use cache::*;
+
 const ONE_HOUR: Duration = Duration::from_secs(3600);
 
 define_load_config!{}
@@ -61,6 +68,12 @@ pub struct HrrrConfig {
     /// to turn this into a real URL we have to expand the "${yyyyMMdd}" field
     pub dir_url_pattern: String,
 
+    /// NOMADS filename template with "${HH}" (base hour) and "${FF}" (forecast step) fields. This depends on region and
+    /// product, e.g. "hrrr.t${HH}z.wrfsfcf${FF}.grib2" (CONUS surface), "hrrr.t${HH}z.wrfsfcf${FF}.ak.grib2" (Alaska surface)
+    /// or "hrrr.t${HH}z.wrfsubhf${FF}.grib2" (CONUS sub-hourly)
+    #[serde(default="default_file_pattern")]
+    pub file_pattern: String,
+
     // fallbacks if we don't want to query schedules (the nomads.ncep.nooa.gov/.. dir listings do change and might not be reliable)
     // we assume roughly linear computation time
     pub reg_first: u32,
@@ -77,6 +90,11 @@ pub struct HrrrConfig {
     /// interval in which we check next forecast step availability
     pub check_interval: Duration,
 
+    /// if set, the check interval adapts to the observed arrival rate of forecast steps between `check_interval`
+    /// (steps are coming in) and this value (quiet period)
+    #[serde(default)]
+    pub max_check_interval: Option<Duration>,
+
     /// delay between download attempts
     pub retry_delay: Duration,
 
@@ -85,14 +103,65 @@ pub struct HrrrConfig {
 
     /// how long to keep downloaded HRRR files
     pub max_age: Duration,
+
+    /// max number of files that are downloaded concurrently
+    #[serde(default="default_max_concurrent_downloads")]
+    pub max_concurrent_downloads: usize,
+
+    /// fallback servers we try in order if downloads from `url` fail `max_retry` times
+    #[serde(default)]
+    pub mirrors: Vec<HrrrMirror>,
+
+    /// if set we verify files downloaded from `url` against the `.idx` file of the complete GRIB2 file
+    /// (retrieved from `dir_url_pattern`) and reject downloads that do not contain all requested fields
+    #[serde(default)]
+    pub verify_idx: bool,
+}
+
+/// an alternative source for HRRR files such as the AWS or Google open-data buckets.
+/// Note that mirrors serve complete (not subsetted) GRIB2 files, i.e. downloads are larger and not restricted to the
+/// configured fields, levels and bbox of the data set
+#[derive(Clone,Serialize,Deserialize,Debug)]
+pub struct HrrrMirror {
+    pub name: String,
+
+    /// URL template with "${yyyyMMdd}", "${region}" and "${filename}" fields, e.g.
+    /// "https://noaa-hrrr-bdp-pds.s3.amazonaws.com/hrrr.${yyyyMMdd}/${region}/${filename}"
+    pub url_pattern: String,
+
+    /// filename template with "${HH}" (base hour) and "${FF}" (forecast step) fields, e.g. "hrrr.t${HH}z.wrfsfcf${FF}.grib2"
+    pub filename_pattern: String,
+}
+
+impl HrrrMirror {
+    pub fn url (&self, region: &str, dt: &DateTime<Utc>, step: usize)->String {
+        let filename = expand_file_pattern( &self.filename_pattern, dt, step);
+
+        self.url_pattern
+            .replace( "${yyyyMMdd}", &format!("{:04}{:02}{:02}", dt.year(), dt.month(), dt.day()))
+            .replace( "${region}", region)
+            .replace( "${filename}", &filename)
+    }
+}
+
+/// expand the "${HH}" (base hour) and "${FF}" (forecast step) fields of a filename template
+pub fn expand_file_pattern (pattern: &str, dt: &DateTime<Utc>, step: usize)->String {
+    pattern
+        .replace( "${HH}", &format!("{:02}", dt.hour()))
+        .replace( "${FF}", &format!("{:02}", step))
 }
 
+fn default_max_concurrent_downloads()->usize { 4 }
+
+fn default_file_pattern()->String { "hrrr.t${HH}z.wrfsfcf${FF}.grib2".to_string() }
+
 impl Default for HrrrConfig {
     fn default() -> Self {
         Self { 
             region: "conus".to_string(),
             url: "https://nomads.ncep.noaa.gov/cgi-bin/filter_hrrr_2d.pl".to_string(), 
             dir_url_pattern: "https://nomads.ncep.noaa.gov/pub/data/nccf/com/hrrr/prod/hrrr.${yyyyMMdd}/conus".to_string(), 
+            file_pattern: default_file_pattern(),
 
             // those are just estimates (first dmin, last dmin, steps) - it might change
             reg_first: 48,
@@ -105,9 +174,13 @@ impl Default for HrrrConfig {
 
             delay: Duration::from_secs( 60), 
             check_interval: Duration::from_secs(30),
+            max_check_interval: Some(Duration::from_secs(240)),
             retry_delay: Duration::from_secs( 30),
             max_retry: 4, 
             max_age: Duration::from_secs(7200), // 2h 
+            max_concurrent_downloads: default_max_concurrent_downloads(),
+            mirrors: Vec::new(),
+            verify_idx: false,
         }
     }
 }
@@ -133,49 +206,56 @@ impl HrrrDataSetConfig {
 }
 
 /// a wrapper for a HrrrDataSetSpec that we want to retrieve from the NOAA server
-/// note we consider two requests as equal if they have the same (canonical) query string
+/// note we consider two requests as equal if they have the same canonical (sorted, not encoded) query tokens
 #[derive(Debug)]
 pub struct HrrrDataSetRequest {
     pub ds: HrrrDataSetConfig,
 
-    /// canonical query string computed from `ds`
-    pub query: String
+    /// percent-encoded query string computed from `ds`
+    pub query: String,
+
+    /// canonical query string (before encoding) that is used for hashing and equality
+    canonical: String,
 }
 
+/// the characters we encode in query tokens (everything except RFC 3986 unreserved characters)
+const QUERY_TOKEN: &AsciiSet = &NON_ALPHANUMERIC.remove(b'-').remove(b'.').remove(b'_').remove(b'~');
+
 impl HrrrDataSetRequest {
     pub fn new (mut ds_cfg: HrrrDataSetConfig)->Self {
         ds_cfg.fields.sort();
         ds_cfg.levels.sort();
 
         let bbox = &ds_cfg.bbox;
-        let mut query = format!("subregion=&toplat={}&leftlon={}&rightlon={}&bottomlat={}", 
+        let bbox_query = format!("subregion=&toplat={}&leftlon={}&rightlon={}&bottomlat={}", 
                                 bbox.north.degrees(), bbox.west.degrees(), bbox.east.degrees(), bbox.south.degrees());
-        for v in &ds_cfg.fields {
-            query.push('&');
-            query.push_str("var_");
-            query.push_str(v.as_str());
-            query.push_str("=on");
-        }
+        let mut query = bbox_query.clone();
+        let mut canonical = bbox_query;
+
+        let tokens = ds_cfg.fields.iter().map( |v| format!("var_{}", v)).chain( ds_cfg.levels.iter().cloned());
+        for token in tokens {
+            canonical.push('&');
+            canonical.push_str( token.as_str());
+            canonical.push_str("=on");
 
-        for v in &ds_cfg.levels {
             query.push('&');
-            query.push_str(v.as_str());
+            query.extend( utf8_percent_encode( token.as_str(), QUERY_TOKEN));
             query.push_str("=on");
         }
 
-        HrrrDataSetRequest {ds: ds_cfg,query}
+        HrrrDataSetRequest {ds: ds_cfg, query, canonical}
     }
 }
 
 impl Hash for HrrrDataSetRequest {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        self.query.hash(state);
+        self.canonical.hash(state);
     }
 }
 
 impl PartialEq for HrrrDataSetRequest {
     fn eq(&self, other: &Self) -> bool {
-        self.query == other.query
+        self.canonical == other.canonical
     }
 }
 impl Eq for HrrrDataSetRequest {}
@@ -225,79 +305,284 @@ fn get_filename (cfg: &HrrrConfig, ds: &HrrrDataSetConfig, dt: &DateTime<Utc>, s
     format!("hrrr-wrfsfcf-{}-{}-{:4}{:02}{:02}-{:02}+{:02}.grib2", cfg.region, ds.name, dt.year(),dt.month(),dt.day(),dt.hour(), step)
 } 
 
-// NOMADS file name: hrrr.t15z.wrfsfcf08.grib2
-fn get_nomad_filename (dt: &DateTime<Utc>, step: usize) -> String {
-    format!("hrrr.t{:02}z.wrfsfcf{:02}.grib2", dt.hour(), step)
+/// the name of a complete (not subsetted) GRIB2 file as served by mirrors. This is not specific to a data set and
+/// has to differ from [`get_filename`] so that we never mistake it for a verified subset
+pub fn get_full_filename (cfg: &HrrrConfig, dt: &DateTime<Utc>, step: usize) -> String {
+    format!("hrrr-wrfsfcf-{}-{:4}{:02}{:02}-{:02}+{:02}-full.grib2", cfg.region, dt.year(),dt.month(),dt.day(),dt.hour(), step)
+}
+
+/// NOMADS file name for the configured region/product, e.g. "hrrr.t15z.wrfsfcf08.grib2" (CONUS) or "hrrr.t15z.wrfsfcf08.ak.grib2" (Alaska)
+pub fn get_nomad_filename (cfg: &HrrrConfig, dt: &DateTime<Utc>, step: usize) -> String {
+    expand_file_pattern( &cfg.file_pattern, dt, step)
+}
+
+
+/// check if the file at `path` starts with the "GRIB" magic and ends with the "7777" end section marker
+pub fn check_grib_file (path: &Path) -> Result<()> {
+    let mut file = std::fs::File::open(path)?;
+    let len = file.metadata()?.len();
+    let mut magic = [0u8;4];
+
+    if len >= 8 {
+        file.read_exact( &mut magic)?;
+        if &magic == b"GRIB" {
+            file.seek( SeekFrom::End(-4))?;
+            file.read_exact( &mut magic)?;
+            if &magic == b"7777" { return Ok(()) }
+        }
+    }
+    Err( op_failed("not a GRIB2 response"))
+}
+
+/// a message entry of a NOMADS GRIB2 `.idx` file, e.g. "71:38232148:d=2024102112:TMP:2 m above ground:8 hour fcst:"
+#[derive(Debug,Clone,PartialEq)]
+pub struct GribIdxEntry {
+    pub msg_no: usize,
+    pub offset: u64,
+    pub field: String,
+    pub level: String,
+}
+
+/// parse the text of a GRIB2 `.idx` file. Empty lines are ignored
+pub fn parse_grib_idx (txt: &str)->Result<Vec<GribIdxEntry>> {
+    let mut entries = Vec::new();
+    for line in txt.lines().map( |l| l.trim()).filter( |l| !l.is_empty()) {
+        let parts: Vec<&str> = line.split(':').collect();
+        if parts.len() < 5 { return Err( op_failed( format!("malformed idx line: {}", line))) }
+
+        let msg_no = parts[0].parse::<usize>().map_err( |_| op_failed( format!("invalid idx message number: {}", line)))?;
+        let offset = parts[1].parse::<u64>().map_err( |_| op_failed( format!("invalid idx byte offset: {}", line)))?;
+        entries.push( GribIdxEntry { msg_no, offset, field: parts[3].to_string(), level: parts[4].to_string() });
+    }
+    Ok(entries)
+}
+
+/// get the lengths of all GRIB2 messages in the file at `path` from their section 0 headers. Returns an error
+/// if the message sequence does not exactly cover the file
+pub fn get_grib_message_lengths (path: &Path)->Result<Vec<u64>> {
+    let mut file = std::fs::File::open(path)?;
+    let file_len = file.metadata()?.len();
+    let mut lengths = Vec::new();
+    let mut offset = 0u64;
+    let mut header = [0u8;16];
+
+    while offset < file_len {
+        if offset + 16 > file_len { return Err( op_failed( format!("truncated GRIB2 message at offset {}", offset))) }
+        file.seek( SeekFrom::Start(offset))?;
+        file.read_exact( &mut header)?;
+        if &header[0..4] != b"GRIB" { return Err( op_failed( format!("no GRIB2 message at offset {}", offset))) }
+
+        let len = u64::from_be_bytes( header[8..16].try_into().unwrap());
+        if len < 16 || offset + len > file_len { return Err( op_failed( format!("inconsistent GRIB2 message length {} at offset {}", len, offset))) }
+        lengths.push( len);
+        offset += len;
+    }
+    Ok(lengths)
+}
+
+// data set levels are NOMADS filter tokens such as "lev_2_m_above_ground" whereas .idx files use "2 m above ground"
+fn idx_level (level: &str)->String {
+    level.strip_prefix("lev_").unwrap_or(level).replace('_', " ")
+}
+
+/// verify that the (subsetted) GRIB2 file at `path` contains the messages for all fields and levels of `ds` that
+/// are listed in the `.idx` entries of the complete file. Since subsetting by region only shrinks messages
+/// we also check that the downloaded messages do not exceed the byte ranges of the matching idx entries.
+/// The byte range of the last idx entry ends with the complete file, i.e. if that entry is requested we need
+/// `full_size` (see [`get_grib_file_size`])
+pub fn verify_grib_subset (path: &Path, idx: &[GribIdxEntry], ds: &HrrrDataSetConfig, full_size: Option<u64>)->Result<()> {
+    let levels: Vec<String> = ds.levels.iter().map( |l| idx_level(l)).collect();
+    let expected: Vec<usize> = idx.iter().enumerate()
+        .filter( |(_,e)| ds.fields.contains( &e.field) && levels.contains( &e.level))
+        .map( |(i,_)| i)
+        .collect();
+
+    for field in &ds.fields {
+        if !expected.iter().any( |i| &idx[*i].field == field) {
+            return Err( op_failed( format!("requested field {} not in idx", field)))
+        }
+    }
+
+    let lengths = get_grib_message_lengths( path)?;
+    if lengths.len() != expected.len() {
+        return Err( op_failed( format!("{} GRIB2 messages in file, expected {}", lengths.len(), expected.len())))
+    }
+
+    let mut max_len = 0;
+    for i in &expected {
+        let end = match idx.get( i+1) {
+            Some(next) => next.offset,
+            None => full_size.ok_or_else( || op_failed( format!("no file size to verify last idx entry {}", idx[*i].field)))?
+        };
+        max_len += end.saturating_sub( idx[*i].offset);
+    }
+
+    let len: u64 = lengths.iter().sum();
+    if len > max_len { return Err( op_failed( format!("file size {} exceeds idx byte ranges {}", len, max_len))) }
+    Ok(())
 }
 
+fn get_grib_url (cfg: &HrrrConfig, dt: &DateTime<Utc>, step: usize)->String {
+    let dir_url = cfg.dir_url_pattern.replace( "${yyyyMMdd}", &format!("{:04}{:02}{:02}", dt.year(), dt.month(), dt.day()));
+    format!("{}/{}", dir_url, get_nomad_filename( cfg, dt, step))
+}
 
-/// download a single file for given base date and forecast step
+fn get_idx_url (cfg: &HrrrConfig, dt: &DateTime<Utc>, step: usize)->String {
+    format!("{}.idx", get_grib_url( cfg, dt, step))
+}
+
+/// get the size of the complete GRIB2 file for the given base date and forecast step, without downloading it
+pub async fn get_grib_file_size (cfg: &HrrrConfig, dt: &DateTime<Utc>, step: usize)->Result<u64> {
+    let response = reqwest::Client::new().head( get_grib_url( cfg, dt, step)).send().await?;
+    if response.status() == reqwest::StatusCode::OK {
+        // don't use response.content_length() - the body of a HEAD response is always empty
+        response.headers().get( reqwest::header::CONTENT_LENGTH)
+            .and_then( |v| v.to_str().ok())
+            .and_then( |v| v.parse::<u64>().ok())
+            .ok_or_else( || op_failed("no content length for GRIB2 file"))
+    } else {
+        Err( op_failed( format!("GRIB2 file size request failed with code {}", response.status().as_str())))
+    }
+}
+
+/// retrieve and parse the `.idx` file of the complete GRIB2 file for the given base date and forecast step
+pub async fn get_grib_idx (cfg: &HrrrConfig, dt: &DateTime<Utc>, step: usize)->Result<Vec<GribIdxEntry>> {
+    let response = reqwest::get( get_idx_url( cfg, dt, step)).await?;
+    if response.status() == reqwest::StatusCode::OK {
+        parse_grib_idx( &response.text().await?)
+    } else {
+        Err( op_failed( format!("idx request failed with code {}", response.status().as_str())))
+    }
+}
+
+/// download a single file for given base date and forecast step from the primary (NOMADS) server
 pub async fn download_file (cfg: &HrrrConfig, ds: &HrrrDataSetRequest, dt: &DateTime<Utc>, step: usize, cache_dir: &PathBuf) -> Result<PathBuf> {
-    let filename = get_filename( cfg, &ds.ds, dt, step);
-    let nomad_filename = get_nomad_filename( dt, step);
+    download_file_from( &get_nomads_url( cfg, ds, dt, step), &get_filename( cfg, &ds.ds, dt, step), cache_dir).await
+}
 
-    let url = format!("{}?dir=%2Fhrrr.{:04}{:02}{:02}%2F{}&file={}&{}", 
+/// the NOMADS filter URL for the configured region and data set
+pub fn get_nomads_url (cfg: &HrrrConfig, ds: &HrrrDataSetRequest, dt: &DateTime<Utc>, step: usize) -> String {
+    format!("{}?dir=%2Fhrrr.{:04}{:02}{:02}%2F{}&file={}&{}", 
         cfg.url, 
         dt.year(), dt.month(), dt.day(),
         cfg.region,
-        nomad_filename,
+        get_nomad_filename( cfg, dt, step),
         ds.query
-    );
+    )
+}
 
-    let mut pb = cache_dir.clone();
-    pb.push(filename.as_str());
-    let path = pb.as_path();
-    let path_str = path.to_str().unwrap();
+/// download `url` into `cache_dir/filename` unless we already have that file
+async fn download_file_from (url: &str, filename: &str, cache_dir: &PathBuf) -> Result<PathBuf> {
+    let path = cache_dir.join(filename);
 
     if path.is_file() { // we already have it (from a previous run)
         info!("file {} already downloaded", filename);
-        Ok(path.to_path_buf())
+        Ok(path)
 
     } else { // we have to retrieve it from the NOAA server
         info!("downloading {}..", filename);
+        let file = download_temp_file( url).await?;
+        save_download( file, &path)
+    }
+}
 
-        let mut file = tempfile::NamedTempFile::new()?; // don't use path yet as that would expose partial downloads to the world
-        let mut response = reqwest::get(&url).await?;
-        while let Some(chunk) = response.chunk().await? {
-            file.write_all(&chunk)?;
+/// download `url` into a temp file. We don't use the cache path yet as that would expose partial downloads to the world
+async fn download_temp_file (url: &str) -> Result<tempfile::NamedTempFile> {
+    let mut file = tempfile::NamedTempFile::new()?;
+    let mut response = reqwest::get(url).await?;
+    while let Some(chunk) = response.chunk().await? {
+        file.write_all(&chunk)?;
+    }
+
+    if response.status() == reqwest::StatusCode::OK {
+        if std::fs::metadata(file.path())?.len() > 0 {
+            check_grib_file( file.path())?; // NOMADS might respond with an HTML error page and status 200
+            Ok(file)
+        } else {
+            Err(op_failed("empty file"))
         }
+    } else {
+        Err(op_failed( format!("request failed with code {}", response.status().as_str())))
+    }
+    // note existing temp files will be automatically closed/deleted when dropped
+}
 
-        if response.status() == reqwest::StatusCode::OK {
-            let file_len_kb = std::fs::metadata(file.path())?.len() / 1024;
-            if file_len_kb > 0 {
-                std::fs::rename(file.path(), path); // now make it visible to the world as a permanent file
-                info!("{} kB saved to {}", file_len_kb, path_str);
-                Ok(path.to_path_buf())
-            } else {
-                Err(op_failed("empty file"))
+fn save_download (file: tempfile::NamedTempFile, path: &Path) -> Result<PathBuf> {
+    let file_len_kb = std::fs::metadata(file.path())?.len() / 1024;
+    std::fs::rename(file.path(), path); // now make it visible to the world as a permanent file
+    info!("{} kB saved to {:?}", file_len_kb, path);
+    Ok(path.to_path_buf())
+}
+
+/// download `url` and (optionally) verify the downloaded file against the `.idx` of the complete file before it is
+/// moved into the cache. Already cached files are not verified again. If we cannot get the `.idx` we keep the file
+async fn download_verified_file_from (cfg: &HrrrConfig, ds: &HrrrDataSetRequest, dt: &DateTime<Utc>, step: usize,
+                                      url: &str, filename: &str, cache_dir: &PathBuf, verify: bool) -> Result<PathBuf> {
+    let path = cache_dir.join(filename);
+    if !verify || path.is_file() {
+        return download_file_from( url, filename, cache_dir).await
+    }
+
+    info!("downloading {}..", filename);
+    let file = download_temp_file( url).await?;
+
+    match get_grib_idx( cfg, dt, step).await {
+        Ok(idx) => {
+            let full_size = get_grib_file_size( cfg, dt, step).await.ok(); // only needed if we requested the last idx entry
+            if let Err(e) = verify_grib_subset( file.path(), &idx, &ds.ds, full_size) {
+                warn!("rejecting {}: {}", filename, e);
+                return Err(e) // the temp file is deleted when dropped
             }
-        } else {
-            Err(op_failed( format!("request failed with code {}", response.status().as_str())))
         }
-        // note existing temp files will be automatically closed/deleted when dropped
+        Err(e) => warn!("cannot verify {} (keeping it): {}", filename, e)
     }
+
+    save_download( file, &path)
 }
 
-/// account for slightly varying file schedule and availability
+/// account for slightly varying file schedule and availability. If the primary server fails `max_retry` times we
+/// fall through to the configured mirrors (in order), each of them again with `max_retry` retries.
+/// Since mirrors serve complete files we store those under [`get_full_filename`], which is also returned if we
+/// already have such a file for the requested base date and step
 pub async fn download_file_with_retry (cfg: &HrrrConfig, ds: &HrrrDataSetRequest, dt: &DateTime<Utc>, step: usize, cache_dir: &PathBuf) -> Result<PathBuf> {
-    let mut retry = 0;
-    loop {
-        match download_file( cfg, ds, dt, step, cache_dir).await {
-            Ok(path) => {
-                return Ok(path)
-            }
-            Err(e) => {
-                //println!("@@ step {} : {} failed with {e:?}, at min {}, retry {retry}", step, *dt + (ONE_HOUR * step as u32), Utc::now().minute() + 60);
-                if retry < cfg.max_retry {
-                    info!("step {} retry {}/{} in {} sec", step, retry, cfg.max_retry, cfg.retry_delay.as_secs());
-                    tokio::time::sleep(cfg.retry_delay).await;
-                    retry += 1;
-                } else {
-                    return Err(e)
+    let full_filename = get_full_filename( cfg, dt, step);
+    if !cfg.mirrors.is_empty() && !cache_dir.join( get_filename( cfg, &ds.ds, dt, step)).is_file() {
+        let full_path = cache_dir.join( &full_filename);
+        if full_path.is_file() {
+            info!("file {} already downloaded", full_filename);
+            return Ok(full_path)
+        }
+    }
+
+    let mut sources: Vec<(&str,String,String)> = vec![ ("primary", get_nomads_url( cfg, ds, dt, step), get_filename( cfg, &ds.ds, dt, step)) ];
+    for mirror in &cfg.mirrors {
+        sources.push( (mirror.name.as_str(), mirror.url( &cfg.region, dt, step), full_filename.clone()));
+    }
+
+    let mut last_err = op_failed("no download source");
+    for (i, (source, url, filename)) in sources.iter().enumerate() {
+        let verify = cfg.verify_idx && i == 0; // mirrors serve complete files
+        let mut retry = 0;
+        loop {
+            match download_verified_file_from( cfg, ds, dt, step, url, filename, cache_dir, verify).await {
+                Ok(path) => {
+                    return Ok(path)
+                }
+                Err(e) => {
+                    if retry < cfg.max_retry {
+                        info!("step {} retry {}/{} from {} in {} sec", step, retry, cfg.max_retry, source, cfg.retry_delay.as_secs());
+                        tokio::time::sleep(cfg.retry_delay).await;
+                        retry += 1;
+                    } else {
+                        warn!("step {} failed from {}: {}", step, source, e);
+                        last_err = e;
+                        break
+                    }
                 }
             }
         }
     }
+    Err(last_err)
 }
 
 
@@ -322,30 +607,111 @@ pub struct HrrrFileAvailable {
     pub path: PathBuf,
 }
 
-pub async fn process_download_requests<A> (rx: MpscReceiver<DownloadCmd>, cfg: Arc<HrrrConfig>, cache_dir: PathBuf, action: A) 
+pub async fn process_download_requests<A> (rx: MpscReceiver<DownloadCmd>, cfg: Arc<HrrrConfig>, cache_dir: PathBuf, action: A, cancel: CancellationToken) 
     where A: DataAction<HrrrFileAvailable>
 {
-    remove_old_files( &cache_dir, cfg.max_age);
-    let mut last_cleanup = SystemTime::now();
+    let download_cfg = cfg.clone();
+    let download_dir = cache_dir.clone();
+
+    // consult the cache index before we probe the filesystem or issue a server request. Note that cache entry ages
+    // are file (wall clock) ages since this is what remove_old_cache_files uses - the clock only affects schedules
+    let mut index = CacheIndex::load( &cache_dir);
+    index.remove_stale( cfg.max_age, Utc::now());
+    let index = Arc::new( Mutex::new( index));
+
+    process_requests_until( rx, cfg, cache_dir, action, cancel, |request: HrrrFileRequest| {
+        let cfg = download_cfg.clone();
+        let cache_dir = download_dir.clone();
+        let index = index.clone();
+        async move {
+            let key = CacheIndex::key( &cfg.region, request.ds.as_ref(), &request.base, request.step);
+            let cached = index.lock().unwrap().get( &key, cfg.max_age, Utc::now());
+            if let Some(path) = cached {
+                info!("file {:?} already downloaded", path.file_name().unwrap_or_default());
+                return (request, Ok(path))
+            }
 
-    loop {
-        match recv(&rx).await {
-            Ok(DownloadCmd::GetFile(request)) => {
-                if let Ok(path) = download_file_with_retry(cfg.as_ref(), request.ds.as_ref(), &request.base, request.step, &cache_dir).await {
-                    let data = HrrrFileAvailable { request, path };
-                    action.execute(data).await;
-                } else {
-                    warn!("step {}+{} permanently failed", request.base, request.step);
+            let res = download_file_with_retry( cfg.as_ref(), request.ds.as_ref(), &request.base, request.step, &cache_dir).await;
+            if let Ok(path) = &res {
+                let mut index = index.lock().unwrap();
+                index.remove_stale( cfg.max_age, Utc::now());
+                if let Err(e) = index.insert( key, path).and_then( |_| index.save()) {
+                    warn!("failed to update cache index: {}", e);
                 }
             }
-            Ok(DownloadCmd::Terminate) => { break }
-            Err(_) => { break } // request queue closed, no use to go on
+            (request, res)
+        }
+    }).await
+}
+
+/// files in the cache dir that were modified within this duration are never removed by the periodic cleanup, to
+/// avoid deleting files that were just renamed from their download temp file
+pub const CLEANUP_GRACE: Duration = Duration::from_secs(60);
+
+/// remove cache files that are older than `max_age` but skip recently modified files (see [`CLEANUP_GRACE`]) and
+/// the target paths of downloads that are still in flight
+pub fn remove_old_cache_files (cache_dir: &Path, max_age: Duration, in_flight: &HashSet<PathBuf>)->Result<usize> {
+    Ok( remove_old_files_except( &cache_dir, max_age, CLEANUP_GRACE, |path| in_flight.contains(path))? )
+}
+
+/// get the path in `cache_dir` for the file of a given request
+pub fn get_request_path (cfg: &HrrrConfig, request: &HrrrFileRequest, cache_dir: &Path)->PathBuf {
+    cache_dir.join( get_filename( cfg, &request.ds.ds, &request.base, request.step))
+}
+
+/// process `GetFile` requests from `rx` with up to `cfg.max_concurrent_downloads` concurrent `download` futures,
+/// executing `action` for each successfully completed download (in order of completion). This is separated from
+/// [`process_download_requests`] so that we can use different download functions
+pub async fn process_requests<A,D,F> (rx: MpscReceiver<DownloadCmd>, cfg: Arc<HrrrConfig>, cache_dir: PathBuf, action: A, download: D)
+    where A: DataAction<HrrrFileAvailable>, D: Fn(HrrrFileRequest)->F, F: Future<Output=(HrrrFileRequest,Result<PathBuf>)>
+{
+    process_requests_until( rx, cfg, cache_dir, action, CancellationToken::new(), download).await
+}
+
+/// cancellable version of [`process_requests`]. Once `cancel` is triggered we stop accepting new requests, i.e.
+/// requests that are still queued in `rx` are dropped. Downloads that are already in flight are completed
+pub async fn process_requests_until<A,D,F> (rx: MpscReceiver<DownloadCmd>, cfg: Arc<HrrrConfig>, cache_dir: PathBuf, action: A, 
+                                            cancel: CancellationToken, download: D)
+    where A: DataAction<HrrrFileAvailable>, D: Fn(HrrrFileRequest)->F, F: Future<Output=(HrrrFileRequest,Result<PathBuf>)>
+{
+    // target paths of started but not yet completed downloads, which are exempt from cleanup
+    let in_flight: Mutex<HashSet<PathBuf>> = Mutex::new( HashSet::new());
+
+    remove_old_cache_files( &cache_dir, cfg.max_age, &in_flight.lock().unwrap());
+    let mut last_cleanup = SystemTime::now();
+
+    let requests = futures::stream::unfold( (rx,cancel), |(rx,cancel)| async move {
+        tokio::select! {
+            biased;
+            _ = cancel.cancelled() => None, // drop whatever is still queued
+            cmd = recv(&rx) => match cmd {
+                Ok(DownloadCmd::GetFile(request)) => Some( (request, (rx,cancel))),
+                Ok(DownloadCmd::Terminate) => None,
+                Err(_) => None // request queue closed, no use to go on
+            }
+        }
+    });
+    let start_download = |request: HrrrFileRequest| {
+        in_flight.lock().unwrap().insert( get_request_path( &cfg, &request, &cache_dir));
+        download( request)
+    };
+    let mut results = pin!( requests.map( start_download).buffer_unordered( cfg.max_concurrent_downloads.max(1)));
+
+    while let Some((request,res)) = results.next().await {
+        in_flight.lock().unwrap().remove( &get_request_path( &cfg, &request, &cache_dir));
+
+        match res {
+            Ok(path) => {
+                let data = HrrrFileAvailable { request, path };
+                action.execute(data).await;
+            }
+            Err(_) => warn!("step {}+{} permanently failed", request.base, request.step)
         }
 
         let now = SystemTime::now();
         if let Ok(elapsed) = now.duration_since(last_cleanup) {
             if elapsed > cfg.max_age {
-                remove_old_files( &cache_dir, cfg.max_age);
+                remove_old_cache_files( &cache_dir, cfg.max_age, &in_flight.lock().unwrap());
                 last_cleanup = now;
             }
         }
@@ -354,13 +720,20 @@ pub async fn process_download_requests<A> (rx: MpscReceiver<DownloadCmd>, cfg: A
 
 pub fn spawn_download_task<A> (cfg: Arc<HrrrConfig>, cache_dir: PathBuf, action: A)->Result<(JoinHandle<()>,MpscSender<DownloadCmd>)>
      where A: DataAction<HrrrFileAvailable> + 'static
+{
+    spawn_cancellable_download_task( cfg, cache_dir, action, CancellationToken::new())
+}
+
+/// spawn a download task that stops processing queued requests once `cancel` is triggered (see [`process_requests_until`])
+pub fn spawn_cancellable_download_task<A> (cfg: Arc<HrrrConfig>, cache_dir: PathBuf, action: A, cancel: CancellationToken)->Result<(JoinHandle<()>,MpscSender<DownloadCmd>)>
+     where A: DataAction<HrrrFileAvailable> + 'static
 {
     let (tx,rx) = create_mpsc_sender_receiver::<DownloadCmd>(128);
-    Ok( (spawn("hrrr-download", process_download_requests( rx, cfg, cache_dir, action))?, tx) )
+    Ok( (spawn("hrrr-download", process_download_requests( rx, cfg, cache_dir, action, cancel))?, tx) )
 }
 
 
-/// get the next base hour and step (forecast hour) for a given time. This is used to determine when to retrieve the next available data set
+/// get the next base hour and step (forecast hour) for a given time (e.g. `clock.now()`). This is used to determine when to retrieve the next available data set
 /// and based on the following HRRR schedule model:
 /// ```
 ///     Bi   : base hour i (cycle base)
@@ -376,16 +749,17 @@ pub fn spawn_download_task<A> (cfg: Arc<HrrrConfig>, cache_dir: PathBuf, action:
 ///          ├──────────────────────|────>T                │
 ///                           dm: minutes(T) + 60
 ///```
-pub fn get_next_base_step (schedules: &HrrrSchedules, dt: &DateTime<Utc>)->(DateTime<Utc>,usize) {
+/// Returns a schedule error if the applicable schedule is empty (e.g. misconfigured or not yet populated)
+pub fn get_next_base_step (schedules: &HrrrSchedules, dt: &DateTime<Utc>)->Result<(DateTime<Utc>,usize)> {
    let mut dm = dt.minute();
    let mut base = full_hour(dt);
-   let mut sched = schedules.schedule_for(&base);
+   let mut sched = non_empty_schedule( schedules, &base)?;
    let mut step = 0;
 
    if dm < sched[0] {// base if previous hour
        dm += 60;
        base -= ONE_HOUR;
-       sched = schedules.schedule_for(&base);
+       sched = non_empty_schedule( schedules, &base)?;
    }
 
    if dm >= sched[sched.len() - 1] {
@@ -396,7 +770,12 @@ pub fn get_next_base_step (schedules: &HrrrSchedules, dt: &DateTime<Utc>)->(Date
        }
    }
 
-  (base, step)
+  Ok( (base, step) )
+}
+
+fn non_empty_schedule<'a> (schedules: &'a HrrrSchedules, base: &DateTime<Utc>)->Result<&'a Vec<u32>> {
+    let sched = schedules.schedule_for(base);
+    if sched.is_empty() { Err( schedule_error( format!("empty schedule for base hour {}", fmt_date(base)))) } else { Ok(sched) }
 }
 
 /// get all *most recent* forecasts for a `HrrrDataSetRequests` that are already available.
@@ -415,8 +794,26 @@ pub fn get_next_base_step (schedules: &HrrrSchedules, dt: &DateTime<Utc>)->(Date
 ///     ◻︎◻︎◻︎◻︎◻︎◻︎◻︎◻︎◻︎◻︎◻︎◻︎◻︎◻︎◻︎◼︎◼︎◼︎                                    (2) last cycle:    always completely available
 ///      ◼︎◼︎◼︎◼︎◼︎◼︎◼︎◼︎◼︎◼︎◼︎◼︎◼︎◼︎○○○○                                   (1) current cycle: might only be partially available
 /// ```
-pub async fn queue_available_forecasts (tx: &MpscSender<DownloadCmd>, ds: Arc<HrrrDataSetRequest>, schedules: &HrrrSchedules) {
-    let now = Utc::now();  // TODO - this should use sim time
+/// The current time is obtained from the provided `clock`, which allows to run this with simulation time.
+/// See [`plan_available_forecasts`] for the list of requests that are queued
+pub async fn queue_available_forecasts (tx: &MpscSender<DownloadCmd>, ds: Arc<HrrrDataSetRequest>, schedules: &HrrrSchedules, clock: &dyn Clock) {
+    for request in plan_available_forecasts( ds, schedules, clock) {
+        tx.send( DownloadCmd::GetFile( request)).await;
+    }
+}
+
+/// compute the (base,step) file requests [`queue_available_forecasts`] issues at the time of the provided `clock`,
+/// in the order in which they are queued (current cycle, previous cycle, last extended cycle).
+/// This does not access the network and hence can be used to verify cycle coverage (dry-run).
+/// Returns an empty list if any of the schedules is empty
+pub fn plan_available_forecasts (ds: Arc<HrrrDataSetRequest>, schedules: &HrrrSchedules, clock: &dyn Clock)->Vec<HrrrFileRequest> {
+    let now = clock.now();
+    let mut requests: Vec<HrrrFileRequest> = Vec::new();
+
+    if schedules.has_empty_schedule() {
+        warn!("cannot plan forecasts for {}: empty schedule", ds.ds.name);
+        return requests
+    }
 
     let mut dm = now.minute();
     let mut base = full_hour(&now);
@@ -429,18 +826,18 @@ pub async fn queue_available_forecasts (tx: &MpscSender<DownloadCmd>, ds: Arc<Hr
         sched = schedules.schedule_for(&base);
     }
 
-    //--- (1) queue what is available from current cycle
+    //--- (1) what is available from current cycle
     while (step < sched.len()) && (dm >= sched[step]) {
-        tx.send( DownloadCmd::GetFile( HrrrFileRequest{ds: ds.clone(),base,step}) ).await;
+        requests.push( HrrrFileRequest{ds: ds.clone(),base,step});
         step += 1;
     }
 
-    //--- (2) queue not-yet-updated forecasts from previous cycle
+    //--- (2) not-yet-updated forecasts from previous cycle
     base -= ONE_HOUR;
     sched = schedules.schedule_for(&base);
     step += 1;
     while step < sched.len() {
-        tx.send( DownloadCmd::GetFile( HrrrFileRequest{ds: ds.clone(),base,step}) ).await;
+        requests.push( HrrrFileRequest{ds: ds.clone(),base,step});
         step += 1;
     }
 
@@ -454,35 +851,53 @@ pub async fn queue_available_forecasts (tx: &MpscSender<DownloadCmd>, ds: Arc<Hr
         step += 1;
         sched = schedules.schedule_for(&base);
         while step < sched.len() {
-            tx.send( DownloadCmd::GetFile( HrrrFileRequest{ds: ds.clone(),base,step}) ).await;
+            requests.push( HrrrFileRequest{ds: ds.clone(),base,step});
             step += 1;
         }
     }
+
+    requests
 }
 
 
-/// non-actor function to spawn download task and periodically send it file requests for a fixed set of HrrrDataSetRequests
+/// non-actor function to spawn download task and periodically send it file requests for a fixed set of HrrrDataSetRequests.
+/// Forecast availability is computed based on the time of the provided `clock`
 pub async fn run_downloads<A> (conf: HrrrConfig, dsrs: Vec<Arc<HrrrDataSetRequest>>, schedules: HrrrSchedules, 
-                               is_periodic: bool, file_avail_action: A) -> Result<()>
+                               is_periodic: bool, file_avail_action: A, clock: &dyn Clock) -> Result<()>
     where A: DataAction<HrrrFileAvailable> + 'static
 {
+    run_cancellable_downloads( conf, dsrs, schedules, is_periodic, file_avail_action, clock, hrrr_cache_dir(), CancellationToken::new()).await
+}
+
+/// cancellable version of [`run_downloads`] that stores files in `cache_dir`. Once `cancel` is triggered we exit the
+/// periodic loop, drop pending requests, terminate the download task and wait for it to finish its in-flight downloads
+pub async fn run_cancellable_downloads<A> (conf: HrrrConfig, dsrs: Vec<Arc<HrrrDataSetRequest>>, schedules: HrrrSchedules, 
+                                           is_periodic: bool, file_avail_action: A, clock: &dyn Clock, 
+                                           cache_dir: PathBuf, cancel: CancellationToken) -> Result<()>
+    where A: DataAction<HrrrFileAvailable> + 'static
+{
+    if schedules.has_empty_schedule() { return Err( schedule_error("empty schedule")) }
+
     let check_interval = conf.check_interval;
-    let (download_task,tx) = spawn_download_task( Arc::new(conf), hrrr_cache_dir(), file_avail_action)?;
+    let (download_task,tx) = spawn_cancellable_download_task( Arc::new(conf), cache_dir, file_avail_action, cancel.clone())?;
 
     //--- initial download
     for dsr in &dsrs {
-        queue_available_forecasts( &tx, dsr.clone(), &schedules).await;
+        queue_available_forecasts( &tx, dsr.clone(), &schedules, clock).await;
     }
 
     //--- periodic download
     if is_periodic {
-        let now = Utc::now();
-        let (mut base, mut step) = get_next_base_step( &schedules, &now);
+        let now = clock.now();
+        let (mut base, mut step) = get_next_base_step( &schedules, &now)?;
 
         loop {
-            sleep( check_interval).await;
+            tokio::select! {
+                _ = cancel.cancelled() => break,
+                _ = sleep( check_interval) => {}
+            }
 
-            let now = Utc::now();
+            let now = clock.now();
             let mut sched = schedules.schedule_for(&base);
 
             while (now - base).num_minutes() as u32 >= sched[step] {
@@ -499,12 +914,11 @@ pub async fn run_downloads<A> (conf: HrrrConfig, dsrs: Vec<Arc<HrrrDataSetReques
                 }
             }
         }
-
-    } else {
-        tx.send( DownloadCmd::Terminate).await;
-        download_task.await.map_err(|e| op_failed(e))?;
     }
-    
+
+    tx.send( DownloadCmd::Terminate).await; // might already be closed if we got cancelled
+    download_task.await.map_err(|e| op_failed(e))?;
+
     Ok(())
 }
 
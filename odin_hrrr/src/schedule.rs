@@ -37,6 +37,11 @@ impl HrrrSchedules {
             &self.reg
         }
     }
+
+    /// is either the regular or the extended schedule empty (in which case we can't compute forecast availability)
+    pub fn has_empty_schedule (&self)->bool {
+        self.reg.is_empty() || self.ext.is_empty()
+    }
 }
 
 pub async fn get_schedules (conf: &HrrrConfig, statistic_schedules: bool)->Result<HrrrSchedules> {
@@ -83,18 +88,20 @@ pub async fn get_statistic_schedules (conf: &HrrrConfig) -> Result<HrrrSchedules
     match response.status() {
         reqwest::StatusCode::OK => {
             let txt = response.text().await?;
-            parse_schedules(&txt, delay_minutes)
+            parse_schedules(&txt, &conf.file_pattern, delay_minutes)
         }
         code => Err(schedule_error(format!("request failed with status {}", code.as_u16())))
     }
 }
 
 // get schedules for both regular (18h) and extended (48h) forecast cycles
-fn parse_schedules (txt: &String, delay_minutes: u32) -> Result<HrrrSchedules> {
+fn parse_schedules (txt: &String, file_pattern: &str, delay_minutes: u32) -> Result<HrrrSchedules> {
     // WATCH OUT - the HTML format for HRRR dir listings might change. This will cause the "unexpected directory contents.." error below
     // current line format (as of 10/21/2024): 
     // <tr><td><a href="hrrr.t00z.wrfsfcf06.grib2">hrrr.t00z.wrfsfcf06.grib2</a></td><td align="right">21-Oct-2024 00:53  </td><td align="right">137M</td></tr>
-    let re = Regex::new(r#"\.grib2">hrrr\.t(\d{2})z.wrfsfcf(\d{2}).grib2[^\d]*(\d+)-(.+)-(\d{4})\s+(\d{2}):(\d{2})"#).unwrap();
+    // the filename part is derived from the configured file_pattern, i.e. we only look at files of our region/product
+    let re = Regex::new( &format!(r#"">{}</a>[^\d]*(\d+)-(.+)-(\d{{4}})\s+(\d{{2}}):(\d{{2}})"#, file_pattern_regex( file_pattern)))
+        .map_err( |e| schedule_error( format!("invalid file pattern {}: {}", file_pattern, e)))?;
 
     let mut avg_reg_schedule: Vec<u32> = Vec::new();
     let mut max_reg_schedule: Vec<u32> = Vec::new();
@@ -108,8 +115,8 @@ fn parse_schedules (txt: &String, delay_minutes: u32) -> Result<HrrrSchedules> {
     for cap in re.captures_iter(txt.as_str()) {
         if cap.len() == 8 {
             // regex makes sure those are valid numbers, cap[0] is whole match
-            let bh: usize = cap[1].parse().unwrap();  // base hour (number of data points for fch) == HRRR "model cycle runtime hour"
-            let fch: usize = cap[2].parse().unwrap(); // forecast hour
+            let bh: usize = cap["HH"].parse().unwrap();  // base hour (number of data points for fch) == HRRR "model cycle runtime hour"
+            let fch: usize = cap["FF"].parse().unwrap(); // forecast hour
             let h: usize = cap[6].parse().unwrap(); // file creation minute
             let m: usize = cap[7].parse().unwrap(); // file creation minute
 
@@ -141,6 +148,13 @@ fn parse_schedules (txt: &String, delay_minutes: u32) -> Result<HrrrSchedules> {
 }
 
 
+/// turn a filename template such as "hrrr.t${HH}z.wrfsfcf${FF}.grib2" into a regex with "HH" and "FF" capture groups
+fn file_pattern_regex (file_pattern: &str)->String {
+    regex::escape( file_pattern)
+        .replace( r"\$\{HH\}", r"(?P<HH>\d{2})")
+        .replace( r"\$\{FF\}", r"(?P<FF>\d{2})")
+}
+
 fn update_schedule (avg_schedule: &mut Vec<u32>, max_schedule: &mut Vec<u32>, data_points: &mut Vec<u32>,
                     bh: usize, fch: usize, h: usize, m: usize, diff_minutes: u32) {
     if fch >= avg_schedule.len() { // first one for this forecast hour
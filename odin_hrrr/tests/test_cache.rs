@@ -0,0 +1,63 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of 
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License"); 
+ * you may not use this file except in compliance with the License. You may obtain a copy 
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+use std::{fs::File, sync::Arc, time::{Duration,SystemTime}};
+use chrono::{TimeZone,Utc};
+use odin_common::geo::GeoBoundingBox;
+use odin_hrrr::{CacheIndex, HrrrDataSetConfig, HrrrDataSetRequest};
+
+const MAX_AGE: Duration = Duration::from_secs(7200);
+
+#[test]
+fn test_cache_index() {
+    let cache_dir = tempfile::tempdir().unwrap();
+    let bbox = GeoBoundingBox::from_wsen_degrees( &[-123.0, 37.0, -121.0, 39.0]);
+    let ds = Arc::new( HrrrDataSetRequest::new( HrrrDataSetConfig::new( "test".to_string(), bbox, &["TMP"], &["lev_2_m_above_ground"])));
+    let base = Utc.with_ymd_and_hms( 2024, 10, 21, 12, 0, 0).unwrap();
+
+    let fresh_key = CacheIndex::key( "conus", &ds, &base, 1);
+    let stale_key = CacheIndex::key( "conus", &ds, &base, 2);
+    assert_ne!( fresh_key, stale_key);
+
+    let fresh_path = cache_dir.path().join("fresh.grib2");
+    let stale_path = cache_dir.path().join("stale.grib2");
+    std::fs::write( &fresh_path, b"GRIB...7777").unwrap();
+    std::fs::write( &stale_path, b"GRIB...7777").unwrap();
+    File::options().write(true).open( &stale_path).unwrap().set_modified( SystemTime::now() - MAX_AGE * 2).unwrap();
+
+    let mut index = CacheIndex::load( cache_dir.path());
+    assert_eq!( index.len(), 0);
+    index.insert( fresh_key.clone(), &fresh_path).unwrap();
+    index.insert( stale_key.clone(), &stale_path).unwrap();
+    index.save().unwrap();
+
+    //--- a restart loads the warm index
+    let now = Utc::now();
+    let mut index = CacheIndex::load( cache_dir.path());
+    assert_eq!( index.len(), 2);
+    assert_eq!( index.get( &fresh_key, MAX_AGE, now), Some(fresh_path.clone())); // no re-download
+    assert_eq!( index.get( &stale_key, MAX_AGE, now), None); // needs to be downloaded again
+    assert_eq!( index.get( &CacheIndex::key( "conus", &ds, &base, 3), MAX_AGE, now), None); // never downloaded
+
+    // entries from the future (negative age) are not trusted
+    assert_eq!( index.get( &fresh_key, MAX_AGE, now - chrono::Duration::hours(1)), None);
+
+    index.remove_stale( MAX_AGE, now);
+    assert_eq!( index.len(), 1);
+
+    //--- entries of files that were removed (e.g. by cache cleanup) are dropped
+    std::fs::remove_file( &fresh_path).unwrap();
+    assert_eq!( index.get( &fresh_key, MAX_AGE, now), None);
+    assert_eq!( index.len(), 0);
+}
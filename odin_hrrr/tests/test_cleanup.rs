@@ -0,0 +1,50 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of 
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License"); 
+ * you may not use this file except in compliance with the License. You may obtain a copy 
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+use std::{collections::HashSet, fs::File, path::Path, time::{Duration,SystemTime}};
+use odin_hrrr::{remove_old_cache_files, CLEANUP_GRACE};
+
+fn create_file (path: &Path, age: Duration) {
+    let file = File::create( path).unwrap();
+    file.set_modified( SystemTime::now() - age).unwrap();
+}
+
+#[test]
+fn test_cleanup_grace() {
+    let cache_dir = tempfile::tempdir().unwrap();
+    let fresh = cache_dir.path().join("fresh.grib2");
+    let old = cache_dir.path().join("old.grib2");
+    create_file( &fresh, Duration::ZERO);
+    create_file( &old, CLEANUP_GRACE * 2);
+
+    // even with a tiny max_age the fresh file is within the grace window
+    let n = remove_old_cache_files( cache_dir.path(), Duration::from_millis(1), &HashSet::new()).unwrap();
+    assert_eq!( n, 1);
+    assert!( fresh.is_file());
+    assert!( !old.exists());
+}
+
+#[test]
+fn test_cleanup_in_flight() {
+    let cache_dir = tempfile::tempdir().unwrap();
+    let in_flight = cache_dir.path().join("in_flight.grib2");
+    let old = cache_dir.path().join("old.grib2");
+    create_file( &in_flight, CLEANUP_GRACE * 2);
+    create_file( &old, CLEANUP_GRACE * 2);
+
+    let n = remove_old_cache_files( cache_dir.path(), Duration::from_millis(1), &HashSet::from( [in_flight.clone()])).unwrap();
+    assert_eq!( n, 1);
+    assert!( in_flight.is_file());
+    assert!( !old.exists());
+}
@@ -0,0 +1,85 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of 
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License"); 
+ * you may not use this file except in compliance with the License. You may obtain a copy 
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+use std::sync::Arc;
+use chrono::{DateTime,TimeZone,Utc};
+use odin_actor::prelude::*;
+use odin_common::{geo::GeoBoundingBox, sim_clock::{Clock,SimClock}};
+use odin_hrrr::{get_next_base_step, queue_available_forecasts, schedule::HrrrSchedules, DownloadCmd, HrrrDataSetConfig, HrrrDataSetRequest, OdinHrrrError};
+
+fn schedules()->HrrrSchedules {
+    HrrrSchedules {
+        reg: (0..19).map(|i| 50 + 2*i).collect(), // minutes after base hour
+        ext: (0..49).map(|i| 50 + i).collect(),
+    }
+}
+
+fn dsr()->Arc<HrrrDataSetRequest> {
+    let bbox = GeoBoundingBox::from_wsen_degrees( &[-123.0, 37.0, -121.0, 39.0]);
+    Arc::new( HrrrDataSetRequest::new( HrrrDataSetConfig::new( "test".to_string(), bbox, &["TMP"], &["lev_2_m_above_ground"])))
+}
+
+fn utc (h: u32, m: u32)->DateTime<Utc> {
+    Utc.with_ymd_and_hms( 2024, 10, 21, h, m, 0).unwrap()
+}
+
+async fn queued (schedules: &HrrrSchedules, clock: &dyn Clock)->Vec<(DateTime<Utc>,usize)> {
+    let (tx,rx) = create_mpsc_sender_receiver::<DownloadCmd>(128);
+    queue_available_forecasts( &tx, dsr(), schedules, clock).await;
+    drop(tx);
+
+    let mut requests = Vec::new();
+    while let Ok(cmd) = recv(&rx).await {
+        if let DownloadCmd::GetFile(req) = cmd { requests.push( (req.base, req.step)) }
+    }
+    requests
+}
+
+#[tokio::test]
+async fn test_sim_clock_queue() {
+    let schedules = schedules();
+    let clock = SimClock::new( utc(14,55), 0); // frozen unless set
+
+    //--- before the end of cycle 14
+    assert_eq!( get_next_base_step( &schedules, &clock.now()).unwrap(), (utc(14,0), 3));
+    let requests = queued( &schedules, &clock).await;
+    let current: Vec<usize> = requests.iter().filter(|(b,_)| *b == utc(14,0)).map(|(_,s)| *s).collect();
+    assert_eq!( current, vec![0,1,2]);
+    assert!( requests.iter().filter(|(b,_)| *b == utc(13,0)).all(|(_,s)| *s >= 4)); // superseded steps of prev cycle are skipped
+    assert!( requests.iter().any(|(b,s)| *b == utc(12,0) && *s == 48)); // last extended cycle
+
+    //--- after the hour we are still in cycle 14 (dm = 65), but more steps are available
+    clock.set( utc(15,5));
+    assert_eq!( get_next_base_step( &schedules, &clock.now()).unwrap(), (utc(14,0), 8));
+    let requests = queued( &schedules, &clock).await;
+    let current: Vec<usize> = requests.iter().filter(|(b,_)| *b == utc(14,0)).map(|(_,s)| *s).collect();
+    assert_eq!( current, (0..8).collect::<Vec<usize>>());
+    assert!( !requests.iter().any(|(b,_)| *b == utc(15,0)));
+
+    //--- cycle 14 completed (last step at 14:00 + 86min), next request is step 0 of cycle 15
+    clock.set( utc(15,40));
+    assert_eq!( get_next_base_step( &schedules, &clock.now()).unwrap(), (utc(15,0), 0));
+}
+
+#[tokio::test]
+async fn test_empty_schedule() {
+    let schedules = HrrrSchedules { reg: Vec::new(), ext: Vec::new() };
+    let clock = SimClock::new( utc(14,55), 0);
+
+    match get_next_base_step( &schedules, &clock.now()) {
+        Err(OdinHrrrError::ScheduleError(msg)) => assert!( msg.contains("empty schedule")),
+        other => panic!("expected schedule error, got {other:?}")
+    }
+    assert!( queued( &schedules, &clock).await.is_empty()); // no panic, nothing queued
+}
@@ -0,0 +1,148 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of 
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License"); 
+ * you may not use this file except in compliance with the License. You may obtain a copy 
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+use std::{path::PathBuf, sync::{Arc, atomic::{AtomicUsize,Ordering}}, time::{Duration,Instant}};
+use chrono::{TimeZone,Utc};
+use odin_actor::prelude::*;
+use odin_action::data_action;
+use odin_common::geo::GeoBoundingBox;
+use odin_common::sim_clock::SimClock;
+use odin_hrrr::{process_requests, process_requests_until, run_cancellable_downloads, schedule::HrrrSchedules, 
+    DownloadCmd, HrrrConfig, HrrrDataSetConfig, HrrrDataSetRequest, HrrrFileAvailable, HrrrFileRequest};
+use tokio_util::sync::CancellationToken;
+
+const N_REQUESTS: usize = 12;
+const MAX_CONCURRENT: usize = 4;
+const DOWNLOAD_TIME: Duration = Duration::from_millis(100);
+
+#[tokio::test]
+async fn test_bounded_concurrent_downloads() {
+    let cache_dir = tempfile::tempdir().unwrap();
+    let cfg = Arc::new( HrrrConfig { max_concurrent_downloads: MAX_CONCURRENT, ..HrrrConfig::default() });
+
+    let bbox = GeoBoundingBox::from_wsen_degrees( &[-123.0, 37.0, -121.0, 39.0]);
+    let ds = Arc::new( HrrrDataSetRequest::new( HrrrDataSetConfig::new( "test".to_string(), bbox, &["TMP"], &["lev_2_m_above_ground"])));
+    let base = Utc.with_ymd_and_hms( 2024, 10, 21, 12, 0, 0).unwrap();
+
+    let (tx,rx) = create_mpsc_sender_receiver::<DownloadCmd>(128);
+    for step in 0..N_REQUESTS {
+        tx.send( DownloadCmd::GetFile( HrrrFileRequest{ ds: ds.clone(), base, step })).await.unwrap();
+    }
+    tx.send( DownloadCmd::Terminate).await.unwrap();
+
+    let active = Arc::new( AtomicUsize::new(0));
+    let max_active = Arc::new( AtomicUsize::new(0));
+    let completed = Arc::new( AtomicUsize::new(0));
+
+    // mocked download that takes a fixed time and records how many downloads are active at the same time
+    let download = |request: HrrrFileRequest| {
+        let active = active.clone();
+        let max_active = max_active.clone();
+        async move {
+            let n = active.fetch_add( 1, Ordering::SeqCst) + 1;
+            max_active.fetch_max( n, Ordering::SeqCst);
+            tokio::time::sleep( DOWNLOAD_TIME).await;
+            active.fetch_sub( 1, Ordering::SeqCst);
+
+            let path = PathBuf::from( format!("step-{}.grib2", request.step));
+            (request, Ok(path))
+        }
+    };
+
+    let action = data_action!( let completed: Arc<AtomicUsize> = completed.clone() => |_data: HrrrFileAvailable| {
+        completed.fetch_add( 1, Ordering::SeqCst);
+        Ok(())
+    });
+
+    let start = Instant::now();
+    process_requests( rx, cfg, cache_dir.path().to_path_buf(), action, download).await;
+    let elapsed = start.elapsed();
+    println!("{} downloads in {} ms, max active: {}", N_REQUESTS, elapsed.as_millis(), max_active.load(Ordering::SeqCst));
+
+    assert_eq!( completed.load(Ordering::SeqCst), N_REQUESTS);
+    assert!( max_active.load(Ordering::SeqCst) <= MAX_CONCURRENT);
+    assert!( max_active.load(Ordering::SeqCst) > 1);
+    assert!( elapsed < DOWNLOAD_TIME * N_REQUESTS as u32 / 2); // serial would take N_REQUESTS * DOWNLOAD_TIME
+}
+
+#[tokio::test]
+async fn test_cancel_queued_requests() {
+    let cache_dir = tempfile::tempdir().unwrap();
+    let cfg = Arc::new( HrrrConfig { max_concurrent_downloads: 2, ..HrrrConfig::default() });
+
+    let bbox = GeoBoundingBox::from_wsen_degrees( &[-123.0, 37.0, -121.0, 39.0]);
+    let ds = Arc::new( HrrrDataSetRequest::new( HrrrDataSetConfig::new( "test".to_string(), bbox, &["TMP"], &["lev_2_m_above_ground"])));
+    let base = Utc.with_ymd_and_hms( 2024, 10, 21, 12, 0, 0).unwrap();
+
+    let (tx,rx) = create_mpsc_sender_receiver::<DownloadCmd>(128);
+    for step in 0..N_REQUESTS {
+        tx.send( DownloadCmd::GetFile( HrrrFileRequest{ ds: ds.clone(), base, step })).await.unwrap();
+    }
+    // note we do not send a Terminate - the cancel token has to stop the task
+
+    let cancel = CancellationToken::new();
+    let completed = Arc::new( AtomicUsize::new(0));
+
+    let download = |request: HrrrFileRequest| async move {
+        tokio::time::sleep( DOWNLOAD_TIME).await;
+        let path = PathBuf::from( format!("step-{}.grib2", request.step));
+        (request, Ok(path))
+    };
+
+    // cancel after the first completed download
+    let action = data_action!( let completed: Arc<AtomicUsize> = completed.clone(), let cancel: CancellationToken = cancel.clone() => |_data: HrrrFileAvailable| {
+        completed.fetch_add( 1, Ordering::SeqCst);
+        cancel.cancel();
+        Ok(())
+    });
+
+    let task = tokio::spawn( process_requests_until( rx, cfg, cache_dir.path().to_path_buf(), action, cancel.clone(), download));
+    let res = tokio::time::timeout( DOWNLOAD_TIME * 4, task).await;
+    assert!( res.is_ok()); // task joined within timeout
+
+    let n_completed = completed.load(Ordering::SeqCst);
+    println!("{} of {} downloads completed before cancellation", n_completed, N_REQUESTS);
+    assert!( n_completed >= 1 && n_completed <= 2); // only the downloads in flight when we got cancelled
+}
+
+#[tokio::test]
+async fn test_cancel_periodic_downloads() {
+    let cache_dir = tempfile::tempdir().unwrap();
+    let check_interval = Duration::from_millis(50);
+    let conf = HrrrConfig { check_interval, ..HrrrConfig::default() };
+    let schedules = HrrrSchedules {
+        reg: (0..19).map(|i| 50 + 2*i).collect(),
+        ext: (0..49).map(|i| 50 + i).collect(),
+    };
+    let clock = SimClock::new( Utc.with_ymd_and_hms( 2024, 10, 21, 14, 55, 0).unwrap(), 0);
+    let action = data_action!( => |_data: HrrrFileAvailable| Ok(()));
+
+    // cancel after the first check cycle
+    let cancel = CancellationToken::new();
+    let canceller = cancel.clone();
+    tokio::spawn( async move {
+        tokio::time::sleep( check_interval + check_interval/2).await;
+        canceller.cancel();
+    });
+
+    // no data set requests so that we don't access the network
+    let start = Instant::now();
+    let res = tokio::time::timeout( Duration::from_secs(2), 
+        run_cancellable_downloads( conf, Vec::new(), schedules, true, action, &clock, cache_dir.path().to_path_buf(), cancel)
+    ).await;
+    println!("periodic downloads terminated after {} ms", start.elapsed().as_millis());
+
+    assert!( res.is_ok()); // loop exited and download task joined within timeout
+    assert!( res.unwrap().is_ok());
+}
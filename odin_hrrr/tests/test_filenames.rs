@@ -0,0 +1,70 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of 
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License"); 
+ * you may not use this file except in compliance with the License. You may obtain a copy 
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+use chrono::{DateTime,TimeZone,Utc};
+use odin_common::geo::GeoBoundingBox;
+use odin_hrrr::{get_nomad_filename, get_nomads_url, HrrrConfig, HrrrDataSetConfig, HrrrDataSetRequest};
+
+fn base_time ()->DateTime<Utc> {
+    Utc.with_ymd_and_hms( 2024, 10, 21, 15, 0, 0).unwrap()
+}
+
+fn alaska_config ()->HrrrConfig {
+    HrrrConfig {
+        region: "alaska".to_string(),
+        url: "https://nomads.ncep.noaa.gov/cgi-bin/filter_hrrr_ak_2d.pl".to_string(),
+        dir_url_pattern: "https://nomads.ncep.noaa.gov/pub/data/nccf/com/hrrr/prod/hrrr.${yyyyMMdd}/alaska".to_string(),
+        file_pattern: "hrrr.t${HH}z.wrfsfcf${FF}.ak.grib2".to_string(),
+        ..HrrrConfig::default()
+    }
+}
+
+#[test]
+fn test_conus_filename() {
+    let cfg = HrrrConfig::default();
+    assert_eq!( get_nomad_filename( &cfg, &base_time(), 8), "hrrr.t15z.wrfsfcf08.grib2");
+
+    let subhourly = HrrrConfig { file_pattern: "hrrr.t${HH}z.wrfsubhf${FF}.grib2".to_string(), ..HrrrConfig::default() };
+    assert_eq!( get_nomad_filename( &subhourly, &base_time(), 1), "hrrr.t15z.wrfsubhf01.grib2");
+}
+
+#[test]
+fn test_alaska_filename() {
+    let cfg = alaska_config();
+    assert_eq!( get_nomad_filename( &cfg, &base_time(), 3), "hrrr.t15z.wrfsfcf03.ak.grib2");
+
+    let bbox = GeoBoundingBox::from_wsen_degrees( &[-150.0, 60.0, -148.0, 62.0]);
+    let ds = HrrrDataSetRequest::new( HrrrDataSetConfig::new( "anchorage".to_string(), bbox, &["TMP"], &["lev_2_m_above_ground"]));
+    let url = get_nomads_url( &cfg, &ds, &base_time(), 3);
+    println!("alaska url: {url}");
+    assert!( url.starts_with("https://nomads.ncep.noaa.gov/cgi-bin/filter_hrrr_ak_2d.pl?dir=%2Fhrrr.20241021%2Falaska&file=hrrr.t15z.wrfsfcf03.ak.grib2&"));
+}
+
+#[test]
+fn test_default_file_pattern() {
+    // configs that predate file_pattern still parse and use the CONUS surface product
+    let cfg: HrrrConfig = ron::from_str( r#"HrrrConfig(
+        region: "conus",
+        url: "https://nomads.ncep.noaa.gov/cgi-bin/filter_hrrr_2d.pl",
+        dir_url_pattern: "https://nomads.ncep.noaa.gov/pub/data/nccf/com/hrrr/prod/hrrr.${yyyyMMdd}/conus",
+        reg_first: 49, reg_last: 86, reg_len: 19,
+        ext_first: 49, ext_last: 108, ext_len: 49,
+        delay: Duration(secs:90,nanos:0),
+        check_interval: Duration(secs:30,nanos:0),
+        retry_delay: Duration(secs:60,nanos:0),
+        max_retry: 5,
+        max_age: Duration(secs:21600,nanos:0),
+    )"#).unwrap();
+    assert_eq!( cfg.file_pattern, "hrrr.t${HH}z.wrfsfcf${FF}.grib2");
+}
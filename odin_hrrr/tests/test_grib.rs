@@ -0,0 +1,45 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of 
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License"); 
+ * you may not use this file except in compliance with the License. You may obtain a copy 
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+use std::io::Write;
+use odin_hrrr::{check_grib_file, OdinHrrrError};
+
+fn tmp_file (content: &[u8])->tempfile::NamedTempFile {
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    file.write_all( content).unwrap();
+    file.flush().unwrap();
+    file
+}
+
+#[test]
+fn test_reject_html_response() {
+    let html = b"<!DOCTYPE html><html><body><h1>Error</h1><p>invalid parameter lev_2_m_above_grund</p></body></html>";
+    let file = tmp_file( html);
+
+    match check_grib_file( file.path()) {
+        Err(OdinHrrrError::OpFailed(msg)) => assert_eq!( msg, "not a GRIB2 response"),
+        other => panic!("HTML response not rejected: {other:?}")
+    }
+}
+
+#[test]
+fn test_accept_grib_response() {
+    let mut content = b"GRIB\0\0\0\x02".to_vec(); // magic, reserved, discipline, edition 2
+    content.extend_from_slice( &[0u8; 64]);
+    content.extend_from_slice( b"7777");
+    assert!( check_grib_file( tmp_file( &content).path()).is_ok());
+
+    content.truncate( content.len() - 4); // truncated download
+    assert!( check_grib_file( tmp_file( &content).path()).is_err());
+}
@@ -0,0 +1,165 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of 
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License"); 
+ * you may not use this file except in compliance with the License. You may obtain a copy 
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+use std::time::Duration;
+use chrono::{TimeZone,Utc};
+use tokio::{io::{AsyncReadExt,AsyncWriteExt}, net::TcpListener};
+use odin_common::geo::GeoBoundingBox;
+use odin_hrrr::{download_file_with_retry, parse_grib_idx, verify_grib_subset, HrrrConfig, HrrrDataSetConfig, HrrrDataSetRequest};
+
+// idx of the complete file, each message is 1000 bytes
+const FULL_IDX: &str = "\
+1:0:d=2024102112:REFC:entire atmosphere:3 hour fcst:
+2:1000:d=2024102112:TMP:2 m above ground:3 hour fcst:
+3:2000:d=2024102112:UGRD:10 m above ground:3 hour fcst:
+4:3000:d=2024102112:VGRD:10 m above ground:3 hour fcst:
+";
+
+// same as above but without the UGRD message
+const PARTIAL_IDX: &str = "\
+1:0:d=2024102112:REFC:entire atmosphere:3 hour fcst:
+2:1000:d=2024102112:TMP:2 m above ground:3 hour fcst:
+3:2000:d=2024102112:VGRD:10 m above ground:3 hour fcst:
+";
+
+/// a GRIB2 body with `n` minimal (header + end section) messages of `len` bytes each
+fn grib_messages (n: usize, len: usize)->Vec<u8> {
+    let mut body = Vec::new();
+    for _ in 0..n {
+        body.extend_from_slice( b"GRIB");
+        body.extend_from_slice( &[0, 0, 0, 2]); // reserved, discipline, edition
+        body.extend_from_slice( &(len as u64).to_be_bytes());
+        body.extend_from_slice( &vec![0u8; len - 20]);
+        body.extend_from_slice( b"7777");
+    }
+    body
+}
+
+fn request ()->HrrrDataSetRequest {
+    let bbox = GeoBoundingBox::from_wsen_degrees( &[-123.0, 37.0, -121.0, 39.0]);
+    HrrrDataSetRequest::new( HrrrDataSetConfig::new( "test".to_string(), bbox, &["TMP","UGRD"], &["lev_2_m_above_ground", "lev_10_m_above_ground"]))
+}
+
+/// a minimal HTTP server that responds with the given idx text for *.idx requests and with a GRIB2 subset
+/// of two messages otherwise
+async fn spawn_server (idx: &'static str)->String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = format!("http://{}", listener.local_addr().unwrap());
+
+    tokio::spawn( async move {
+        loop {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0u8; 4096];
+            let len = socket.read( &mut buf).await.unwrap();
+            let req = String::from_utf8_lossy( &buf[..len]).to_string();
+            let path = req.split_whitespace().nth(1).unwrap_or("").to_string();
+
+            let body = if path.ends_with(".idx") { idx.as_bytes().to_vec() } else { grib_messages( 2, 600) }; // > 1kB or it is rejected as empty
+            let mut response = format!("HTTP/1.1 200 OK\r\ncontent-length: {}\r\nconnection: close\r\n\r\n", body.len()).into_bytes();
+            response.extend_from_slice( &body);
+            socket.write_all( &response).await.unwrap();
+            socket.shutdown().await.ok();
+        }
+    });
+
+    addr
+}
+
+fn config (addr: &str)->HrrrConfig {
+    HrrrConfig {
+        url: format!("{}/filter_hrrr_2d.pl", addr),
+        dir_url_pattern: format!("{}/hrrr.${{yyyyMMdd}}/conus", addr),
+        retry_delay: Duration::from_millis(10),
+        max_retry: 0,
+        verify_idx: true,
+        ..HrrrConfig::default()
+    }
+}
+
+#[test]
+fn test_verify_grib_subset() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("subset.grib2");
+    let ds = request();
+    let idx = parse_grib_idx( FULL_IDX).unwrap();
+    assert_eq!( idx.len(), 4);
+    assert_eq!( (idx[2].offset, idx[2].field.as_str(), idx[2].level.as_str()), (2000, "UGRD", "10 m above ground"));
+
+    std::fs::write( &path, grib_messages( 2, 500)).unwrap();
+    verify_grib_subset( &path, &idx, &ds.ds, None).unwrap();
+
+    std::fs::write( &path, grib_messages( 1, 500)).unwrap(); // one message missing
+    assert!( verify_grib_subset( &path, &idx, &ds.ds, None).is_err());
+
+    std::fs::write( &path, grib_messages( 2, 1200)).unwrap(); // larger than the idx byte ranges
+    assert!( verify_grib_subset( &path, &idx, &ds.ds, None).is_err());
+
+    let mut truncated = grib_messages( 2, 500);
+    truncated.truncate( 900);
+    std::fs::write( &path, truncated).unwrap();
+    assert!( verify_grib_subset( &path, &idx, &ds.ds, None).is_err());
+}
+
+#[test]
+fn test_verify_last_idx_entry() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("subset.grib2");
+    let bbox = GeoBoundingBox::from_wsen_degrees( &[-123.0, 37.0, -121.0, 39.0]);
+    let ds = HrrrDataSetConfig::new( "test".to_string(), bbox, &["TMP","VGRD"], &["lev_2_m_above_ground", "lev_10_m_above_ground"]);
+    let idx = parse_grib_idx( FULL_IDX).unwrap(); // VGRD is the last entry, its byte range ends with the complete file
+
+    std::fs::write( &path, grib_messages( 2, 500)).unwrap();
+    assert!( verify_grib_subset( &path, &idx, &ds, None).is_err()); // can't silently skip the size check
+    verify_grib_subset( &path, &idx, &ds, Some(4000)).unwrap();
+
+    std::fs::write( &path, grib_messages( 2, 1200)).unwrap();
+    assert!( verify_grib_subset( &path, &idx, &ds, Some(4000)).is_err());
+}
+
+#[tokio::test]
+async fn test_verified_download() {
+    let addr = spawn_server( FULL_IDX).await;
+    let cache_dir = tempfile::tempdir().unwrap();
+    let base = Utc.with_ymd_and_hms( 2024, 10, 21, 12, 0, 0).unwrap();
+
+    let path = download_file_with_retry( &config(&addr), &request(), &base, 3, &cache_dir.path().to_path_buf()).await.unwrap();
+    assert!( path.is_file());
+}
+
+#[tokio::test]
+async fn test_rejected_download() {
+    let addr = spawn_server( PARTIAL_IDX).await;
+    let cache_dir = tempfile::tempdir().unwrap();
+    let base = Utc.with_ymd_and_hms( 2024, 10, 21, 12, 0, 0).unwrap();
+
+    let res = download_file_with_retry( &config(&addr), &request(), &base, 3, &cache_dir.path().to_path_buf()).await;
+    println!("result: {:?}", res);
+    assert!( res.unwrap_err().to_string().contains("UGRD"));
+    assert_eq!( std::fs::read_dir( cache_dir.path()).unwrap().count(), 0); // rejected file was removed
+}
+
+#[tokio::test]
+async fn test_cached_download_not_reverified() {
+    let cache_dir = tempfile::tempdir().unwrap();
+    let base = Utc.with_ymd_and_hms( 2024, 10, 21, 12, 0, 0).unwrap();
+
+    let addr = spawn_server( FULL_IDX).await;
+    let path = download_file_with_retry( &config(&addr), &request(), &base, 3, &cache_dir.path().to_path_buf()).await.unwrap();
+
+    // a server that would reject the file does not touch what we already have in the cache
+    let addr = spawn_server( PARTIAL_IDX).await;
+    let cached = download_file_with_retry( &config(&addr), &request(), &base, 3, &cache_dir.path().to_path_buf()).await.unwrap();
+    assert_eq!( cached, path);
+    assert!( path.is_file());
+}
@@ -0,0 +1,86 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of 
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License"); 
+ * you may not use this file except in compliance with the License. You may obtain a copy 
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+use std::{sync::{Arc,Mutex}, time::Duration};
+use chrono::{TimeZone,Utc};
+use tokio::{io::{AsyncReadExt,AsyncWriteExt}, net::TcpListener};
+use odin_common::geo::GeoBoundingBox;
+use odin_hrrr::{download_file_with_retry, HrrrConfig, HrrrDataSetConfig, HrrrDataSetRequest, HrrrMirror};
+
+/// a minimal HTTP server that responds with 404 for everything under /primary and with a GRIB2 body otherwise.
+/// Returns the server address and the list of received request paths
+async fn spawn_server ()->(String, Arc<Mutex<Vec<String>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = format!("http://{}", listener.local_addr().unwrap());
+    let requests = Arc::new( Mutex::new( Vec::new()));
+    let received = requests.clone();
+
+    tokio::spawn( async move {
+        loop {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0u8; 4096];
+            let len = socket.read( &mut buf).await.unwrap();
+            let req = String::from_utf8_lossy( &buf[..len]).to_string();
+            let path = req.split_whitespace().nth(1).unwrap_or("").to_string();
+            received.lock().unwrap().push( path.clone());
+
+            let response = if path.starts_with("/primary") {
+                b"HTTP/1.1 404 Not Found\r\ncontent-length: 0\r\nconnection: close\r\n\r\n".to_vec()
+            } else {
+                let mut body = b"GRIB".to_vec();
+                body.extend_from_slice( &[0u8; 2048]);
+                body.extend_from_slice( b"7777");
+                let mut r = format!("HTTP/1.1 200 OK\r\ncontent-length: {}\r\nconnection: close\r\n\r\n", body.len()).into_bytes();
+                r.extend_from_slice( &body);
+                r
+            };
+            socket.write_all( &response).await.unwrap();
+            socket.shutdown().await.ok();
+        }
+    });
+
+    (addr, requests)
+}
+
+#[tokio::test]
+async fn test_mirror_fallback() {
+    let (addr, requests) = spawn_server().await;
+    let cache_dir = tempfile::tempdir().unwrap();
+
+    let cfg = HrrrConfig {
+        url: format!("{}/primary/filter_hrrr_2d.pl", addr),
+        retry_delay: Duration::from_millis(10),
+        max_retry: 1,
+        mirrors: vec![ HrrrMirror {
+            name: "test-mirror".to_string(),
+            url_pattern: format!("{}/mirror/hrrr.${{yyyyMMdd}}/${{region}}/${{filename}}", addr),
+            filename_pattern: "hrrr.t${HH}z.wrfsfcf${FF}.grib2".to_string(),
+        }],
+        ..HrrrConfig::default()
+    };
+
+    let bbox = GeoBoundingBox::from_wsen_degrees( &[-123.0, 37.0, -121.0, 39.0]);
+    let ds = HrrrDataSetRequest::new( HrrrDataSetConfig::new( "test".to_string(), bbox, &["TMP"], &["lev_2_m_above_ground"]));
+    let base = Utc.with_ymd_and_hms( 2024, 10, 21, 12, 0, 0).unwrap();
+
+    let path = download_file_with_retry( &cfg, &ds, &base, 3, &cache_dir.path().to_path_buf()).await.unwrap();
+    assert!( path.is_file());
+    assert!( path.starts_with( cache_dir.path()));
+    assert!( path.to_str().unwrap().ends_with("-full.grib2")); // complete files are not stored under the subset name
+
+    let requests = requests.lock().unwrap();
+    println!("requests: {:?}", requests);
+    assert_eq!( requests.iter().filter( |p| p.starts_with("/primary")).count(), 2); // initial attempt + max_retry
+    assert_eq!( requests.last().unwrap(), "/mirror/hrrr.20241021/conus/hrrr.t12z.wrfsfcf03.grib2");
+}
@@ -0,0 +1,71 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of 
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License"); 
+ * you may not use this file except in compliance with the License. You may obtain a copy 
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+use std::{ops::RangeInclusive, sync::Arc};
+use chrono::{DateTime,TimeZone,Utc};
+use odin_common::{geo::GeoBoundingBox, sim_clock::SimClock};
+use odin_hrrr::{plan_available_forecasts, schedule::HrrrSchedules, HrrrDataSetConfig, HrrrDataSetRequest};
+
+fn schedules()->HrrrSchedules {
+    HrrrSchedules {
+        reg: (0..19).map(|i| 50 + 2*i).collect(), // minutes after base hour
+        ext: (0..49).map(|i| 50 + i).collect(),
+    }
+}
+
+fn dsr()->Arc<HrrrDataSetRequest> {
+    let bbox = GeoBoundingBox::from_wsen_degrees( &[-123.0, 37.0, -121.0, 39.0]);
+    Arc::new( HrrrDataSetRequest::new( HrrrDataSetConfig::new( "test".to_string(), bbox, &["TMP"], &["lev_2_m_above_ground"])))
+}
+
+fn utc (h: u32, m: u32)->DateTime<Utc> {
+    Utc.with_ymd_and_hms( 2024, 10, 21, h, m, 0).unwrap()
+}
+
+fn plan (h: u32, m: u32)->Vec<(DateTime<Utc>,usize)> {
+    let clock = SimClock::new( utc(h,m), 0);
+    plan_available_forecasts( dsr(), &schedules(), &clock).into_iter().map( |r| (r.base, r.step)).collect()
+}
+
+fn expected (cycles: &[(u32,RangeInclusive<usize>)])->Vec<(DateTime<Utc>,usize)> {
+    cycles.iter().flat_map( |(h,steps)| steps.clone().map( |s| (utc(*h,0), s))).collect()
+}
+
+#[test]
+fn test_plan_regular_cycle() {
+    // current regular cycle 14 is partially available, previous cycle 13 is regular, last extended cycle 12
+    assert_eq!( plan(14,55), expected( &[(14, 0..=2), (13, 4..=18), (12, 20..=48)]));
+}
+
+#[test]
+fn test_plan_extended_cycle() {
+    // current cycle 12 is extended, previous cycle 11 is regular so we also need the tail of extended cycle 06
+    assert_eq!( plan(12,55), expected( &[(12, 0..=5), (11, 7..=18), (6, 24..=48)]));
+}
+
+#[test]
+fn test_plan_after_hour() {
+    // dm = 90 for extended cycle 12 - its steps supersede all of the regular cycle 11
+    assert_eq!( plan(13,30), expected( &[(12, 0..=40), (6, 47..=48)]));
+
+    // dm = 90 for regular cycle 13 (completely available), previous cycle 12 is extended
+    assert_eq!( plan(14,30), expected( &[(13, 0..=18), (12, 20..=48)]));
+}
+
+#[test]
+fn test_plan_empty_schedule() {
+    let clock = SimClock::new( utc(14,55), 0);
+    let schedules = HrrrSchedules { reg: Vec::new(), ext: Vec::new() };
+    assert!( plan_available_forecasts( dsr(), &schedules, &clock).is_empty());
+}
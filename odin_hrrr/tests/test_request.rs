@@ -0,0 +1,49 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of 
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License"); 
+ * you may not use this file except in compliance with the License. You may obtain a copy 
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+use std::collections::HashSet;
+use odin_common::geo::GeoBoundingBox;
+use odin_hrrr::{HrrrDataSetConfig, HrrrDataSetRequest};
+
+const LEVEL: &str = "lev_0-0.1 m below ground";
+
+fn request (fields: &[&str], levels: &[&str])->HrrrDataSetRequest {
+    let bbox = GeoBoundingBox::from_wsen_degrees( &[-123.0, 37.0, -121.0, 39.0]);
+    HrrrDataSetRequest::new( HrrrDataSetConfig::new( "test".to_string(), bbox, fields, levels))
+}
+
+#[test]
+fn test_query_encoding() {
+    let req = request( &["TMP","UGRD"], &[LEVEL, "lev_2_m_above_ground"]);
+    assert!( !req.query.contains(' '));
+    assert!( req.query.contains("lev_0-0.1%20m%20below%20ground=on"));
+    assert!( req.query.contains("lev_2_m_above_ground=on")); // unreserved chars are not encoded
+
+    let url = reqwest::Url::parse( &format!("https://nomads.ncep.noaa.gov/cgi-bin/filter_hrrr_2d.pl?{}", req.query)).unwrap();
+    assert!( url.query_pairs().any( |(k,v)| k == LEVEL && v == "on"));
+    assert!( url.query_pairs().any( |(k,v)| k == "var_UGRD" && v == "on"));
+}
+
+#[test]
+fn test_request_equality() {
+    let a = request( &["TMP","UGRD"], &[LEVEL, "lev_2_m_above_ground"]);
+    let b = request( &["UGRD","TMP"], &["lev_2_m_above_ground", LEVEL]);
+    let c = request( &["TMP"], &[LEVEL]);
+
+    assert_eq!( a, b);
+    assert_ne!( a, c);
+
+    let set: HashSet<HrrrDataSetRequest> = [a, b, c].into_iter().collect();
+    assert_eq!( set.len(), 2);
+}
@@ -38,12 +38,12 @@ use proc_macro::TokenStream;
 use proc_macro2::{
 	Literal, Punct, Spacing, Span, TokenStream as TokenStream2, TokenTree
 };
-use quote::{format_ident, quote, ToTokens, TokenStreamExt};
+use quote::{format_ident, quote, quote_spanned, ToTokens, TokenStreamExt};
 use syn::{ 
 	self, parse::{Lookahead1, Parse, ParseStream, Result}, 
-    parse_macro_input, punctuated::{Punctuated}, visit::{self, Visit}, 
+    parse_macro_input, punctuated::{Punctuated}, spanned::Spanned, visit::{self, Visit}, 
     token::{self, Mut, Ref, Where, Colon, Gt, Lt, Comma, Paren, PathSep, Use, For, In}, 
-    Attribute, Block, Expr, ExprLit, ExprCall, ExprBlock, ExprMacro, ExprMethodCall, FnArg, Ident, ItemEnum, ItemFn, ItemStruct, Path, PathSegment, 
+    Attribute, Block, Expr, ExprLit, Lit, Meta, ExprCall, ExprBlock, ExprMacro, ExprMethodCall, FnArg, Ident, ItemEnum, ItemFn, ItemStruct, Path, PathSegment, 
     PredicateType, Stmt, Token, Type, TypePath, Visibility, WhereClause, WherePredicate, GenericParam, PathArguments,
     parenthesized
 };
@@ -85,15 +85,33 @@ macro_rules! stringify_path {
 /// }
 /// ```
 /// 
+/// If the struct spec is preceded by a `#[builder]` attribute the macro also generates a `<name>Builder` companion
+/// that is created with the same args as `new(..)` and has chainable `with_<field>(v)` setters for all fields. Its
+/// `build()` method uses the init exprs for fields that were not explicitly set:
+/// ```rust
+/// define_struct! {
+///     #[builder]
+///     pub MyStruct = 
+///         field_1: String,
+///         field_2: usize = { field_1.len() }
+/// }
+/// ...
+/// let s = MyStruct::builder( "blah".to_string()).with_field_2( 42).build();
+/// ```
+/// 
+/// Doc comments of fields without init exprs are added as an `# Arguments` section to the docs of the generated
+/// `new(..)` (and `builder(..)`) functions. Other field attributes (e.g. `#[serde(..)]`) are only emitted on the
+/// struct fields.
 #[proc_macro]
 pub fn define_struct (item: TokenStream) -> TokenStream {
-    let StructSpec{ attrs, visibility, name, generic_params, derives, where_clause, field_specs } = match syn::parse(item) {
+    let StructSpec{ attrs, visibility, name, generic_params, derives, where_clause, field_specs, has_builder } = match syn::parse(item) {
         Ok(struct_spec) => struct_spec,
         Err(e) => panic!( "expected \"structName [: Trait,..] = fieldSpec, ..\" got error: {:?}", e)
     };
     let generics = if generic_params.is_empty() { quote!{} } else { quote! { < #( #generic_params ),* > } };
     let derive_clause = if derives.is_empty() { quote!{} } else { quote! { #[derive( #( #derives ),* )] } };
     let inherent_impl = get_inherent_impl( &visibility, &name, &generic_params, &where_clause,  &field_specs);
+    let builder = if has_builder { get_builder( &visibility, &name, &generic_params, &where_clause,  &field_specs) } else { quote!{} };
 
     let new_item: TokenStream = quote! {
         #derive_clause
@@ -102,6 +120,7 @@ pub fn define_struct (item: TokenStream) -> TokenStream {
             #( #field_specs ),*
         }
         #inherent_impl
+        #builder
     }.into();
     //println!("-----\n{}\n-----", new_item.to_string());
     new_item
@@ -113,9 +132,11 @@ fn get_inherent_impl (visibility: &Visibility, name: &Ident, generic_params: &Ve
     let generics = if generic_params.is_empty() { quote!{} } else { quote! { < #( #generic_params ),* > } };
     let field_names: Vec<&Ident> = field_specs.iter().map( |f| &f.name).collect();
     let init_stmts: TokenStream2 = get_ctor_init_stmts( field_specs);
+    let ctor_docs: TokenStream2 = get_ctor_docs( field_specs);
 
     quote!{
         impl #generic_names #name #generics #where_clause {
+            #ctor_docs
             #visibility fn new ( #ctor_arg_list )->Self {
                 #init_stmts
                 #name { #( #field_names ),* }
@@ -124,6 +145,70 @@ fn get_inherent_impl (visibility: &Visibility, name: &Ident, generic_params: &Ve
     }
 }
 
+fn get_builder (visibility: &Visibility, name: &Ident, generic_params: &Vec<GenericParam>, where_clause: &Option<WhereClause>, field_specs: &Vec<FieldSpec>)->TokenStream2 {
+    let builder_name = format_ident!("{}Builder", name);
+    let ctor_arg_list: TokenStream2 = get_ctor_arg_list( field_specs);
+    let ctor_docs: TokenStream2 = get_ctor_docs( field_specs);
+    let generic_names = get_generic_names(generic_params);
+    let generics = if generic_params.is_empty() { quote!{} } else { quote! { < #( #generic_params ),* > } };
+    let field_names: Vec<&Ident> = field_specs.iter().map( |f| &f.name).collect();
+    let arg_names: Vec<&Ident> = field_specs.iter().filter( |f| f.init_expr.is_none()).map( |f| &f.name).collect();
+    let opt_names: Vec<&Ident> = field_specs.iter().filter( |f| f.init_expr.is_some()).map( |f| &f.name).collect();
+
+    // fields with init exprs are optional, the others are set from the ctor args
+    let builder_fields: Vec<TokenStream2> = field_specs.iter().map( |f| {
+        let fname = &f.name;
+        let ftype = &f.field_type;
+        if f.init_expr.is_some() { quote!{ #fname : Option<#ftype> } } else { quote!{ #fname : #ftype } }
+    }).collect();
+
+    let setters: Vec<TokenStream2> = field_specs.iter().map( |f| {
+        let fname = &f.name;
+        let ftype = &f.field_type;
+        let setter_name = format_ident!("with_{}", fname);
+        let value = if f.init_expr.is_some() { quote!{ Some(v) } } else { quote!{ v } };
+        quote!{
+            #visibility fn #setter_name (mut self, v: #ftype)->Self { self.#fname = #value; self }
+        }
+    }).collect();
+
+    // same order as in the ctor so that init exprs can refer to ctor args and preceding fields
+    let resolve_stmts: Vec<TokenStream2> = field_specs.iter().filter_map( |f| {
+        f.init_expr.as_ref().map( |init| {
+            let fname = &f.name;
+            let ftype = &f.field_type;
+            quote!{ let #fname : #ftype = match #fname { Some(v) => v, None => #init }; }
+        })
+    }).collect();
+
+    quote!{
+        #visibility struct #builder_name #generics #where_clause {
+            #( #builder_fields ),*
+        }
+
+        impl #generic_names #builder_name #generics #where_clause {
+            #visibility fn new ( #ctor_arg_list )->Self {
+                #builder_name { #( #arg_names, )* #( #opt_names: None ),* }
+            }
+
+            #( #setters )*
+
+            #visibility fn build (self)->#name #generic_names {
+                let #builder_name { #( #field_names ),* } = self;
+                #( #resolve_stmts )*
+                #name { #( #field_names ),* }
+            }
+        }
+
+        impl #generic_names #name #generics #where_clause {
+            #ctor_docs
+            #visibility fn builder ( #ctor_arg_list )->#builder_name #generic_names {
+                #builder_name::new( #( #arg_names ),* )
+            }
+        }
+    }
+}
+
 fn get_generic_names (generic_params: &Vec<GenericParam>)->TokenStream2 {
     let mut ts = TokenStream2::new();
 
@@ -166,6 +251,42 @@ fn get_ctor_arg_list (field_specs: &Vec<FieldSpec>)->TokenStream2 {
     }
 }
 
+/// get the string values of all `#[doc = "..."]` attributes (i.e. `///` comments)
+fn get_doc_lines (attrs: &Vec<Attribute>)->Vec<String> {
+    attrs.iter().filter_map( |a| {
+        if let Meta::NameValue(nv) = &a.meta {
+            if nv.path.is_ident("doc") {
+                if let Expr::Lit( ExprLit{ lit: Lit::Str(s), .. }) = &nv.value {
+                    return Some( s.value().trim().to_string())
+                }
+            }
+        }
+        None
+    }).collect()
+}
+
+/// turn the doc comments of ctor arg fields into an "# Arguments" doc section. Note that we only emit
+/// `#[doc]` attrs here - other field attributes such as `#[serde(..)]` do not apply to fn args
+fn get_ctor_docs (field_specs: &Vec<FieldSpec>)->TokenStream2 {
+    let mut lines: Vec<String> = Vec::new();
+    for f in field_specs.iter().filter( |f| f.init_expr.is_none()) {
+        let doc_lines = get_doc_lines( &f.attrs);
+        if !doc_lines.is_empty() {
+            lines.push( format!(" * `{}` - {}", f.name, doc_lines[0]));
+            for l in &doc_lines[1..] { lines.push( format!("   {}", l)) }
+        }
+    }
+
+    if lines.is_empty() {
+        quote!{}
+    } else {
+        quote!{
+            #[doc = " # Arguments"]
+            #( #[doc = #lines] )*
+        }
+    }
+}
+
 fn get_ctor_init_stmts (field_specs: &Vec<FieldSpec>)->TokenStream2 {
     let mut ts = TokenStream2::new();
     for f in field_specs {
@@ -186,11 +307,16 @@ struct StructSpec {
     derives: Vec<Path>,
     where_clause: Option<WhereClause>,
     field_specs: Vec<FieldSpec>,
+    has_builder: bool,
 }
 
 impl Parse for StructSpec {
     fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
-        let attrs: Vec<Attribute> = input.call(Attribute::parse_outer)?;
+        let mut attrs: Vec<Attribute> = input.call(Attribute::parse_outer)?;
+        let n_attrs = attrs.len();
+        attrs.retain( |a| !a.path().is_ident("builder")); // our own marker, not to be emitted
+        let has_builder = attrs.len() < n_attrs;
+
         let visibility: Visibility = parse_visibility(input);
         let name: Ident = input.parse()?;
 
@@ -222,7 +348,7 @@ impl Parse for StructSpec {
             field_specs = Punctuated::<FieldSpec,Token![,]>::parse_separated_nonempty(input)?.into_iter().collect();
         }
 
-        Ok(StructSpec { attrs, visibility, name, generic_params, derives, where_clause, field_specs })
+        Ok(StructSpec { attrs, visibility, name, generic_params, derives, where_clause, field_specs, has_builder })
     }
 }
 
@@ -287,6 +413,11 @@ impl ToTokens for FieldSpec {
 /// ```
 /// that is expanded into a respective `#[derive(Trait1,..)` macro for the resulting enum.
 /// 
+/// Variant types can be preceded by outer attributes that are passed on to the generated variant, e.g.
+/// ```
+///     define_algebraic_type! { #[serde(tag="type")] MyEnum: Serialize = #[serde(rename="a")] A | B }
+/// ```
+/// 
 /// As a convenience feature it also supports optional method definitions that are expanded for
 /// all variants if their bodies include `__` (double underscore) as variable names. If present these
 /// methods are turned into an inherent impl for the enum. 
@@ -321,12 +452,22 @@ impl ToTokens for FieldSpec {
 /// impl From<A> for MyMsg {...}
 /// impl From<B<std::vec::Vec<(u32,&'static str)>>> for MyMsg {...}
 /// ```
+/// 
+/// Variant sets can be shared by adding a `#[shared_variants]` attribute to the base ADT, which can then be
+/// referenced with `include!(BaseAdt)` in the variant list of subsequent ADT or actor message set definitions
+/// (see [`define_actor_msg_set`])
 #[proc_macro]
 pub fn define_algebraic_type (item: TokenStream) -> TokenStream {
-    let AdtEnum {attrs, visibility, name, generic_params, derives, where_clause, variant_types, methods }= match syn::parse(item) {
+    let input = TokenStream2::from( item.clone());
+    let mut adt: AdtEnum = match syn::parse(item) {
         Ok(adt) => adt,
         Err(e) => panic!( "expected \"adtName [: Trait,..] = variantType | ..  [ func ... ]\" got error: {:?}", e)
     };
+    if let Some(callback) = resolve_included_variants( "define_algebraic_type", input, &mut adt) { 
+        return callback.into() 
+    }
+    let shared_variants = if adt.has_shared_variants { get_shared_variants_macro( &adt) } else { quote!{} };
+    let AdtEnum {attrs, visibility, name, generic_params, derives, where_clause, variant_attrs, variant_types, methods, .. } = adt;
 
     let mut variant_names = get_variant_names_from_types(&variant_types);
 
@@ -340,7 +481,7 @@ pub fn define_algebraic_type (item: TokenStream) -> TokenStream {
         #derive_clause
         #( #attrs )*
         #visibility enum #name #generics #where_clause {
-            #( #variant_names ( #variant_types ) ),*
+            #( #( #variant_attrs )* #variant_names ( #variant_types ) ),*
         }
         #inherent_impl
         #(
@@ -355,6 +496,7 @@ pub fn define_algebraic_type (item: TokenStream) -> TokenStream {
                 }
             }
         }
+        #shared_variants
     }.into();
     //println!("-----\n{}\n-----", new_item.to_string());
     new_item
@@ -385,6 +527,8 @@ fn build_enum_method (variant_names: &Vec<Ident>, method: &ItemFn)->TokenStream2
         // we also might use normal wildcards in the function body.
         // the next best choice is '__', which is actually a valid ident and rarely used 
 
+        // method generics and where clauses are part of the parsed signature. Note that generic params have to be
+        // valid for all variant types, e.g. 'F: Fn(&dyn Trait)' if the method calls 'f(__)'
         quote! {
             #vis #sig {
                 match self {
@@ -437,11 +581,27 @@ impl<'a> Visit<'a> for BlockAnalyzer {
 /// impl From<A> for MyActorMsg {...}
 /// impl From<B> for MyActorMsg {...}
 /// impl DefaultReceiveAction for MyActorMsg {...}
+/// ```
 /// 
+/// The variants of an ADT that was defined with a `#[shared_variants]` attribute can be included by name, i.e.
+/// without restating all its variant types:
+/// ```
+/// define_algebraic_type! { #[shared_variants] pub SensorMsg = A | B }
+/// define_actor_msg_set! { pub MyActorMsg = include!(SensorMsg) | C }  // => A | B | C + system messages
+/// ```
+/// This uses a `SensorMsg_variants!` macro generated for `SensorMsg`, which is a `macro_rules` macro and hence
+/// has to be defined textually before the `include!(..)` use. Only the name of the included ADT is used (not its path)
+/// and the including macro has to be in scope by its unqualified name
 #[proc_macro]
 pub fn define_actor_msg_set (item: TokenStream) -> TokenStream {
-    let AdtEnum {attrs, visibility, name, generic_params, derives, where_clause, mut variant_types, methods }= syn::parse(item).unwrap();
+    let input = TokenStream2::from( item.clone());
+    let mut adt: AdtEnum = syn::parse(item).unwrap();
+    if let Some(callback) = resolve_included_variants( "define_actor_msg_set", input, &mut adt) { 
+        return callback.into() 
+    }
+    let AdtEnum {attrs, visibility, name, generic_params, derives, where_clause, mut variant_attrs, mut variant_types, methods, .. } = adt;
     for var_type in get_sys_msg_types() {
+        variant_attrs.push(Vec::new());
         variant_types.push(var_type)
     }
 
@@ -460,7 +620,7 @@ pub fn define_actor_msg_set (item: TokenStream) -> TokenStream {
         #derive_clause
         #( #attrs )*
         #visibility enum #name #generics #where_clause {
-            #( #variant_names ( #variant_types ) ),*
+            #( #( #variant_attrs )* #variant_names ( #variant_types ) ),*
         }
 
         #inherent_impl
@@ -510,13 +670,61 @@ struct AdtEnum {
     generic_params:Vec<GenericParam>,
     derives: Vec<Path>,
     where_clause: Option<WhereClause>,
+    variant_attrs: Vec<Vec<Attribute>>, // per variant outer attributes (e.g. `#[serde(rename="x")]`)
     variant_types: Vec<Path>,
-    methods: Vec<ItemFn>
+    methods: Vec<ItemFn>,
+    includes: Vec<(usize,Ident)>, // `include!(BaseAdt)` variant positions and names of the included sets
+    included: Vec<(Ident,Vec<Vec<Attribute>>,Vec<Path>)>, // resolved variant sets (from `@include` callback prefixes)
+    has_shared_variants: bool, // do we generate a `<name>_variants!` macro so that other ADTs can include our variants
+}
+
+mod kw {
+    syn::custom_keyword!(include);
+    syn::custom_keyword!(init);
+}
+
+/// parse a `|` separated list of (optionally attributed) variant types
+fn parse_variant_list (input: ParseStream<'_>, includes: &mut Vec<(usize,Ident)>)->Result<(Vec<Vec<Attribute>>,Vec<Path>)> {
+    let mut variant_attrs: Vec<Vec<Attribute>> = Vec::new();
+    let mut variant_types: Vec<Path> = Vec::new();
+    loop {
+        let attrs = input.call(Attribute::parse_outer)?;
+        if input.peek(kw::include) && input.peek2(Token![!]) {
+            if !attrs.is_empty() { return Err( input.error("included variant sets cannot have attributes")) }
+            input.parse::<kw::include>()?;
+            input.parse::<Token![!]>()?;
+            let content;
+            parenthesized!( content in input);
+            let base: Path = content.parse()?;
+            let base_name = base.segments.last().ok_or_else( || content.error("expected ADT name"))?.ident.clone();
+            includes.push( (variant_types.len(), base_name));
+        } else {
+            variant_attrs.push( attrs);
+            variant_types.push( input.parse()?);
+        }
+        if input.peek(Token![|]) { input.parse::<Token![|]>()?; } else { break }
+    }
+    Ok( (variant_attrs, variant_types) )
 }
 
 impl Parse for AdtEnum {
     fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
-        let attrs: Vec<Attribute> = input.call(Attribute::parse_outer)?;
+        // resolved variant sets prepended by `<name>_variants!` callbacks: `@include BaseAdt ( A | B )`
+        let mut included: Vec<(Ident,Vec<Vec<Attribute>>,Vec<Path>)> = Vec::new();
+        while input.peek(Token![@]) {
+            input.parse::<Token![@]>()?;
+            input.parse::<kw::include>()?;
+            let base_name: Ident = input.parse()?;
+            let content;
+            parenthesized!( content in input);
+            let mut nested: Vec<(usize,Ident)> = Vec::new();
+            let (attrs, types) = parse_variant_list( &content, &mut nested)?;
+            included.push( (base_name, attrs, types));
+        }
+
+        let mut attrs: Vec<Attribute> = input.call(Attribute::parse_outer)?;
+        let has_shared_variants = attrs.iter().any( |a| a.path().is_ident("shared_variants"));
+        attrs.retain( |a| !a.path().is_ident("shared_variants")); // our own marker, not to be emitted
         let visibility: Visibility = parse_visibility(input);
         let name: Ident = input.parse()?;
 
@@ -543,13 +751,13 @@ impl Parse for AdtEnum {
             lookahead = input.lookahead1();
         }
 
-        let variant_types: Vec<Path> = if !input.is_empty() && lookahead.peek(Token![=]) {
+        let mut variant_attrs: Vec<Vec<Attribute>> = Vec::new();
+        let mut variant_types: Vec<Path> = Vec::new();
+        let mut includes: Vec<(usize,Ident)> = Vec::new();
+        if !input.is_empty() && lookahead.peek(Token![=]) {
             input.parse::<Token![=]>()?;
-            let variant_types = Punctuated::<Path,Token![|]>::parse_separated_nonempty(input)?;
-            variant_types.into_iter().collect()
-        } else {
-            Vec::new()
-        };
+            (variant_attrs, variant_types) = parse_variant_list( input, &mut includes)?;
+        }
         
         let mut methods: Vec<ItemFn> = Vec::new();
         lookahead = input.lookahead1();
@@ -559,7 +767,67 @@ impl Parse for AdtEnum {
             lookahead = input.lookahead1()
         }
 
-        Ok( AdtEnum { attrs, visibility, name, generic_params, derives, where_clause, variant_types, methods })
+        Ok( AdtEnum { attrs, visibility, name, generic_params, derives, where_clause, variant_attrs, variant_types, methods, 
+                      includes, included, has_shared_variants })
+    }
+}
+
+/// if the ADT has an `include!(BaseAdt)` variant entry that is not resolved yet return a `BaseAdt_variants!` invocation
+/// that calls back into `macro_name` with the variants of `BaseAdt` prepended to the original `input`. Otherwise splice
+/// the resolved variant sets into the variant list of `adt` and return `None`
+fn resolve_included_variants (macro_name: &str, input: TokenStream2, adt: &mut AdtEnum)->Option<TokenStream2> {
+    for (_,base_name) in &adt.includes {
+        if !adt.included.iter().any( |(name,_,_)| name == base_name) {
+            let variants_macro = format_ident!("{}_variants", base_name);
+            let callback = Ident::new( macro_name, Span::call_site());
+            return Some( quote! { #variants_macro! { #callback { #input } } } )
+        }
+    }
+
+    if !adt.includes.is_empty() {
+        let mut variant_attrs: Vec<Vec<Attribute>> = Vec::new();
+        let mut variant_types: Vec<Path> = Vec::new();
+        let mut own = adt.variant_attrs.drain(..).zip( adt.variant_types.drain(..));
+        let mut n_own = 0;
+
+        for (pos,base_name) in &adt.includes {
+            while n_own < *pos {
+                let (attrs,vtype) = own.next().unwrap();
+                variant_attrs.push( attrs);
+                variant_types.push( vtype);
+                n_own += 1;
+            }
+            let (_,attrs,types) = adt.included.iter().find( |(name,_,_)| name == base_name).unwrap();
+            variant_attrs.extend( attrs.iter().cloned());
+            variant_types.extend( types.iter().cloned());
+        }
+        for (attrs,vtype) in own {
+            variant_attrs.push( attrs);
+            variant_types.push( vtype);
+        }
+
+        adt.variant_attrs = variant_attrs;
+        adt.variant_types = variant_types;
+        adt.includes.clear();
+    }
+    None
+}
+
+/// generate the `<name>_variants!` callback macro for an ADT with a `#[shared_variants]` attribute. Note this is a
+/// `macro_rules` macro, i.e. it is only visible in the textual scope following the ADT definition
+fn get_shared_variants_macro (adt: &AdtEnum)->TokenStream2 {
+    let name = &adt.name;
+    let variants_macro = format_ident!("{}_variants", name);
+    let variant_attrs = &adt.variant_attrs;
+    let variant_types = &adt.variant_types;
+
+    quote! {
+        #[allow(unused_macros)]
+        macro_rules! #variants_macro {
+            ( $callback:ident { $($input:tt)* } ) => {
+                $callback! { @include #name ( #( #( #variant_attrs )* #variant_types )|* ) $($input)* }
+            }
+        }
     }
 }
 
@@ -579,6 +847,13 @@ impl Parse for AdtEnum {
 ///   E => { println!("got an E") }
 /// }
 /// ```
+/// Several variant types can share the same action:
+/// ```
+/// match_algebraic_type! { my_type: MyType as
+///   A | E => println!("got an A or E: {:?}", my_type),
+///   _ => {}
+/// }
+/// ```
 #[proc_macro]
 pub fn match_algebraic_type (item: TokenStream) -> TokenStream {
     let MsgMatch { msg_name, msg_type, match_arms }: MsgMatch = match syn::parse(item) {
@@ -651,6 +926,7 @@ fn get_match_patterns(msg_name: &Ident, msg_type: &Path, match_arms: &Vec<MsgMat
 #[proc_macro]
 pub fn match_actor_msg (item: TokenStream)->TokenStream {
     let MsgMatch { msg_name, msg_type, match_arms }: MsgMatch = syn::parse(item).unwrap();
+    if let Err(e) = check_unreachable_match_arms( &match_arms) { return e.to_compile_error().into() }
     
     let variant_names: Vec<Ident> = get_variant_names_from_match_arms(&match_arms);
     let is_mut: Vec<&Option<Token![mut]>> = match_arms.iter().map( |a| { &a.maybe_mut }).collect();
@@ -758,25 +1034,31 @@ impl Parse for MsgMatch {
 }
 
 // TODO - this should be consistent over all our ADTs
+// arms can combine several variant types with a shared action ("A | B<C> => ..."), which we expand
+// into one match arm per variant type so that the msg variable is bound to the respective type in each of them
 fn parse_match_arms (input: ParseStream)->Result<Vec::<MsgMatchArm>> {
     let mut match_arms = Vec::<MsgMatchArm>::new();
     
     while !input.is_empty() {
         let lookahead = input.lookahead1();
-        let (variant_spec,is_ref,is_mut) = if lookahead.peek( Token![_]) {
+        let (variant_specs,is_ref,is_mut) = if lookahead.peek( Token![_]) {
             let _: Token![_] = input.parse()?;
-            (VariantSpec::Wildcard,Option::<Ref>::None,Option::<Mut>::None)
+            (vec![VariantSpec::Wildcard],Option::<Ref>::None,Option::<Mut>::None)
         } else {
             let is_ref: Option<Token![ref]> = if lookahead.peek( Token![ref]) {
                 Some(input.parse()?)
             } else { None };
 
-            let is_mut: Option<Token![mut]> = if lookahead.peek( Token![mut]) {
+            let is_mut: Option<Token![mut]> = if input.peek( Token![mut]) {
                 Some(input.parse()?)
             } else { None };
     
-            let path: Path = input.parse()?;
-            (VariantSpec::Type(path),is_ref,is_mut)
+            let mut variant_specs = vec![ VariantSpec::Type( input.parse::<Path>()?) ];
+            while input.peek( Token![|]) {
+                let _: Token![|] = input.parse()?;
+                variant_specs.push( VariantSpec::Type( input.parse::<Path>()?));
+            }
+            (variant_specs,is_ref,is_mut)
         };
         
         //--- the match 
@@ -788,12 +1070,39 @@ fn parse_match_arms (input: ParseStream)->Result<Vec::<MsgMatchArm>> {
             let _: Token![,] = input.parse()?;
         }
 
-        match_arms.push( MsgMatchArm { variant_spec, maybe_ref: is_ref, maybe_mut: is_mut, match_action } );
+        for variant_spec in variant_specs {
+            match_arms.push( MsgMatchArm { variant_spec, maybe_ref: is_ref, maybe_mut: is_mut, match_action: match_action.clone() } );
+        }
     }
 
     Ok(match_arms)
 }
 
+/// since the generated match relies on Rust ignoring all but the first of duplicated patterns (and we can't add a
+/// catch-all) a user arm for an already matched variant or an arm following a wildcard would silently never be reached.
+/// We report those as errors pointing to the offending variant spec
+fn check_unreachable_match_arms (match_arms: &Vec<MsgMatchArm>)->Result<()> {
+    let mut seen: Vec<String> = Vec::new();
+    let mut has_wildcard = false;
+
+    for a in match_arms {
+        if let VariantSpec::Type(path) = &a.variant_spec {
+            let ps = path_to_string( path);
+            if has_wildcard {
+                return Err( syn::Error::new_spanned( path, format!("unreachable match arm for `{}` after wildcard `_` arm", ps)))
+            }
+            if seen.contains( &ps) {
+                return Err( syn::Error::new_spanned( path, format!("duplicate match arm for `{}` (only the first one is reachable)", ps)))
+            }
+            seen.push( ps);
+        } else {
+            has_wildcard = true;
+        }
+    }
+
+    Ok(())
+}
+
 /// if the ADT type has generic params we have to remove these from match arm expressions:
 /// ```
 ///   enum MyAdt<T> { Foo(T), ... }
@@ -840,6 +1149,7 @@ pub fn impl_actor (item: TokenStream) -> TokenStream {
         Ok(actor_receive) => actor_receive,
         Err(e) => panic!( "expected impl_actor!{{ match «msgVarName» for Actor<«stateType»,«msgType»> [where ..] as «msgTypeVariant» => {{..}},...}}, got {:?}", e)
     };
+    if let Err(e) = check_unreachable_match_arms( &match_arms) { return e.to_compile_error().into() }
 
     let match_msg_type = get_match_adt_type( &msg_type);
 
@@ -950,9 +1260,11 @@ fn collect_typevars<'a> (where_clause: &'a WhereClause) -> Vec<&'a Path> {
 /// This is expanded into:
 /// ```
 ///     match msg {
-///         A(msg) => { {println!("actor received an A = {:?}", msg)}; ReceiveAction::Continue }
+///         A(msg) => { let () = {println!("actor received an A = {:?}", msg)}; ReceiveAction::Continue }
 ///         ...
 /// ```
+/// The body has to be of unit type, i.e. non-unit values (such as `Result`s of `try_send_msg(..)`) have to be
+/// explicitly handled or discarded with a trailing `;`
 #[proc_macro]
 pub fn cont (ts: TokenStream)->TokenStream {
     expand_msg_match_action( ts, quote! { ReceiveAction::Continue })
@@ -976,16 +1288,39 @@ pub fn term (ts: TokenStream)->TokenStream {
 
 /* #region spawn_actor ***********************************************************/
 
+/// spawn a new actor from the provided actor system, name and state expressions:
+/// ```
+///     spawn_actor!( actor_system, "greeter", Greeter{})?
+/// ```
+/// The optional channel bounds default to `DEFAULT_CHANNEL_BOUNDS`. If the state needs a one-shot initialization
+/// (e.g. loading config or opening files) before the actor gets spawned this can be specified as a trailing
+/// `init: «closure»` argument. The closure gets a `&mut` reference to the actor state and has to return a
+/// `odin_actor::Result<()>`. If the closure fails the actor is not spawned and its error is returned:
+/// ```
+///     spawn_actor!( actor_system, "reader", Reader::new(path), 32, init: |s| s.open_file())?
+/// ```
 #[proc_macro]
 pub fn spawn_actor (item: TokenStream)->TokenStream {
-    let SpawnActor { spawner, aname_expr, astate_expr, channel_bounds } = match syn::parse(item) {
+    let SpawnActor { spawner, aname_expr, astate_expr, channel_bounds, init_expr } = match syn::parse(item) {
         Ok(actor_receive) => actor_receive,
-        Err(e) => panic!( "expected \"spawn_actor!( «actorSystem», «actorName», «actorState» [,«channelBounds»])\", got {:?}", e)
+        Err(e) => panic!( "expected \"spawn_actor!( «actorSystem», «actorName», «actorState» [,«channelBounds»] [,init: «closure»])\", got {:?}", e)
     };
     let cbounds = if let Some(channel_bounds) = channel_bounds { quote!{#channel_bounds} } else { quote!{ DEFAULT_CHANNEL_BOUNDS} };
     
-    let new_item: TokenStream = quote! { 
-        #spawner.spawn_actor( #spawner.new_actor( #aname_expr, #astate_expr, #cbounds)) 
+    let new_item: TokenStream = if let Some(init_expr) = init_expr {
+        quote! {
+            {
+                let mut __actor_tuple = #spawner.new_actor( #aname_expr, #astate_expr, #cbounds);
+                match init_actor_state( &mut __actor_tuple, #init_expr) {
+                    Ok(()) => #spawner.spawn_actor( __actor_tuple),
+                    Err(e) => Err(e)
+                }
+            }
+        }
+    } else {
+        quote! { 
+            #spawner.spawn_actor( #spawner.new_actor( #aname_expr, #astate_expr, #cbounds)) 
+        }
     }.into();
     //println!("-----\n{}\n-----", new_item.to_string());
 
@@ -996,7 +1331,12 @@ struct SpawnActor {
     spawner: Expr,
     aname_expr: Expr,
     astate_expr: Expr,
-    channel_bounds: Option<Expr>
+    channel_bounds: Option<Expr>,
+    init_expr: Option<Expr>
+}
+
+fn peek_init (input: ParseStream<'_>)->bool {
+    input.peek(kw::init) && input.peek2(Token![:]) && !input.peek2(Token![::])
 }
 impl Parse for SpawnActor {
     fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
@@ -1006,30 +1346,58 @@ impl Parse for SpawnActor {
         let _: Token![,] = input.parse()?;
         let astate_expr: Expr = input.parse()?;
 
-        let lookahead = input.lookahead1();
-        let channel_bounds = if lookahead.peek( Token![,]) {
+        let mut channel_bounds: Option<Expr> = None;
+        let mut init_expr: Option<Expr> = None;
+
+        if input.peek( Token![,]) {
             let _: Token![,] = input.parse()?;
-            let bounds_expr: Expr = input.parse()?;
-            Some(bounds_expr)
-        } else {
-            None
-        };
+            if !peek_init( input) {
+                channel_bounds = Some( input.parse()?);
+                if input.peek( Token![,]) {
+                    let _: Token![,] = input.parse()?;
+                }
+            }
+        }
+
+        if !input.is_empty() {
+            let _: kw::init = input.parse()?;
+            let _: Token![:] = input.parse()?;
+            init_expr = Some( input.parse()?);
+        }
 
-        Ok( SpawnActor { spawner, aname_expr, astate_expr, channel_bounds } )
+        Ok( SpawnActor { spawner, aname_expr, astate_expr, channel_bounds, init_expr } )
     }
 }
 
 #[proc_macro]
 pub fn spawn_dyn_actor (item: TokenStream)->TokenStream {
-    let SpawnActor { spawner, aname_expr, astate_expr, channel_bounds } = match syn::parse(item) {
+    let SpawnActor { spawner, aname_expr, astate_expr, channel_bounds, init_expr } = match syn::parse(item) {
         Ok(actor_receive) => actor_receive,
-        Err(e) => panic!( "expected \"spawn_dyn_actor!( «actorHandle», «actorName», «actorState» [,«channelBounds»])\", got {:?}", e)
+        Err(e) => panic!( "expected \"spawn_dyn_actor!( «actorHandle», «actorName», «actorState» [,«channelBounds»] [,init: «closure»])\", got {:?}", e)
     };
 
     let cbounds = if let Some(channel_bounds) = channel_bounds { quote!{#channel_bounds} } else { quote!{ DEFAULT_CHANNEL_BOUNDS} };
 
-    let new_item: TokenStream = quote! {
-        #spawner.hsys().spawn_actor( #spawner.new_actor( #aname_expr, #astate_expr, #cbounds))
+    let new_item: TokenStream = if let Some(init_expr) = init_expr {
+        quote! {
+            {
+                let mut __actor_tuple = #spawner.new_actor( #aname_expr, #astate_expr, #cbounds);
+                let __init_result = init_actor_state( &mut __actor_tuple, #init_expr);
+                async move { // keep the same (awaitable) shape as the non-init variant
+                    match __init_result {
+                        Ok(()) => {
+                            let __h = __actor_tuple.1.clone();
+                            __h.hsys().spawn_actor( __actor_tuple).await
+                        }
+                        Err(e) => Err(e)
+                    }
+                }
+            }
+        }
+    } else {
+        quote! {
+            #spawner.hsys().spawn_actor( #spawner.new_actor( #aname_expr, #astate_expr, #cbounds))
+        }
     }.into();
     //println!("-----\n{}\n-----", new_item.to_string());
 
@@ -1259,8 +1627,12 @@ fn get_sys_msg_types()->[Path;N_SYS_MSGS] {
 
 fn expand_msg_match_action (ts: TokenStream, ret_val: TokenStream2)->TokenStream {
     let body = TokenStream2::from(ts); // we need a TokenStream2 to get a ToToken impl
-    let new_item: TokenStream =quote! {
-        { { #body }; #ret_val }
+    let body_span = body.span();
+    // the explicit '()' binding turns non-unit bodies (which would otherwise be silently discarded) into type errors,
+    // which we span to the body so that they point to user code
+    let unit_body = quote_spanned! { body_span=> let _: () = { #body }; };
+    let new_item: TokenStream = quote! {
+        { #unit_body #ret_val }
     }.into();
 
     new_item
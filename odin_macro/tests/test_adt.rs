@@ -95,6 +95,68 @@ fn test_match()->Result<(),Box<dyn Error>> {
     Ok(())
 }
 
+#[derive(Debug,Clone)] struct SmokeData { density: f64 }
+
+define_algebraic_type! {
+    SensorUpdate: Clone = Record<GpsData> | Record<ThermoData> | Record<SmokeData> | String
+}
+
+fn classify (update: SensorUpdate)->String {
+    match_algebraic_type! { update: SensorUpdate as
+        Record<GpsData> | Record<ThermoData> | Record<SmokeData> => format!("record {} from {}", update.id, update.device),
+        String => format!("text {update}")
+    }
+}
+
+#[test]
+fn test_combined_match_arm() {
+    let gps = SensorUpdate::from( Record{ id: 1, device: 42, data: GpsData{lat:37.0,lon:-121.0}});
+    let thermo = SensorUpdate::from( Record{ id: 2, device: 43, data: ThermoData{temp:42.0}});
+    let smoke = SensorUpdate::from( Record{ id: 3, device: 44, data: SmokeData{density:0.5}});
+    let text = SensorUpdate::from( "blah".to_string());
+
+    assert_eq!( classify( gps), "record 1 from 42");
+    assert_eq!( classify( thermo), "record 2 from 43");
+    assert_eq!( classify( smoke), "record 3 from 44");
+    assert_eq!( classify( text), "text blah");
+}
+
+trait Shape {
+    fn area (&self)->f64;
+    fn name (&self)->&'static str;
+}
+
+#[derive(Debug,Clone)] struct Circle { r: f64 }
+impl Shape for Circle { 
+    fn area (&self)->f64 { std::f64::consts::PI * self.r * self.r } 
+    fn name (&self)->&'static str { "circle" }
+}
+
+#[derive(Debug,Clone)] struct Square { a: f64 }
+impl Shape for Square { 
+    fn area (&self)->f64 { self.a * self.a } 
+    fn name (&self)->&'static str { "square" }
+}
+
+define_algebraic_type! {
+    AnyShape: Clone = Circle | Square
+
+    pub fn with_shape<R, F: Fn(&dyn Shape)->R> (&self, f: F)->R { f(__) }
+    pub fn map_name<F,R> (&self, f: F)->R where F: Fn(&str)->R { f(__.name()) }
+}
+
+#[test]
+fn test_generic_methods() {
+    let shapes = vec![ AnyShape::from( Circle{r: 1.0}), AnyShape::from( Square{a: 2.0}) ];
+
+    let areas: Vec<f64> = shapes.iter().map( |s| s.with_shape( |shape| shape.area())).collect();
+    assert_eq!( areas, vec![ std::f64::consts::PI, 4.0]);
+
+    let names: Vec<usize> = shapes.iter().map( |s| s.map_name( |name| name.len())).collect();
+    assert_eq!( names, vec![6, 6]);
+    assert_eq!( shapes[1].map_name( |name| name.to_uppercase()), "SQUARE");
+}
+
 define_struct! {
     pub MyStruct: Debug+Clone =
       a: String,
@@ -111,6 +173,33 @@ fn test_simple_struct()->Result<(),Box<dyn Error>> {
 }
 
 
+define_struct! {
+    #[builder]
+    pub MyBuiltStruct<'a>: Debug + Clone + PartialEq =
+      name: &'a str,
+      len: usize = name.len(),
+      tags: Vec<String> = Vec::new(),
+      id: u64 = { len as u64 * 2 }
+}
+
+#[test]
+fn test_struct_builder() {
+    // builder with explicit values for all fields equals the ctor equivalent
+    let o1 = MyBuiltStruct::builder( "blah").with_len(4).with_tags( Vec::new()).with_id(8).build();
+    let o2 = MyBuiltStruct::new( "blah");
+    assert_eq!( o1, o2);
+
+    // unset fields use their init exprs, which can refer to explicitly set ones
+    let o3 = MyBuiltStruct::builder( "blah").with_len(10).with_tags( vec!["a".to_string()]).build();
+    println!("{:?}", o3);
+    assert_eq!( o3.len, 10);
+    assert_eq!( o3.id, 20);
+    assert_eq!( o3.tags, vec!["a".to_string()]);
+
+    let o4 = MyBuiltStructBuilder::new( "blah").with_name( "foo").build();
+    assert_eq!( o4.name, "foo");
+    assert_eq!( o4.len, 3);
+}
 
 trait MaybeAnswer {
     fn is_answer(&self)->bool;
@@ -158,4 +247,32 @@ define_struct! {
     SomeStruct: Serialize = 
         #[serde(skip)]
         some_field: String
-}
\ No newline at end of file
+}
+define_struct! {
+    #[derive(Debug)]
+    #[serde(rename_all="camelCase")]
+    pub DocStruct: Serialize =
+        /// the name of the object
+        /// (multi-line doc comment)
+        #[serde(rename="objName")]
+        obj_name: String,
+        /// the object id, not serialized
+        #[serde(skip)]
+        obj_id: u64,
+        /// computed field with init expr, not a ctor arg
+        name_len: usize = obj_name.len()
+}
+
+#[test]
+fn test_field_docs() {
+    // this would not compile if #[serde(..)] would end up on the ctor args
+    let o = DocStruct::new( "blah".to_string(), 42);
+    assert_eq!( o.obj_name, "blah");
+    assert_eq!( o.obj_id, 42);
+    assert_eq!( o.name_len, 4);
+
+    // field and struct level serde attrs have to end up on the struct definition
+    let json = serde_json::to_string(&o).unwrap();
+    println!("{json}");
+    assert_eq!( json, r#"{"objName":"blah","nameLen":4}"#);
+}
@@ -0,0 +1,45 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of 
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License"); 
+ * you may not use this file except in compliance with the License. You may obtain a copy 
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+use odin_macro::{cont, stop, term};
+
+#[derive(Debug,PartialEq)]
+enum ReceiveAction { Continue, Stop, RequestTermination }
+
+fn handle (n: u32)->ReceiveAction {
+    match n {
+        0 => cont!{ println!("got zero") },
+        1 => stop!{ let m = n + 1; println!("got {m}"); },
+        _ => term!( println!("got {n}") )
+    }
+}
+
+#[test]
+fn test_unit_bodies() {
+    assert_eq!( handle(0), ReceiveAction::Continue);
+    assert_eq!( handle(1), ReceiveAction::Stop);
+    assert_eq!( handle(2), ReceiveAction::RequestTermination);
+}
+
+#[test]
+fn test_non_unit_body_is_error() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/cont_non_unit.rs");
+}
+
+#[test]
+fn test_duplicate_match_arm_is_error() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/duplicate_match_arm.rs");
+}
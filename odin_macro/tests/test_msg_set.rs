@@ -0,0 +1,63 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of 
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License"); 
+ * you may not use this file except in compliance with the License. You may obtain a copy 
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+#![allow(unused)]
+
+use odin_macro::{define_algebraic_type, define_actor_msg_set};
+
+// minimal stand-ins for the odin_actor types referenced by define_actor_msg_set expansions
+#[derive(Debug,PartialEq)] enum ReceiveAction { Continue, Stop }
+trait FromSysMsg {}
+trait DefaultReceiveAction { fn default_receive_action (&self)->ReceiveAction; }
+
+#[derive(Debug)] struct _Start_;
+#[derive(Debug)] struct _Ping_;
+impl _Ping_ { fn store_response (&self) {} }
+#[derive(Debug)] struct _Timer_;
+struct _Exec_(Box<dyn Fn()>);
+impl std::fmt::Debug for _Exec_ { fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { write!(f, "_Exec_") } }
+#[derive(Debug)] struct _Pause_;
+#[derive(Debug)] struct _Resume_;
+#[derive(Debug)] struct _Terminate_;
+
+#[derive(Debug)] struct Gps(u32);
+#[derive(Debug)] struct Thermo(u32);
+#[derive(Debug)] struct Config(String);
+
+define_algebraic_type! { #[shared_variants] SensorUpdate = Gps | Thermo }
+
+define_actor_msg_set! { SensorActorMsg = include!(SensorUpdate) | Config }
+
+define_algebraic_type! { AllUpdates = Config | include!(SensorUpdate) }
+
+#[test]
+fn test_included_variants() {
+    // base variants
+    assert!( matches!( SensorActorMsg::from( Gps(1)), SensorActorMsg::Gps(Gps(1))));
+    assert!( matches!( SensorActorMsg::from( Thermo(2)), SensorActorMsg::Thermo(Thermo(2))));
+    // extras
+    assert!( matches!( SensorActorMsg::from( Config("c".to_string())), SensorActorMsg::Config(_)));
+    // system messages
+    let msg = SensorActorMsg::from( _Terminate_);
+    assert_eq!( msg.default_receive_action(), ReceiveAction::Stop);
+    assert_eq!( SensorActorMsg::from( _Start_).default_receive_action(), ReceiveAction::Continue);
+
+    // the base ADT itself is unchanged
+    assert!( matches!( SensorUpdate::from( Gps(3)), SensorUpdate::Gps(_)));
+
+    // includes can also be used for ADTs, in any position
+    let upd: AllUpdates = Thermo(4).into();
+    assert_eq!( format!("{upd:?}"), "AllUpdates::Thermo");
+    let upd: AllUpdates = Config("x".to_string()).into();
+    assert_eq!( format!("{upd:?}"), "AllUpdates::Config");
+}
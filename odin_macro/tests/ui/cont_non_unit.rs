@@ -0,0 +1,10 @@
+use odin_macro::cont;
+
+#[allow(unused)]
+enum ReceiveAction { Continue, Stop, RequestTermination }
+
+fn handle (n: u32)->ReceiveAction {
+    cont!{ n + 1 }
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use odin_macro::match_actor_msg;
+
+#[allow(unused)]
+enum MyMsg { A(u32), B(String) }
+
+fn handle (msg: MyMsg) {
+    match_actor_msg! { msg: MyMsg as
+        A => { println!("first A") },
+        B => { println!("B") },
+        A => { println!("second A") }
+    }
+}
+
+fn main() {}
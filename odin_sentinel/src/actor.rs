@@ -90,11 +90,14 @@ impl<C,I,U,IA> SentinelActor <C,I,U,IA>
     }
 
     async fn update (&mut self, sentinel_update: SentinelUpdate)->Result<()> {
-        let SentinelChange { added, removed } = self.sentinels.update_with( sentinel_update, self.connector.max_history());
+        let SentinelChange { added, removed, replaced } = self.sentinels.update_with( sentinel_update, self.connector.max_history());
 
         if let Some(added) = added {
             self.update_action.execute(added).await;
         }
+        if let Some(replaced) = replaced { // same record with changed data - our clients need to know
+            self.update_action.execute(replaced).await;
+        }
         // TODO- shall we also notify about removed records?
         Ok(())
     }
@@ -96,6 +96,8 @@ pub struct SentinelAlarmMonitorConfig {
     pub image_timeout: Duration,
     pub fire_prob: f64,
     pub smoke_prob: f64,
+
+    pub throttle: AlarmThrottle, // to avoid flooding messengers with alarms from frequently reporting sensors
 }
 
 impl Default for SentinelAlarmMonitorConfig {
@@ -107,10 +109,108 @@ impl Default for SentinelAlarmMonitorConfig {
             image_timeout: Duration::from_secs(20),
             fire_prob: 0.7,
             smoke_prob: 0.7,
+            throttle: AlarmThrottle::default(),
         }
     }
 }
 
+/// rate limiter parameters for alarm notifications. Alarms of the same type (capability) and device (if `per_device`
+/// is set) that are recorded within `min_interval` of the last dispatched one are suppressed unless they are an escalation,
+/// i.e. their confidence crosses one of the `escalation_thresholds` the last dispatched alarm did not reach.
+/// A zero `min_interval` means all alarms are dispatched. The dispatch state is kept in an [`AlarmDispatchHistory`]
+#[derive(Deserialize,Serialize,Debug,Clone)]
+#[serde(default)]
+pub struct AlarmThrottle {
+    pub min_interval: Duration,
+    pub per_device: bool,
+    pub escalation_thresholds: Vec<f64>,
+}
+
+impl Default for AlarmThrottle {
+    fn default()->Self {
+        AlarmThrottle::new( Duration::ZERO, true).with_escalation_thresholds( vec![0.9])
+    }
+}
+
+impl AlarmThrottle {
+    pub fn new (min_interval: Duration, per_device: bool)->Self {
+        AlarmThrottle { min_interval, per_device, escalation_thresholds: Vec::new() }
+    }
+
+    pub fn with_escalation_thresholds (mut self, escalation_thresholds: Vec<f64>)->Self {
+        self.escalation_thresholds = escalation_thresholds;
+        self
+    }
+
+    pub fn is_escalation (&self, last_confidence: f64, confidence: f64)->bool {
+        self.escalation_thresholds.iter().any( |t| last_confidence < *t && confidence >= *t)
+    }
+
+    fn key (&self, device_id: &str, alarm_type: &str)->String {
+        if self.per_device { format!("{}/{}", alarm_type, device_id) } else { alarm_type.to_string() }
+    }
+}
+
+/// the runtime state of an [`AlarmThrottle`], i.e. when and with which confidence we last dispatched alarms
+#[derive(Debug,Default)]
+pub struct AlarmDispatchHistory {
+    last_dispatched: HashMap<String,(DateTime<Utc>,f64)>, // throttle key -> (time_recorded,confidence)
+}
+
+impl AlarmDispatchHistory {
+    pub fn new ()->Self {
+        AlarmDispatchHistory { last_dispatched: HashMap::new() }
+    }
+
+    /// check if an alarm with the given properties should be sent out, without recording it
+    pub fn check (&self, throttle: &AlarmThrottle, device_id: &str, alarm_type: &str, time_recorded: DateTime<Utc>, confidence: f64)->ThrottleDecision {
+        if throttle.min_interval.is_zero() { return ThrottleDecision::Dispatch }
+
+        if let Some((last_time,last_confidence)) = self.last_dispatched.get( &throttle.key( device_id, alarm_type)) {
+            if throttle.is_escalation( *last_confidence, confidence) {
+                ThrottleDecision::Escalation
+            } else {
+                // out-of-order records count as being within the window
+                match time_recorded.signed_duration_since( *last_time).to_std() {
+                    Ok(td) if td >= throttle.min_interval => ThrottleDecision::Dispatch,
+                    _ => ThrottleDecision::Throttle
+                }
+            }
+        } else {
+            ThrottleDecision::Dispatch
+        }
+    }
+
+    /// record an alarm that was sent out
+    pub fn record_dispatch (&mut self, throttle: &AlarmThrottle, device_id: &str, alarm_type: &str, time_recorded: DateTime<Utc>, confidence: f64) {
+        if !throttle.min_interval.is_zero() {
+            self.last_dispatched.insert( throttle.key( device_id, alarm_type), (time_recorded,confidence));
+        }
+    }
+
+    /// check if an alarm with the given properties should be sent out, and if so record it as dispatched
+    pub fn should_dispatch (&mut self, throttle: &AlarmThrottle, device_id: &str, alarm_type: &str, time_recorded: DateTime<Utc>, confidence: f64)->bool {
+        if self.check( throttle, device_id, alarm_type, time_recorded, confidence) != ThrottleDecision::Throttle {
+            self.record_dispatch( throttle, device_id, alarm_type, time_recorded, confidence);
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn clear (&mut self) {
+        self.last_dispatched.clear()
+    }
+}
+
+/// result of checking an alarm against an [`AlarmThrottle`]
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub enum ThrottleDecision {
+    Dispatch,   // no dispatched alarm within `min_interval` (or throttling is disabled)
+    Escalation, // crosses an escalation threshold - this also bypasses the `new_alarm_duration` check
+    Throttle    // suppress
+}
+
 /// for now this is just a cache so that we don't have to retrieve EvidenceInfos on each check
 /// but we could add more context info here
 struct ReportedAlarm<T> where T: RecordDataBounds{
@@ -131,22 +231,30 @@ define_struct! { pub SentinelAlarmMonitor =
 
     reported_fire_alarms: VecDeque<ReportedAlarm<FireData>> = VecDeque::with_capacity( ALARM_HISTORY),
     reported_smoke_alarms: VecDeque<ReportedAlarm<SmokeData>> = VecDeque::with_capacity( ALARM_HISTORY),
-    inactive_alerts: Vec<SentinelInactiveAlert> = Vec::new()
+    inactive_alerts: Vec<SentinelInactiveAlert> = Vec::new(),
+    dispatch_history: AlarmDispatchHistory = AlarmDispatchHistory::new()
 }
 
 impl SentinelAlarmMonitor {
 
     async fn process_fire_alarm (&mut self, hself: ActorHandle<SentinelAlarmMonitorMsg>, rec: Arc<SensorRecord<FireData>>) {
         if rec.data.fire_prob >= self.config.fire_prob {
+            let alarm_type = rec.capability().property_name().to_string();
+            let confidence = rec.data.fire_prob;
+            let decision = self.dispatch_history.check( &self.config.throttle, &rec.device_id, &alarm_type, rec.time_recorded, confidence);
+            if decision == ThrottleDecision::Throttle {
+                debug!("throttled alarm {}", Self::alarm_id( &rec));
+                return
+            }
+
             let mut evidence_info = self.retrieve_evidence( &self.hupdater, &rec.evidences, self.config.image_timeout).await;
 
             let reported_alarms = &mut self.reported_fire_alarms;
-            if let Some(alarm_id) = Self::check_new_alarm( &rec, &evidence_info, reported_alarms, &self.config) {
+            if decision == ThrottleDecision::Escalation || Self::is_new_alarm( &rec, &evidence_info, reported_alarms, &self.config) {
+                let alarm_id = Self::record_alarm( &rec, &evidence_info, confidence, reported_alarms, &self.config, &mut self.dispatch_history);
                 let info: &str = self.device_infos.get(&rec.device_id).map(|s|s.name.as_str()).unwrap_or("");
                 let descr = format!("🔥 {}\ndevice: {} {}\nfire probability: {}", 
                     rec.time_recorded.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S %Z"), rec.device_id, info, rec.data.fire_prob);
-                let alarm_type = rec.capability().property_name().to_string();
-                let confidence = rec.data.fire_prob;
                 self.process_alarm( hself, &alarm_id, &rec.id, rec.device_id.clone(), descr, rec.time_recorded, alarm_type, confidence, evidence_info).await;
             }
         }
@@ -154,25 +262,37 @@ impl SentinelAlarmMonitor {
 
     async fn process_smoke_alarm (&mut self, hself: ActorHandle<SentinelAlarmMonitorMsg>, rec: Arc<SensorRecord<SmokeData>>) {
         if rec.data.smoke_prob >= self.config.smoke_prob {
+            let alarm_type = rec.capability().property_name().to_string();
+            let confidence = rec.data.smoke_prob;
+            let decision = self.dispatch_history.check( &self.config.throttle, &rec.device_id, &alarm_type, rec.time_recorded, confidence);
+            if decision == ThrottleDecision::Throttle {
+                debug!("throttled alarm {}", Self::alarm_id( &rec));
+                return
+            }
+
             let mut evidence_info = self.retrieve_evidence( &self.hupdater, &rec.evidences, self.config.image_timeout).await;
 
             let reported_alarms = &mut self.reported_smoke_alarms;
-            if let Some(alarm_id) = Self::check_new_alarm( &rec, &evidence_info, reported_alarms, &self.config) { // could use 💨 here but most fires cause smoke alarms
+            if decision == ThrottleDecision::Escalation || Self::is_new_alarm( &rec, &evidence_info, reported_alarms, &self.config) {
+                let alarm_id = Self::record_alarm( &rec, &evidence_info, confidence, reported_alarms, &self.config, &mut self.dispatch_history); // could use 💨 here but most fires cause smoke alarms
                 let info: &str = self.device_infos.get(&rec.device_id).map(|s|s.name.as_str()).unwrap_or("");
                 let descr = format!("🔥 {}\ndevice: {} {}\nsmoke probability: {}", 
                     rec.time_recorded.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S %Z"), rec.device_id, info, rec.data.smoke_prob);
-                let alarm_type = rec.capability().property_name().to_string();
-                let confidence = rec.data.smoke_prob;
                 self.process_alarm( hself, &alarm_id, &rec.id, rec.device_id.clone(), descr, rec.time_recorded, alarm_type, confidence, evidence_info).await;
             }
         }   
     }
 
-    fn check_new_alarm<T> (rec: &Arc<SensorRecord<T>>, evidence: &Vec<EvidenceInfo>, reported_alarms: &mut VecDeque<ReportedAlarm<T>>, config: &SentinelAlarmMonitorConfig) -> Option<String> 
+    fn alarm_id<T> (rec: &SensorRecord<T>)->String where T: RecordDataBounds {
+        format!("{}({},{})", rec.capability().property_name(), rec.device_id, rec.time_recorded.format("%Y-%m-%dT%H:%M:%S%Z"))
+    }
+
+    /// check if this alarm has not been reported within `new_alarm_duration`. This does not record the alarm
+    fn is_new_alarm<T> (rec: &Arc<SensorRecord<T>>, evidence: &Vec<EvidenceInfo>, reported_alarms: &mut VecDeque<ReportedAlarm<T>>, config: &SentinelAlarmMonitorConfig) -> bool 
         where T: RecordDataBounds 
     {
         if config.new_alarm_duration.is_zero() { // every alarm is treated as a new one - no need to store ReportedAlarms
-            Some(format!("{}({},{})", rec.capability().property_name(), rec.device_id, rec.time_recorded.format("%Y-%m-%dT%H:%M:%S%Z")))
+            true
 
         } else {
             // Ok to panic if there is no sim_clock or the config is inconsistent (but should happen sooner?)
@@ -182,17 +302,25 @@ impl SentinelAlarmMonitor {
             let max_age = TimeDelta::from_std(config.old_alarm_duration).unwrap();
             reported_alarms.retain_mut( |alarm| now - alarm.rec.date() < max_age);
 
-            //--- add it if new
-            if !Self::is_reported_alarm(rec, evidence, reported_alarms, config.new_alarm_duration) {
-                let new_alarm = ReportedAlarm { rec: rec.clone(), evidence_info: evidence.clone() };
-                reported_alarms.push_front( new_alarm);
-                Some(format!("{}({},{})", rec.capability().property_name(), rec.device_id, rec.time_recorded.format("%Y-%m-%dT%H:%M:%S%Z")))
-            } else {
-                None
-            }
+            !Self::is_reported_alarm(rec, evidence, reported_alarms, config.new_alarm_duration)
         }
     }
 
+    /// record an alarm that is going to be dispatched and return its id
+    fn record_alarm<T> (rec: &Arc<SensorRecord<T>>, evidence: &Vec<EvidenceInfo>, confidence: f64, reported_alarms: &mut VecDeque<ReportedAlarm<T>>, 
+                        config: &SentinelAlarmMonitorConfig, dispatch_history: &mut AlarmDispatchHistory) -> String 
+        where T: RecordDataBounds 
+    {
+        if !config.new_alarm_duration.is_zero() {
+            let new_alarm = ReportedAlarm { rec: rec.clone(), evidence_info: evidence.clone() };
+            reported_alarms.push_front( new_alarm);
+        }
+
+        dispatch_history.record_dispatch( &config.throttle, &rec.device_id, rec.capability().property_name(), rec.time_recorded, confidence);
+
+        Self::alarm_id( rec)
+    }
+
     fn is_reported_alarm<T> (rec: &SensorRecord<T>, evidence: &Vec<EvidenceInfo>, reported_alarms: &VecDeque<ReportedAlarm<T>>, new_alarm_dur: Duration) -> bool where T: RecordDataBounds {
         for ref alarm in reported_alarms {
             // we count a differing evidence as a new alarm, no matter of how old. This is essential so that we don't
@@ -117,6 +117,37 @@ map_to_opaque_error!{ lettre::transport::smtp::Error => OdinSentinelError::SmtpE
 map_to_opaque_error!{ jsonrpsee::core::client::Error => OdinSentinelError::RpcError }
 
 
+/// map a serde_json parse error for target type `T` into a JsonError that includes the error category,
+/// the (line,column) location (which is part of the serde_json error message) and the type name. With the
+/// `json_snippet` feature this also includes the input bytes surrounding the error location
+pub fn json_error<T> (e: &serde_json::Error, 
+                      #[cfg(feature="json_snippet")] bytes: &[u8], 
+                      #[cfg(not(feature="json_snippet"))] _bytes: &[u8])->OdinSentinelError {
+    let msg = format!("{:?} error parsing {}: {}", e.classify(), std::any::type_name::<T>(), e);
+
+    #[cfg(feature="json_snippet")]
+    let msg = match json_snippet( bytes, e.line(), e.column(), 40) {
+        Some(snippet) => format!("{msg} near '{snippet}'"),
+        None => msg
+    };
+
+    OdinSentinelError::JsonError(msg)
+}
+
+#[cfg(feature="json_snippet")]
+fn json_snippet (bytes: &[u8], line: usize, column: usize, radius: usize)->Option<String> {
+    if line == 0 { return None } // no location info (e.g. IO error)
+
+    let mut offset = 0;
+    for _ in 1..line {
+        offset += bytes[offset..].iter().position(|b| *b == b'\n')? + 1;
+    }
+    let pos = (offset + column).min(bytes.len());
+    let start = pos.saturating_sub(radius);
+    let end = (pos + radius).min(bytes.len());
+    Some( String::from_utf8_lossy( &bytes[start..end]).into_owned())
+}
+
 pub fn no_data (msg: impl ToString)->OdinSentinelError {
     OdinSentinelError::NoDataError(msg.to_string())
 }
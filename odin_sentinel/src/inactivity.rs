@@ -0,0 +1,71 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License. You may obtain a copy
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+use std::{collections::HashSet, time::Duration};
+use chrono::{DateTime,Utc};
+use serde::Serialize;
+use odin_common::datetime::duration_since;
+use crate::{DeviceId, SentinelStore};
+
+/// activity status transition of a Sentinel device
+#[derive(Debug,Clone,PartialEq,Serialize)]
+#[serde(rename_all="camelCase")]
+pub enum InactivityEvent {
+    /// no record within the inactive duration (`last_seen` is `None` if we never got a record)
+    DeviceInactive { device_id: DeviceId, last_seen: Option<DateTime<Utc>> },
+    /// a previously inactive device reported new data
+    DeviceReactivated { device_id: DeviceId, last_seen: DateTime<Utc> }
+}
+
+/// detects active->inactive (and inactive->active) transitions of devices in a [`SentinelStore`] based on
+/// the timestamp of their latest record. Other than the periodic check of the [`SentinelActor`] this only
+/// reports transitions, i.e. each device gets one `DeviceInactive` event until it is reactivated
+#[derive(Debug)]
+pub struct InactivityTracker {
+    inactive_duration: Duration,
+    inactive: HashSet<DeviceId>
+}
+
+impl InactivityTracker {
+    pub fn new (inactive_duration: Duration)->Self {
+        InactivityTracker { inactive_duration, inactive: HashSet::new() }
+    }
+
+    pub fn is_inactive (&self, device_id: &DeviceId)->bool {
+        self.inactive.contains( device_id)
+    }
+
+    /// check all devices in the store against the given time and return the status transitions since the last check
+    pub fn check (&mut self, store: &SentinelStore, now: DateTime<Utc>)->Vec<InactivityEvent> {
+        let mut events = Vec::new();
+
+        for sentinel in store.values_iter() {
+            let device_id = &sentinel.device_id;
+            let last_seen = sentinel.time_recorded;
+            let is_inactive = last_seen.map_or( true, |dt| duration_since( &now, &dt) > self.inactive_duration);
+
+            if is_inactive {
+                if self.inactive.insert( device_id.clone()) {
+                    events.push( InactivityEvent::DeviceInactive { device_id: device_id.clone(), last_seen });
+                }
+            } else if self.inactive.remove( device_id) {
+                if let Some(last_seen) = last_seen {
+                    events.push( InactivityEvent::DeviceReactivated { device_id: device_id.clone(), last_seen });
+                }
+            }
+        }
+
+        events
+    }
+}
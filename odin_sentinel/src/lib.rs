@@ -18,7 +18,7 @@ use std::u64;
 #[doc = include_str!("../doc/odin_sentinel.md")]
 
 use std::{
-    cmp::{min, Ordering}, collections::{HashMap, VecDeque}, fmt::{self,Debug}, 
+    any::Any, cmp::{min, Ordering}, collections::{HashMap, HashSet, VecDeque}, fmt::{self,Debug}, 
     fs::File, future::Future, io::{Read, Write}, ops::RangeBounds, path::{Path,PathBuf}, 
     rc::Rc, sync::{atomic::{self,AtomicU64}, Arc}, time::Duration
 };
@@ -31,6 +31,7 @@ use tokio_util::bytes::Buf;
 use uom::si::f64::{Velocity,ThermodynamicTemperature,ElectricCurrent,ElectricPotential};
 use reqwest::{Client,Response};
 use async_trait::async_trait;
+use futures::{stream, StreamExt, TryStreamExt};
 use paste::paste;
 use lazy_static::lazy_static;
 
@@ -40,7 +41,7 @@ use odin_common::{angle::{LatAngle, LonAngle, Angle},
     geo::DatedGeoPos,
     fs::{ensure_writable_dir, get_filename_extension}
 };
-use odin_actor::{MsgReceiver, Query, ActorHandle};
+use odin_actor::{MsgReceiver, Query, ActorHandle, warn};
 use odin_macro::{define_algebraic_type, match_algebraic_type, define_struct};
 
 mod actor;
@@ -54,6 +55,9 @@ pub mod ws;
 mod live_connector;
 pub use live_connector::*;
 
+mod inactivity;
+pub use inactivity::*;
+
 mod errors;
 pub use errors::*;
 
@@ -77,6 +81,8 @@ pub use signal_cmd::*;
 
 lazy_static! {
     static ref MSG_COUNTER: AtomicU64 = AtomicU64::new(42);
+    static ref UNKNOWN_FIELD_RECORDS: AtomicU64 = AtomicU64::new(0);
+    static ref UNKNOWN_FIELD_NAMES: std::sync::Mutex<HashSet<String>> = std::sync::Mutex::new( HashSet::new());
 }
 
 pub fn get_next_msg_id ()->String {
@@ -112,9 +118,53 @@ pub struct SensorRecord <T> where T: RecordDataBounds {
     #[serde(alias="accelerometer",alias="anemometer",alias="cloudcover",alias="event",alias="fire",alias="image",alias="gas",alias="gps",alias="gyroscope",
             alias="magnetometer",alias="orientation",alias="person",alias="power",alias="smoke",alias="thermometer",alias="valve",alias="voc")]
     pub data: T,
+
+    // properties we don't know (e.g. added by a server update). We keep them so that a schema change does not fail
+    // the whole record but we don't serialize them since our clients would not know them either.
+    // The "type" property is sent with every record but redundant (we know T), hence we don't store it
+    #[serde(flatten, deserialize_with="deserialize_extra")]
+    pub extra: serde_json::Map<String,serde_json::Value>,
+}
+
+/// deserialize the flattened remaining record properties, skipping the known but unused "type" property
+fn deserialize_extra<'de,D> (deserializer: D)->std::result::Result<serde_json::Map<String,serde_json::Value>,D::Error> 
+    where D: serde::Deserializer<'de>
+{
+    struct ExtraVisitor;
+
+    impl<'de> serde::de::Visitor<'de> for ExtraVisitor {
+        type Value = serde_json::Map<String,serde_json::Value>;
+
+        fn expecting (&self, f: &mut fmt::Formatter)->fmt::Result {
+            f.write_str("a map of record properties")
+        }
+
+        fn visit_map<A> (self, mut map: A)->std::result::Result<Self::Value,A::Error> where A: serde::de::MapAccess<'de> {
+            let mut extra = serde_json::Map::new();
+            while let Some(key) = map.next_key::<String>()? {
+                if key == "type" {
+                    map.next_value::<serde::de::IgnoredAny>()?;
+                } else {
+                    extra.insert( key, map.next_value()?);
+                }
+            }
+            Ok(extra)
+        }
+    }
+
+    deserializer.deserialize_map( ExtraVisitor)
 }
 
 impl<T> SensorRecord<T> where T: RecordDataBounds {
+    /// check if we got properties we don't know
+    pub fn has_unknown_fields (&self)->bool {
+        !self.extra.is_empty()
+    }
+
+    pub fn unknown_fields (&self)->impl Iterator<Item=&String> {
+        self.extra.keys()
+    }
+
     fn capability(&self)->SensorCapability {
         T::capability()
     }
@@ -353,7 +403,7 @@ define_sensor_data! { Voc =
    #[serde(rename = "eCO2")] pub e_co2: i32,
 }
 
-#[derive(Serialize,Deserialize,Debug,PartialEq,Copy,Clone,IntoStaticStr)] 
+#[derive(Serialize,Deserialize,Debug,PartialEq,Eq,Hash,Copy,Clone,IntoStaticStr)] 
 #[serde(rename_all="lowercase")]
 #[strum(serialize_all="lowercase")]
 pub enum SensorCapability {
@@ -466,35 +516,38 @@ impl SentinelStore {
     }
     
     pub async fn fetch_from_config (&mut self, client: &Client, config: &SentinelConfig)->Result<()> {
-        let base_uri = config.base_uri.as_str();
-        let access_token = config.access_token.as_str();
-        let n_last = config.max_history_len;  // number of initial records to retrieve
-        let max_len = config.max_history_len; // max number of records to keep
-        let device_filter = &config.device_filter;
-    
-        let device_list = get_device_list( client, base_uri, access_token).await?;
-
-        for device in &device_list.data {
-            if device_filter.is_empty() || device_filter.contains( &device.id) {
-                let device_name = if let Some(info) = &device.info { info.clone() } else { "?".to_string() };
-                let mut sentinel = Sentinel::new( device.id.clone(), device_name, max_len);
-        
-                let sensor_list = get_sensor_list( client, base_uri, access_token, device.id.as_str()).await?;
-                for sensor_data in &sensor_list.data {
-                    for capability in &sensor_data.capabilities {
-                        let updates = sentinel.init_records(client, base_uri, access_token, sensor_data.no, *capability, n_last, max_len).await?;
-                        for u in updates { self.updates.insert( u.record_id().clone(), u); }
-                    }
-                }
-        
-                sentinel.set_time_recorded(); // from latest sensor record
-                self.insert( sentinel.device_id.clone(), sentinel);
-            }
+        let device_list = get_device_list( client, &config.base_uri, &config.access_token).await?;
+
+        for device in device_list.data.iter().filter( |d| config.includes_device( &d.id)) {
+            let (sentinel, updates) = fetch_sentinel( client, config, device).await?;
+            self.merge( sentinel, updates);
         }
     
         Ok(())
     }
 
+    /// same as [`fetch_from_config`] but retrieves up to `max_concurrent` devices at a time. Results are collected
+    /// per device and then merged into the store, i.e. the store is only modified if all retrievals succeed
+    pub async fn fetch_from_config_concurrent (&mut self, client: &Client, config: &SentinelConfig, max_concurrent: usize)->Result<()> {
+        let device_list = get_device_list( client, &config.base_uri, &config.access_token).await?;
+
+        let results: Vec<(Sentinel,Vec<SentinelUpdate>)> = stream::iter( device_list.data.iter().filter( |d| config.includes_device( &d.id)))
+            .map( |device| fetch_sentinel( client, config, device))
+            .buffer_unordered( max_concurrent.max(1))
+            .try_collect().await?;
+
+        for (sentinel, updates) in results {
+            self.merge( sentinel, updates);
+        }
+
+        Ok(())
+    }
+
+    fn merge (&mut self, sentinel: Sentinel, updates: Vec<SentinelUpdate>) {
+        for u in updates { self.updates.insert( u.record_id().clone(), u); }
+        self.insert( sentinel.device_id.clone(), sentinel);
+    }
+
     pub fn is_empty(&self)->bool {
         self.sentinels.is_empty()
     }
@@ -559,11 +612,11 @@ impl SentinelStore {
         let update = sentinel_update.clone(); // we have to do this prior to loosing ownership
 
         if let Some(ref mut sentinel) = self.sentinels.get_mut( sentinel_update.device_id()) {
-            let (added_rec_id, removed_rec_id) = sentinel.update_with( sentinel_update);
+            let (added_rec_id, removed_rec_id, replaced_rec_id) = sentinel.update_with( sentinel_update);
 
             let added = if let Some(added_rec_id) = added_rec_id { 
                 self.updates.insert(added_rec_id.clone(), update.clone());
-                Some(update) 
+                Some(update.clone()) 
             } else { 
                 None  // nothing added
             };
@@ -574,7 +627,14 @@ impl SentinelStore {
                 None  // nothing removed
             };
 
-            SentinelChange{ added, removed }
+            let replaced = if let Some(replaced_rec_id) = replaced_rec_id {
+                self.updates.insert(replaced_rec_id, update.clone()); // same record_id, swap in the new value
+                Some(update)
+            } else {
+                None  // nothing replaced
+            };
+
+            SentinelChange{ added, removed, replaced }
 
         } else { // add it as a new Sentinel (we could also reject here)
             let mut new_sentinel = Sentinel::new( sentinel_update.device_id().clone(), "?".to_string(), max_len);
@@ -583,7 +643,7 @@ impl SentinelStore {
             new_sentinel.update_with( sentinel_update);
             self.sentinels.insert( new_sentinel.device_id.clone(), new_sentinel);
 
-            SentinelChange{ added: Some(update), removed: None } // unknown device, nothing to do
+            SentinelChange{ added: Some(update), removed: None, replaced: None } // unknown device, nothing to do
         }
     }
 
@@ -596,7 +656,14 @@ impl SentinelStore {
     }
 }
 
-pub struct SentinelChange { added: Option<SentinelUpdate>, removed: Option<SentinelUpdate> }
+/// what changed in a SentinelStore as the result of an update. If all fields are `None` the update was
+/// dropped because it was older than the retained window of its device/sensor/capability
+#[derive(Debug)]
+pub struct SentinelChange { 
+    pub added: Option<SentinelUpdate>,    // the update was a new record
+    pub removed: Option<SentinelUpdate>,  // a stored record that got evicted to make room for the added one
+    pub replaced: Option<SentinelUpdate>  // the update replaced a stored record with the same id and timestamp
+}
 
 /// helper type so that we can serialize the Sentinel values as a list
 #[derive(Serialize)]
@@ -643,35 +710,18 @@ define_struct! {
 
 
 impl Sentinel {
-    /// initial bulk retrieval based on capability. Note these are homogenous record type retrievals, i.e. we know (and check) the type
-    /// of the returned records
-    pub async fn init_records( &mut self, client: &Client, base_uri: &str, access_token: &str, 
-                               sensor_no: u32, capability: SensorCapability, n_last: usize, max_len: usize)->Result<Vec<SentinelUpdate>> {
-        let device_id = &self.device_id.as_str();
-        use SensorCapability::*;
-        let updates = match capability {
-            Accelerometer => init_recs( &mut self.accelerometer, get_time_sorted_records( client, base_uri, access_token, device_id, sensor_no, n_last).await?, max_len),
-            Anemometer    => init_recs( &mut self.anemometer,    get_time_sorted_records( client, base_uri, access_token, device_id, sensor_no, n_last).await?, max_len),
-            Cloudcover    => init_recs( &mut self.cloudcover,    get_time_sorted_records( client, base_uri, access_token, device_id, sensor_no, n_last).await?, max_len),
-            Event         => init_recs( &mut self.event,         get_time_sorted_records( client, base_uri, access_token, device_id, sensor_no, n_last).await?, max_len),
-            Fire          => init_recs( &mut self.fire,          get_time_sorted_records( client, base_uri, access_token, device_id, sensor_no, n_last).await?, max_len),
-            Gas           => init_recs( &mut self.gas,           get_time_sorted_records( client, base_uri, access_token, device_id, sensor_no, n_last).await?, max_len),
-            Gps           => init_recs( &mut self.gps,           get_time_sorted_records( client, base_uri, access_token, device_id, sensor_no, n_last).await?, max_len),
-            Gyroscope     => init_recs( &mut self.gyro,          get_time_sorted_records( client, base_uri, access_token, device_id, sensor_no, n_last).await?, max_len),
-            Image         => init_image_recs( &mut self.image,   get_time_sorted_records( client, base_uri, access_token, device_id, sensor_no, n_last).await?, max_len),
-            Magnetometer  => init_recs( &mut self.mag,           get_time_sorted_records( client, base_uri, access_token, device_id, sensor_no, n_last).await?, max_len),
-            Orientation   => init_recs( &mut self.orientation,   get_time_sorted_records( client, base_uri, access_token, device_id, sensor_no, n_last).await?, max_len),
-            Person        => init_recs( &mut self.person,        get_time_sorted_records( client, base_uri, access_token, device_id, sensor_no, n_last).await?, max_len),
-            Power         => init_recs( &mut self.power,         get_time_sorted_records( client, base_uri, access_token, device_id, sensor_no, n_last).await?, max_len),
-            Smoke         => init_recs( &mut self.smoke,         get_time_sorted_records( client, base_uri, access_token, device_id, sensor_no, n_last).await?, max_len),
-            Thermometer   => init_recs( &mut self.thermometer,   get_time_sorted_records( client, base_uri, access_token, device_id, sensor_no, n_last).await?, max_len),
-            Valve         => init_recs( &mut self.valve,         get_time_sorted_records( client, base_uri, access_token, device_id, sensor_no, n_last).await?, max_len),
-            Voc           => init_recs( &mut self.voc,           get_time_sorted_records( client, base_uri, access_token, device_id, sensor_no, n_last).await?, max_len),
-        };
-        Ok(updates)
+    /// sort in update and return (added,removed,replaced) record ids
+    pub fn update_with( &mut self, sentinel_update: SentinelUpdate)->(Option<RecordId>,Option<RecordId>,Option<RecordId>) {
+        let dt = sentinel_update.time_recorded();
+        let res = self.sort_in_update( sentinel_update);
+
+        if (res.0.is_some() || res.2.is_some()) && self.time_recorded.map_or( true, |t| dt > t) {
+            self.time_recorded = Some(dt) // keep track of the latest record timestamp we have
+        }
+        res
     }
 
-    pub fn update_with( &mut self, sentinel_update: SentinelUpdate)->(Option<RecordId>,Option<RecordId>) {
+    fn sort_in_update( &mut self, sentinel_update: SentinelUpdate)->(Option<RecordId>,Option<RecordId>,Option<RecordId>) {
         match_algebraic_type! { sentinel_update: SentinelUpdate as
             Arc<SensorRecord<AccelerometerData>> => sort_in_record( &mut self.accelerometer, sentinel_update, self.max_len),
             Arc<SensorRecord<AnemometerData>>    => sort_in_record( &mut self.anemometer,    sentinel_update, self.max_len),
@@ -744,6 +794,58 @@ impl Sentinel {
         }
     }
 
+    /// get the newest stored record for the capability of the requested record data type. The capability
+    /// determines the record queue, which is then type checked against `T`
+    pub fn latest<T> (&self)->Option<&Arc<SensorRecord<T>>> where T: RecordDataBounds {
+        use SensorCapability::*;
+        let recs: &dyn Any = match T::capability() {
+            Accelerometer => &self.accelerometer,
+            Anemometer    => &self.anemometer,
+            Cloudcover    => &self.cloudcover,
+            Event         => &self.event,
+            Fire          => &self.fire,
+            Gas           => &self.gas,
+            Gps           => &self.gps,
+            Gyroscope     => &self.gyro,
+            Image         => &self.image,
+            Magnetometer  => &self.mag,
+            Orientation   => &self.orientation,
+            Person        => &self.person,
+            Power         => &self.power,
+            Smoke         => &self.smoke,
+            Thermometer   => &self.thermometer,
+            Valve         => &self.valve,
+            Voc           => &self.voc,
+        };
+        recs.downcast_ref::<VecDeque<Arc<SensorRecord<T>>>>().and_then( |recs| recs.front())
+    }
+
+    /// write all stored records of the given capability as CSV in ascending time order. The header row consists of
+    /// `timeRecorded`, `sensorNo` and the (alphabetically ordered) fields of the record data. Physical quantities
+    /// are written as magnitudes in their SI base units (e.g. K for temperatures), non-scalar fields as JSON strings
+    pub fn write_capability_csv<W: Write> (&self, capability: SensorCapability, w: &mut W)->Result<()> {
+        use SensorCapability::*;
+        match capability {
+            Accelerometer => write_recs_csv( &self.accelerometer, w),
+            Anemometer    => write_recs_csv( &self.anemometer, w),
+            Cloudcover    => write_recs_csv( &self.cloudcover, w),
+            Event         => write_recs_csv( &self.event, w),
+            Fire          => write_recs_csv( &self.fire, w),
+            Gas           => write_recs_csv( &self.gas, w),
+            Gps           => write_recs_csv( &self.gps, w),
+            Gyroscope     => write_recs_csv( &self.gyro, w),
+            Image         => write_recs_csv( &self.image, w),
+            Magnetometer  => write_recs_csv( &self.mag, w),
+            Orientation   => write_recs_csv( &self.orientation, w),
+            Person        => write_recs_csv( &self.person, w),
+            Power         => write_recs_csv( &self.power, w),
+            Smoke         => write_recs_csv( &self.smoke, w),
+            Thermometer   => write_recs_csv( &self.thermometer, w),
+            Valve         => write_recs_csv( &self.valve, w),
+            Voc           => write_recs_csv( &self.voc, w),
+        }
+    }
+
     pub fn get_position_at (&self, dt: DateTime<Utc>)->Option<DatedGeoPos> {
         if let Some(i_gps) = get_closest_record_idx( dt, &self.gps) {
             let gps = &self.gps[i_gps].data;
@@ -758,6 +860,42 @@ impl Sentinel {
         }
     }
 
+    /// get the position at the given time by linear interpolation between the two gps records that bracket `dt`.
+    /// Use this for mobile units - if `dt` is outside of the time span of our gps records we fall back to the closest one
+    pub fn get_interpolated_position_at (&self, dt: DateTime<Utc>)->Option<DatedGeoPos> {
+        let gps = &self.gps; // newest record first
+        if gps.is_empty() { return None }
+
+        let newest = &gps[0];
+        let oldest = &gps[gps.len()-1];
+        if dt >= newest.time_recorded { return Some( gps_position( &newest.data, dt)) }
+        if dt <= oldest.time_recorded { return Some( gps_position( &oldest.data, dt)) }
+
+        for i in 1..gps.len() {
+            let r0 = &gps[i];   // older
+            let r1 = &gps[i-1]; // newer
+            if r0.time_recorded <= dt && dt <= r1.time_recorded {
+                let dt_rec = (r1.time_recorded - r0.time_recorded).num_milliseconds();
+                if dt_rec == 0 { return Some( gps_position( &r1.data, dt)) }
+                let f = (dt - r0.time_recorded).num_milliseconds() as f64 / dt_rec as f64;
+
+                let p0 = &r0.data;
+                let p1 = &r1.data;
+                let lat = p0.latitude.degrees() + f * (p1.latitude.degrees() - p0.latitude.degrees());
+
+                let mut dlon = p1.longitude.degrees() - p0.longitude.degrees(); // interpolate across the anti-meridian
+                if dlon > 180.0 { dlon -= 360.0 } else if dlon < -180.0 { dlon += 360.0 }
+                let lon = p0.longitude.degrees() + f * dlon;
+
+                let alt0 = p0.altitude.unwrap_or(0.0);
+                let alt = alt0 + f * (p1.altitude.unwrap_or(alt0) - alt0);
+
+                return Some( DatedGeoPos::new( LatAngle::from_degrees(lat), LonAngle::from_degrees(lon), alt, dt))
+            }
+        }
+        None // can't get here since records are time sorted
+    }
+
 
 }
 
@@ -787,6 +925,58 @@ pub fn get_closest_record_idx<T> (dt: DateTime<Utc>, recs: &VecDeque< Arc<Sensor
     }
 }
 
+fn gps_position (gps: &GpsData, dt: DateTime<Utc>)->DatedGeoPos {
+    DatedGeoPos::new( gps.latitude, gps.longitude, gps.altitude.unwrap_or(0.0), dt)
+}
+
+fn write_recs_csv<T,W> (list: &VecDeque<Arc<SensorRecord<T>>>, w: &mut W)->Result<()> 
+    where T: RecordDataBounds, W: Write
+{
+    // uom quantities and angles serialize as plain numbers in their base units, which is what we want here
+    let mut rows: Vec<(&Arc<SensorRecord<T>>, serde_json::Map<String,serde_json::Value>)> = Vec::with_capacity(list.len());
+    for rec in list.iter().rev() { // list is newest first
+        match serde_json::to_value( &rec.data)? {
+            serde_json::Value::Object(map) => rows.push( (rec,map) ),
+            v => return Err( op_failed( format!("record data is not a struct: {v}")))
+        }
+    }
+
+    let field_names: Vec<String> = if let Some((_,map)) = rows.first() {
+        map.keys().cloned().collect()
+    } else {
+        Vec::new()
+    };
+
+    write!( w, "timeRecorded,sensorNo")?;
+    for name in &field_names { write!( w, ",{}", name)?; }
+    writeln!(w)?;
+
+    for (rec,map) in &rows {
+        write!( w, "{},{}", rec.time_recorded.to_rfc3339_opts( chrono::SecondsFormat::Millis, true), rec.sensor_no)?;
+        for name in &field_names {
+            write!( w, ",{}", csv_field( map.get(name)))?;
+        }
+        writeln!(w)?;
+    }
+
+    Ok(())
+}
+
+fn csv_field (v: Option<&serde_json::Value>)->String {
+    use serde_json::Value;
+    let s = match v {
+        None | Some(Value::Null) => return String::new(),
+        Some(Value::String(s)) => s.clone(),
+        Some(v) => v.to_string()
+    };
+
+    if s.contains( |c| c == ',' || c == '"' || c == '\n') {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s
+    }
+}
+
 pub fn rec_key (device_id: &str, sensor_no: u32, capa: SensorCapability)->String {
     format!("/devices/{}/sensors/{}/{}", device_id, sensor_no, capa.property_name())
 }
@@ -805,41 +995,10 @@ fn add_latest_recs<T> (list: &VecDeque<Arc<SensorRecord<T>>>, latest_recs: &mut
     }
 }
 
-// note that most record lists only have a single sensor, but some (images) have several. The max_len is per
-// sensor so we have to sort in recs after the first sensor batch
-fn init_recs<T> (list: &mut VecDeque<Arc<SensorRecord<T>>>, recs: Vec<SensorRecord<T>>, max_len: usize)->Vec<SentinelUpdate> 
-    where T: RecordDataBounds, SentinelUpdate: From<Arc<SensorRecord<T>>>
-{
-    let mut updates = Vec::<SentinelUpdate>::with_capacity(recs.len());
-
-    if list.is_empty() { // first sensor, recs is already time sorted
-        for rec in recs.into_iter() {
-            let rec = Arc::new(rec);
-            updates.push( rec.clone().into()); 
-            list.push_back( rec);
-        }
-    } else { // different sensor, we have to sort in
-        for rec in recs.into_iter() {
-            let rec = Arc::new(rec);
-            updates.push( rec.clone().into()); 
-            sort_in_record( list, rec, max_len);
-        }
-    }
-
-    updates
-}
-
-// this is a pain - we need to set the local filename explicitly
-fn init_image_recs (list: &mut VecDeque<Arc<SensorRecord<ImageData>>>, mut recs: Vec<SensorRecord<ImageData>>, max_len: usize)->Vec<SentinelUpdate> {
-    for rec in recs.iter_mut() {
-        rec.set_local_filename()
-    }
-    init_recs(list, recs, max_len)
-}
-
 /// sort in record according to timestamp (newer records first). Note this transfers ownership of 'rec'.
-/// owner-specific housekeeping can be performed through provided (optional) closures
-pub fn sort_in_record<T> (list: &mut VecDeque<Arc<SensorRecord<T>>>, rec: Arc<SensorRecord<T>>, max_len: usize)->(Option<RecordId>,Option<RecordId>)
+/// Returns the (added,removed,replaced) record ids. If all are `None` the record was not stored because it
+/// was older than the max_len records we already have for this sensor
+pub fn sort_in_record<T> (list: &mut VecDeque<Arc<SensorRecord<T>>>, rec: Arc<SensorRecord<T>>, max_len: usize)->(Option<RecordId>,Option<RecordId>,Option<RecordId>)
     where T: RecordDataBounds, SentinelUpdate: From<Arc<SensorRecord<T>>>
 {
     let mut n_sensor_recs = 0;
@@ -851,14 +1010,15 @@ pub fn sort_in_record<T> (list: &mut VecDeque<Arc<SensorRecord<T>>>, rec: Arc<Se
         if rec.time_recorded > r.time_recorded { // insert record
             added = Some(rec.id.clone());
             list.insert( i, rec);
-            removed = remove_excess_sensor_rec( list, sensor_no, i+1, n_sensor_recs, max_len);
+            removed = remove_excess_sensor_rec( list, sensor_no, i+1, n_sensor_recs+1, max_len); // +1 for the inserted rec
 
-            return (added,removed)
+            return (added,removed,None)
 
         } else if rec.time_recorded == r.time_recorded {
             if rec.id == r.id && sensor_no == r.sensor_no { // replace record, no need to add or remove
+                let replaced = Some(rec.id.clone());
                 list[i] = rec;
-                return (None,None)
+                return (None,None,replaced)
             }
         }
         if sensor_no == r.sensor_no { n_sensor_recs += 1; } 
@@ -870,7 +1030,7 @@ pub fn sort_in_record<T> (list: &mut VecDeque<Arc<SensorRecord<T>>>, rec: Arc<Se
         list.push_back( rec);
     }
 
-    (added,removed)
+    (added,removed,None)
 }
 
 // find the first sensor rec from start_idx that exceeds max_len
@@ -890,11 +1050,57 @@ fn remove_excess_sensor_rec<T> (list: &mut VecDeque<Arc<SensorRecord<T>>>, senso
     None
 }
 
+/// retrieve the sensor records for all capabilities of the given device
+async fn fetch_sentinel (client: &Client, config: &SentinelConfig, device: &Device)->Result<(Sentinel,Vec<SentinelUpdate>)> {
+    let n_last = config.max_history_len;  // number of initial records to retrieve
+    let max_len = config.max_history_len; // max number of records to keep
+    let device_name = if let Some(info) = &device.info { info.clone() } else { "?".to_string() };
+
+    fetch_device_records( client, &config.base_uri, &config.access_token, &device.id, device_name, n_last, max_len).await
+}
+
+/// max number of concurrent capability record requests per device
+const MAX_CONCURRENT_CAPABILITY_REQUESTS: usize = 8;
+
+/// retrieve the `n_last` records of all sensors/capabilities of a device concurrently and sort them into a new Sentinel
+async fn fetch_device_records (client: &Client, base_uri: &str, access_token: &str, device_id: &str, device_name: String,
+                               n_last: usize, max_len: usize)->Result<(Sentinel,Vec<SentinelUpdate>)> {
+    let sensor_list = get_sensor_list( client, base_uri, access_token, device_id).await?;
+    let requests: Vec<(u32,SensorCapability)> = sensor_list.data.iter()
+        .flat_map( |sensor_data| sensor_data.capabilities.iter().map( |capability| (sensor_data.no, *capability)))
+        .collect();
+
+    let results: Vec<Vec<SentinelUpdate>> = stream::iter( requests)
+        .map( |(sensor_no,capability)| get_capability_updates( client, base_uri, access_token, device_id, sensor_no, capability, n_last))
+        .buffer_unordered( MAX_CONCURRENT_CAPABILITY_REQUESTS)
+        .try_collect().await?;
+
+    let mut sentinel = Sentinel::new( device_id.to_string(), device_name, max_len);
+    let mut updates = Vec::new();
+    for update in results.into_iter().flatten() { // results are in order of completion, sort_in_record takes care of time order
+        sentinel.update_with( update.clone());
+        updates.push( update);
+    }
+
+    sentinel.set_time_recorded(); // from latest sensor record
+    Ok((sentinel, updates))
+}
+
+/// get a fully populated Sentinel for the given device, with up to `n_last` records for each of its sensor capabilities.
+/// Records for the different sensors/capabilities are retrieved concurrently
+pub async fn get_device_snapshot (client: &Client, base_uri: &str, access_token: &str, device_id: &str, n_last: usize)->Result<Sentinel> {
+    let device_list = get_device_list( client, base_uri, access_token).await?;
+    let device_name = device_list.data.iter().find( |d| d.id == device_id).and_then( |d| d.info.clone()).unwrap_or( "?".to_string());
+
+    let (sentinel,_) = fetch_device_records( client, base_uri, access_token, device_id, device_name, n_last, n_last).await?;
+    Ok(sentinel)
+}
+
 /* #endregion internal data store */
 
 /* #region config  ************************************************************************************/
 
-#[derive(Deserialize,Serialize,Debug)]
+#[derive(Deserialize,Serialize,Debug,Clone)]
 #[serde(default)]
 pub struct SentinelConfig {
     pub base_uri: String,
@@ -906,9 +1112,15 @@ pub struct SentinelConfig {
     pub ping_interval: Option<Duration>, // interval duration for sending Ping messages on the websocket 
     pub reconnect_delay: Option<Duration>, // sleep duration after which we try to re-initializa a broken websocket 
     pub device_filter: Vec<String>, // optional list of device_ids to filter for
+    pub capability_filter: HashSet<SensorCapability>, // optional set of capabilities to forward updates for
 
     pub inactive_duration: Duration, // max duration since last update after which a device is considered to be inactive
-    pub inactive_interval: Duration  // how often we check for inactive devices
+    pub inactive_interval: Duration, // how often we check for inactive devices
+
+    pub connect_timeout: Duration, // max duration to establish a http connection
+    pub request_timeout: Duration, // max duration for a complete http request (including the response body)
+    pub pool_max_idle: usize, // max number of idle http connections we keep per host
+    pub pool_idle_timeout: Duration, // duration after which idle http connections are closed
 }
 
 impl Default for SentinelConfig {
@@ -925,12 +1137,41 @@ impl Default for SentinelConfig {
             ping_interval: Some(Duration::from_secs(25)),
             reconnect_delay: None,
             device_filter: Vec::new(), // default is no filter
+            capability_filter: HashSet::new(), // default is no filter
             inactive_duration: Duration::from_secs( 7200), // inactive if no update for 2h
-            inactive_interval: Duration::from_secs(300) // check every 5 min
+            inactive_interval: Duration::from_secs(300), // check every 5 min
+            connect_timeout: Duration::from_secs(10),
+            request_timeout: Duration::from_secs(120), // this includes image downloads
+            pool_max_idle: 8,
+            pool_idle_timeout: Duration::from_secs(90),
         }
     }
 }
 
+impl SentinelConfig {
+    /// check if the given device passes our `device_filter` (an empty filter includes all devices)
+    pub fn includes_device (&self, device_id: &str)->bool {
+        self.device_filter.is_empty() || self.device_filter.iter().any( |d| d == device_id)
+    }
+
+    /// check if updates of the given capability should be forwarded (an empty `capability_filter` forwards all)
+    pub fn forwards_capability (&self, capability: SensorCapability)->bool {
+        self.capability_filter.is_empty() || self.capability_filter.contains( &capability)
+    }
+}
+
+/// create a http client for Delphire server requests that is configured according to the timeout and connection pool
+/// settings of the provided [`SentinelConfig`]. Use this instead of a default `Client`, which does not have a request timeout
+pub fn build_sentinel_client (config: &SentinelConfig)->Result<Client> {
+    let client = Client::builder()
+        .connect_timeout( config.connect_timeout)
+        .timeout( config.request_timeout)
+        .pool_max_idle_per_host( config.pool_max_idle)
+        .pool_idle_timeout( config.pool_idle_timeout)
+        .build()?;
+    Ok(client)
+}
+
 pub fn sentinel_cache_dir()->PathBuf {
     let path = odin_build::cache_dir().join("sentinel");
     // Ok to panic - this is called during sys init
@@ -968,7 +1209,8 @@ pub struct SensorInfo {
 }
 
 define_algebraic_type! { 
-    pub ExternalImage: Serialize + Deserialize = DirectUriImage
+    #[serde(tag="type")]
+    pub ExternalImage: Serialize + Deserialize = #[serde(rename="image")] DirectUriImage
 
     pub fn name (&self)->&str { __.name.as_str() }
 
@@ -988,7 +1230,6 @@ define_algebraic_type! {
 
 /// something we can retrieve from a fixed URI with a simple GET
 #[derive(Serialize,Deserialize,Debug)]
-#[serde(rename="image")] // FIXME - this does not rename variant names ?
 pub struct DirectUriImage {
     pub name: String,
     pub sensors: Vec<u32>, // Sentinel image sensor_no for which this external image can be used (empty means any one)
@@ -1072,13 +1313,36 @@ pub trait SentinelConnector {
 // the reqwest::Response::json() alternative does not preserve enough error information
 async fn from_json<T> (response: Response)->Result<T> where T: DeserializeOwned {
     let bytes = response.bytes().await?;
-    serde_json::from_slice( &bytes).map_err(|e| {
-        //let mut s = String::new();
-        //bytes.reader().read_to_string(&mut s);
-        //println!("{s}");
+    parse_json( &bytes)
+}
+
+/// number of received records that had unknown fields (see [`SensorRecord::extra`]) since start
+pub fn unknown_field_record_count ()->u64 {
+    UNKNOWN_FIELD_RECORDS.load( atomic::Ordering::Relaxed)
+}
+
+/// count records with unknown fields, which usually means the server schema has changed. Since this then applies
+/// to all subsequent records we only report the first record for each new unknown field name
+pub fn check_unknown_fields<T> (recs: &[SensorRecord<T>]) where T: RecordDataBounds {
+    for rec in recs.iter().filter( |rec| rec.has_unknown_fields()) {
+        let n = UNKNOWN_FIELD_RECORDS.fetch_add( 1, atomic::Ordering::Relaxed) + 1;
+
+        let mut known_names = UNKNOWN_FIELD_NAMES.lock().unwrap();
+        let new_fields: Vec<&String> = rec.unknown_fields().filter( |f| known_names.insert( f.to_string())).collect();
+        if !new_fields.is_empty() {
+            warn!("record {} has new unknown fields {:?} ({} records with unknown fields so far)", rec.id, new_fields, n);
+        }
+    }
+}
 
-        OdinSentinelError::JsonError(e.to_string())
-    })
+/// names of all unknown record fields we have seen since start
+pub fn unknown_field_names ()->Vec<String> {
+    UNKNOWN_FIELD_NAMES.lock().unwrap().iter().cloned().collect()
+}
+
+/// parse JSON input into the target type, reporting errors with type and location context
+pub fn parse_json<T> (bytes: &[u8])->Result<T> where T: DeserializeOwned {
+    serde_json::from_slice( bytes).map_err(|e| json_error::<T>( &e, bytes))
 }
 
 pub async fn get_device_list (client: &Client, base_uri: &str, access_token: &str)->Result<DeviceList> {
@@ -1107,6 +1371,7 @@ pub async fn get_time_sorted_records <T> (client: &Client, base_uri: &str, acces
     let uri = format!("{base_uri}/devices/{device_id}/sensors/{sensor_no}/{capability:?}?sort=timeRecorded,DESC&limit={n_last}");
     let response = client.get(uri).bearer_auth(access_token).send().await?;
     let record_list: RecordList<T> = from_json(response).await?; 
+    check_unknown_fields( &record_list.data);
     Ok(record_list.data)
 } 
 
@@ -1116,9 +1381,49 @@ pub async fn get_records_since <T> (client: &Client, base_uri: &str, access_toke
     let uri = format!("{base_uri}/{uri_path}?sort=timeRecorded,DESC&last={last}");
     let response = client.get(uri).bearer_auth(access_token).send().await?;
     let record_list: RecordList<T> = from_json(response).await?; 
+    check_unknown_fields( &record_list.data);
     Ok(record_list.data)
 }
 
+async fn get_updates <T> (client: &Client, base_uri: &str, access_token: &str, device_id: &str, sensor_no:u32, n_last: usize) -> Result<Vec<SentinelUpdate>> 
+    where T: RecordDataBounds, SentinelUpdate: From<Arc<SensorRecord<T>>>
+{
+    let recs = get_time_sorted_records::<T>( client, base_uri, access_token, device_id, sensor_no, n_last).await?;
+    Ok( recs.into_iter().map( |rec| Arc::new(rec).into()).collect() )
+}
+
+async fn get_image_updates (client: &Client, base_uri: &str, access_token: &str, device_id: &str, sensor_no:u32, n_last: usize) -> Result<Vec<SentinelUpdate>> {
+    let mut recs = get_time_sorted_records::<ImageData>( client, base_uri, access_token, device_id, sensor_no, n_last).await?;
+    for rec in recs.iter_mut() { rec.set_local_filename() } // this is a pain - we need to set the local filename explicitly
+    Ok( recs.into_iter().map( |rec| Arc::new(rec).into()).collect() )
+}
+
+/// get the `n_last` (time sorted) records for a given sensor and capability as SentinelUpdates. This is the dynamically
+/// typed counterpart of [`get_time_sorted_records`]
+pub async fn get_capability_updates (client: &Client, base_uri: &str, access_token: &str, device_id: &str, 
+                                     sensor_no: u32, capability: SensorCapability, n_last: usize) -> Result<Vec<SentinelUpdate>> {
+    use SensorCapability::*;
+    match capability {
+        Accelerometer => get_updates::<AccelerometerData>( client, base_uri, access_token, device_id, sensor_no, n_last).await,
+        Anemometer    => get_updates::<AnemometerData>( client, base_uri, access_token, device_id, sensor_no, n_last).await,
+        Cloudcover    => get_updates::<CloudcoverData>( client, base_uri, access_token, device_id, sensor_no, n_last).await,
+        Event         => get_updates::<EventData>( client, base_uri, access_token, device_id, sensor_no, n_last).await,
+        Fire          => get_updates::<FireData>( client, base_uri, access_token, device_id, sensor_no, n_last).await,
+        Gas           => get_updates::<GasData>( client, base_uri, access_token, device_id, sensor_no, n_last).await,
+        Gps           => get_updates::<GpsData>( client, base_uri, access_token, device_id, sensor_no, n_last).await,
+        Gyroscope     => get_updates::<GyroscopeData>( client, base_uri, access_token, device_id, sensor_no, n_last).await,
+        Image         => get_image_updates( client, base_uri, access_token, device_id, sensor_no, n_last).await,
+        Magnetometer  => get_updates::<MagnetometerData>( client, base_uri, access_token, device_id, sensor_no, n_last).await,
+        Orientation   => get_updates::<OrientationData>( client, base_uri, access_token, device_id, sensor_no, n_last).await,
+        Person        => get_updates::<PersonData>( client, base_uri, access_token, device_id, sensor_no, n_last).await,
+        Power         => get_updates::<PowerData>( client, base_uri, access_token, device_id, sensor_no, n_last).await,
+        Smoke         => get_updates::<SmokeData>( client, base_uri, access_token, device_id, sensor_no, n_last).await,
+        Thermometer   => get_updates::<ThermometerData>( client, base_uri, access_token, device_id, sensor_no, n_last).await,
+        Valve         => get_updates::<ValveData>( client, base_uri, access_token, device_id, sensor_no, n_last).await,
+        Voc           => get_updates::<VocData>( client, base_uri, access_token, device_id, sensor_no, n_last).await,
+    }
+}
+
 pub async fn get_latest_record <T> (client: &Client, base_uri: &str, access_token: &str, 
                                     device_id: &str, sensor_no:u32) -> Result<SensorRecord<T>> 
     where T: RecordDataBounds, SentinelUpdate: From<Arc<SensorRecord<T>>>
@@ -13,7 +13,7 @@
  */
 #![allow(unused)]
 
-use std::{future,collections::{VecDeque,HashMap},sync::{Arc,atomic::AtomicU64,Mutex}};
+use std::{future,collections::{VecDeque,HashMap,HashSet},sync::{Arc,atomic::AtomicU64,Mutex}};
 use futures::{TryFutureExt, stream::{StreamExt,SplitStream,SplitSink}, SinkExt};
 use tokio_tungstenite::{tungstenite::protocol::Message, MaybeTlsStream};
 use tokio::{select,time::{sleep,Sleep}};
@@ -22,6 +22,7 @@ use async_trait::async_trait;
 
 use odin_actor::prelude::*;
 use odin_common::{fs::{ensure_writable_dir, remove_old_files}, if_let, strings::str_from_last, collections::Snapshot, admin};
+use odin_macro::match_algebraic_type;
 
 use crate::*;
 use crate::actor::*;
@@ -54,6 +55,17 @@ impl LiveSentinelConnector {
         LiveSentinelConnector { config: Arc::new(config), connection: None }
     }
 
+    /// restrict forwarded updates to the given capabilities (an empty set forwards all). Note this has to be
+    /// called before the connector is started since running connection tasks share the config at that point
+    pub fn set_capability_filter (&mut self, caps: HashSet<SensorCapability>) {
+        Arc::make_mut( &mut self.config).capability_filter = caps;
+    }
+
+    /// check if the given update passes our capability filter
+    pub fn forwards_update (&self, update: &SentinelUpdate)->bool {
+        self.config.forwards_capability( update.capability())
+    }
+
     /// called from actor ctor (2nd half of our initialization)
     async fn initialize (&mut self, hself: ActorHandle<SentinelActorMsg>)->Result<()> {
         self.connection = Some(LiveConnection::new(self.config.clone(), hself).await?);
@@ -151,7 +163,7 @@ impl LiveConnection {
         let cache_dir = Arc::new(sentinel_cache_dir());
 
         //--- get current sentinel data according to config (there is no point spawning tasks if we don't have a list of devices to watch)
-        let http_client = Client::new();
+        let http_client = build_sentinel_client( &config)?;
         let mut sentinel_store = SentinelStore::new();
         sentinel_store.fetch_from_config( &http_client, &config).await?; // retrieve all records we need - this can take some time
 
@@ -203,7 +215,10 @@ impl LiveConnection {
                       device_ids: Vec<String>, mut latest_recs: HashMap<String,String>,
                       file_request_tx: MpscSender<FileRequest>, ws_cmd_rx: MpscReceiver<String>) {
         let mut cycle = 0;
-        let client = reqwest::Client::new();
+        let client = match build_sentinel_client( &config) {
+            Ok(client) => client,
+            Err(e) => { error!("failed to create http client: {}", e); return }
+        };
         let ping_interval = if let Some(dur) = config.ping_interval { dur } else { Duration::MAX };
 
         loop {
@@ -295,37 +310,21 @@ impl LiveConnection {
             Message::Text(json) = { msg } else { Err(ws_protocol_error("ignored binary message")) }, // ignore binary messages
             Ok(msg) = { serde_json::from_str::<WsMsg>(&json) } else { warn!("malformed websocket message {json}"); Err(ws_protocol_error("malformed message")) },
             WsMsg::Record { device_id, sensor_no, rec_type } = { msg } else { Err(ws_protocol_error("unknown record type")) } => { // ignore other WsMsg variants
-                use SensorCapability::*;
-                match rec_type {
-                    Accelerometer => Self::get_and_send_update::<AccelerometerData>( hself, client, config, &device_id, sensor_no, latest_recs).await,
-                    Anemometer    => Self::get_and_send_update::<AnemometerData>( hself, client, config, &device_id, sensor_no, latest_recs).await,
-                    Cloudcover    => Self::get_and_send_update::<CloudcoverData>( hself, client, config, &device_id, sensor_no, latest_recs).await,
-                    Event         => Self::get_and_send_update::<EventData>( hself, client, config, &device_id, sensor_no, latest_recs).await,
-                    Fire          => Self::get_and_send_update::<FireData>( hself, client, config, &device_id, sensor_no, latest_recs).await,
-                    Gas           => Self::get_and_send_update::<GasData>( hself, client, config, &device_id, sensor_no, latest_recs).await,
-                    Gps           => Self::get_and_send_update::<GpsData>( hself, client, config, &device_id, sensor_no, latest_recs).await,
-                    Gyroscope     => Self::get_and_send_update::<GyroscopeData>( hself, client, config, &device_id, sensor_no, latest_recs).await,
-                    Magnetometer  => Self::get_and_send_update::<MagnetometerData>( hself, client, config, &device_id, sensor_no, latest_recs).await,
-                    Orientation   => Self::get_and_send_update::<OrientationData>( hself, client, config, &device_id, sensor_no, latest_recs).await,
-                    Person        => Self::get_and_send_update::<PersonData>( hself, client, config, &device_id, sensor_no, latest_recs).await,
-                    Power         => Self::get_and_send_update::<PowerData>( hself, client, config, &device_id, sensor_no, latest_recs).await,
-                    Smoke         => Self::get_and_send_update::<SmokeData>( hself, client, config, &device_id, sensor_no, latest_recs).await,
-                    Thermometer   => Self::get_and_send_update::<ThermometerData>( hself, client, config, &device_id, sensor_no, latest_recs).await,
-                    Valve         => Self::get_and_send_update::<ValveData>( hself, client, config, &device_id, sensor_no, latest_recs).await,
-                    Voc           => Self::get_and_send_update::<VocData>( hself, client, config, &device_id, sensor_no, latest_recs).await,
-
-                    Image         => Self::get_and_send_image_update( hself, client, config, &device_id, sensor_no, latest_recs, cache_dir, file_request_tx).await,
+                if let Some(update) = get_notified_update( client, config, &device_id, sensor_no, rec_type).await? {
+                    Self::send_update( hself, config, update, latest_recs, cache_dir, file_request_tx).await?;
                 }
+                Ok(())
             }
         }
     }
 
-    async fn get_and_send_update<T>(hself: &ActorHandle<SentinelActorMsg>, client: &Client, config: &SentinelConfig, 
-                                    device_id: &str, sensor_no: u32, latest_recs: &mut HashMap<String,String>) -> Result<()> 
-        where T: RecordDataBounds, SentinelUpdate: From<Arc<SensorRecord<T>>>
-    {
-        let rec = get_latest_record::<T>(client, &config.base_uri, &config.access_token, device_id, sensor_no).await?;
-        let update = SentinelUpdate::from(Arc::new(rec));
+    async fn send_update (hself: &ActorHandle<SentinelActorMsg>, config: &SentinelConfig, update: SentinelUpdate,
+                          latest_recs: &mut HashMap<String,String>, 
+                          cache_dir: &PathBuf, file_request_tx: &MpscSender<FileRequest>)->Result<()> {
+        match_algebraic_type! { update: SentinelUpdate as
+            ref Arc<SensorRecord<ImageData>> => Self::request_image_file( config, cache_dir, file_request_tx, update).await?,
+            _ => {}
+        }
         Self::update_latest_recs( latest_recs, &update);
         hself.send_msg( UpdateStore( update)).await?;
 
@@ -337,88 +336,24 @@ impl LiveConnection {
         latest_recs.insert(rec_key, update.record_id().clone());
     }
 
-    async fn get_and_send_image_update (hself: &ActorHandle<SentinelActorMsg>, client: &Client, config: &SentinelConfig, 
-                                        device_id: &str, sensor_no: u32, latest_recs: &mut HashMap<String,String>,
-                                        cache_dir: &PathBuf, file_request_tx: &MpscSender<FileRequest> ) -> Result<()>  
-    {
-        let mut rec = get_latest_record::<ImageData>(client, &config.base_uri, &config.access_token, device_id, sensor_no).await?;
-        rec.set_local_filename();
-
-        Self::request_image_file( config, cache_dir, file_request_tx, &rec).await?;
-        let update = SentinelUpdate::from(Arc::new(rec));
-        Self::update_latest_recs( latest_recs, &update);
-        hself.send_msg( UpdateStore( update)).await?;
-
-        Ok(())
-    }
-
     async fn get_and_send_missing_updates (hself: &ActorHandle<SentinelActorMsg>, client: &Client, config: &SentinelConfig, 
                                            latest_recs: &mut HashMap<String,String>,
                                            cache_dir: &PathBuf, file_request_tx: &MpscSender<FileRequest> )->Result<()> {
-        let base_uri = config.base_uri.as_str();
-        let access_token = config.access_token.as_str();
-
         for (uri_path, rec_id) in latest_recs.snapshot() {
-            if let Some(rec_type) = str_from_last( &uri_path, '/') {
-                use SensorCapability::*;
-
-                let res = match SensorCapability::capability_of(rec_type) {
-                    Some(Accelerometer)  => Self::get_and_send_missing::<AccelerometerData>( hself, client, config, &uri_path, &rec_id, latest_recs).await,
-                    Some(Anemometer)     => Self::get_and_send_missing::<AnemometerData>( hself, client, config, &uri_path, &rec_id, latest_recs).await,
-                    Some(Cloudcover)     => Self::get_and_send_missing::<CloudcoverData>( hself, client, config, &uri_path, &rec_id, latest_recs).await,
-                    Some(Event)          => Self::get_and_send_missing::<EventData>( hself, client, config, &uri_path, &rec_id, latest_recs).await,
-                    Some(Fire)           => Self::get_and_send_missing::<FireData>( hself, client, config, &uri_path, &rec_id, latest_recs).await,
-                    Some(Gas)            => Self::get_and_send_missing::<GasData>( hself, client, config, &uri_path, &rec_id, latest_recs).await,
-                    Some(Gps)            => Self::get_and_send_missing::<GpsData>( hself, client, config, &uri_path, &rec_id, latest_recs).await,
-                    Some(Gyroscope)      => Self::get_and_send_missing::<GyroscopeData>( hself, client, config, &uri_path, &rec_id, latest_recs).await,
-                    Some(Magnetometer)   => Self::get_and_send_missing::<MagnetometerData>( hself, client, config, &uri_path, &rec_id, latest_recs).await,
-                    Some(Orientation)    => Self::get_and_send_missing::<OrientationData>( hself, client, config, &uri_path, &rec_id, latest_recs).await,
-                    Some(Person)         => Self::get_and_send_missing::<PersonData>( hself, client, config, &uri_path, &rec_id, latest_recs).await,
-                    Some(Power)          => Self::get_and_send_missing::<PowerData>( hself, client, config, &uri_path, &rec_id, latest_recs).await,
-                    Some(Smoke)          => Self::get_and_send_missing::<SmokeData>( hself, client, config, &uri_path, &rec_id, latest_recs).await,
-                    Some(Thermometer)    => Self::get_and_send_missing::<ThermometerData>( hself, client, config, &uri_path, &rec_id, latest_recs).await,
-                    Some(Valve)          => Self::get_and_send_missing::<ValveData>( hself, client, config, &uri_path, &rec_id, latest_recs).await,
-                    Some(Voc)            => Self::get_and_send_missing::<VocData>( hself, client, config, &uri_path, &rec_id, latest_recs).await,
-
-                    Some(Image)          => Self::get_and_send_missing_images( hself, client, config, &uri_path, &rec_id, latest_recs, cache_dir, file_request_tx).await,
-
-                    None => Err( op_failed("unknown capability")) 
-                };
-                if let Err(e) = res { warn!("failed to get missing updates for {uri_path}: {e}") }
-            }
-        }
-
-        Ok(())
-    }
-
-    async fn get_and_send_missing<T> (hself: &ActorHandle<SentinelActorMsg>, 
-                                      client: &Client, config: &SentinelConfig, uri_path: &str, last: &str, 
-                                      latest_recs: &mut HashMap<String,String>) -> Result<()> 
-        where T: RecordDataBounds, SentinelUpdate: From<Arc<SensorRecord<T>>>
-    {
-        let recs = get_records_since::<T>(client, &config.base_uri, &config.access_token, uri_path, last).await?;
-        for rec in recs.into_iter() {
-            let update = SentinelUpdate::from(Arc::new(rec));
-            Self::update_latest_recs( latest_recs, &update);
-            hself.send_msg( UpdateStore(update)).await?;
+            let res = Self::get_and_send_missing( hself, client, config, &uri_path, &rec_id, latest_recs, cache_dir, file_request_tx).await;
+            if let Err(e) = res { warn!("failed to get missing updates for {uri_path}: {e}") }
         }
 
         Ok(())
     }
 
-    async fn get_and_send_missing_images (hself: &ActorHandle<SentinelActorMsg>, 
-                                      client: &Client, config: &SentinelConfig, uri_path: &str, last: &str, 
-                                      latest_recs: &mut HashMap<String,String>,
-                                      cache_dir: &PathBuf, file_request_tx: &MpscSender<FileRequest> ) -> Result<()> 
+    async fn get_and_send_missing (hself: &ActorHandle<SentinelActorMsg>, 
+                                   client: &Client, config: &SentinelConfig, uri_path: &str, last: &str, 
+                                   latest_recs: &mut HashMap<String,String>,
+                                   cache_dir: &PathBuf, file_request_tx: &MpscSender<FileRequest> ) -> Result<()> 
     {
-        let recs = get_records_since::<ImageData>(client, &config.base_uri, &config.access_token, uri_path, last).await?;
-        for mut rec in recs.into_iter() {
-            rec.set_local_filename();
-
-            Self::request_image_file( config, cache_dir, file_request_tx, &rec).await?;
-            let update = SentinelUpdate::from(Arc::new(rec));
-            Self::update_latest_recs( latest_recs, &update);
-            hself.send_msg( UpdateStore(update)).await?;
+        for update in get_missing_updates( client, config, uri_path, last).await? {
+            Self::send_update( hself, config, update, latest_recs, cache_dir, file_request_tx).await?;
         }
 
         Ok(())
@@ -485,6 +420,92 @@ impl LiveConnection {
 
 /* #endregion LiveConnection */
 
+/* #region record retrieval ******************************************************************************************/
+
+/// retrieve the record announced by a websocket record notification. This returns `None` without retrieving
+/// anything if the capability is not forwarded according to the `capability_filter` of the provided config
+pub async fn get_notified_update (client: &Client, config: &SentinelConfig, 
+                                  device_id: &str, sensor_no: u32, rec_type: SensorCapability)->Result<Option<SentinelUpdate>> {
+    if !config.forwards_capability( rec_type) { return Ok(None) } // no need to retrieve records we don't forward
+
+    use SensorCapability::*;
+    let update = match rec_type {
+        Accelerometer => get_latest_update::<AccelerometerData>( client, config, device_id, sensor_no).await?,
+        Anemometer    => get_latest_update::<AnemometerData>( client, config, device_id, sensor_no).await?,
+        Cloudcover    => get_latest_update::<CloudcoverData>( client, config, device_id, sensor_no).await?,
+        Event         => get_latest_update::<EventData>( client, config, device_id, sensor_no).await?,
+        Fire          => get_latest_update::<FireData>( client, config, device_id, sensor_no).await?,
+        Gas           => get_latest_update::<GasData>( client, config, device_id, sensor_no).await?,
+        Gps           => get_latest_update::<GpsData>( client, config, device_id, sensor_no).await?,
+        Gyroscope     => get_latest_update::<GyroscopeData>( client, config, device_id, sensor_no).await?,
+        Magnetometer  => get_latest_update::<MagnetometerData>( client, config, device_id, sensor_no).await?,
+        Orientation   => get_latest_update::<OrientationData>( client, config, device_id, sensor_no).await?,
+        Person        => get_latest_update::<PersonData>( client, config, device_id, sensor_no).await?,
+        Power         => get_latest_update::<PowerData>( client, config, device_id, sensor_no).await?,
+        Smoke         => get_latest_update::<SmokeData>( client, config, device_id, sensor_no).await?,
+        Thermometer   => get_latest_update::<ThermometerData>( client, config, device_id, sensor_no).await?,
+        Valve         => get_latest_update::<ValveData>( client, config, device_id, sensor_no).await?,
+        Voc           => get_latest_update::<VocData>( client, config, device_id, sensor_no).await?,
+
+        Image         => {
+            let mut rec = get_latest_record::<ImageData>(client, &config.base_uri, &config.access_token, device_id, sensor_no).await?;
+            rec.set_local_filename();
+            SentinelUpdate::from(Arc::new(rec))
+        }
+    };
+    Ok(Some(update))
+}
+
+/// retrieve all records that were recorded after `last` for the given record uri path (as created by [`rec_key`]).
+/// The result is empty if the capability is not forwarded according to the `capability_filter` of the provided config
+pub async fn get_missing_updates (client: &Client, config: &SentinelConfig, uri_path: &str, last: &str)->Result<Vec<SentinelUpdate>> {
+    use SensorCapability::*;
+
+    match str_from_last( uri_path, '/').and_then( SensorCapability::capability_of) {
+        Some(capa) if !config.forwards_capability( capa) => Ok(Vec::new()),
+
+        Some(Accelerometer) => get_updates_since::<AccelerometerData>( client, config, uri_path, last).await,
+        Some(Anemometer)    => get_updates_since::<AnemometerData>( client, config, uri_path, last).await,
+        Some(Cloudcover)    => get_updates_since::<CloudcoverData>( client, config, uri_path, last).await,
+        Some(Event)         => get_updates_since::<EventData>( client, config, uri_path, last).await,
+        Some(Fire)          => get_updates_since::<FireData>( client, config, uri_path, last).await,
+        Some(Gas)           => get_updates_since::<GasData>( client, config, uri_path, last).await,
+        Some(Gps)           => get_updates_since::<GpsData>( client, config, uri_path, last).await,
+        Some(Gyroscope)     => get_updates_since::<GyroscopeData>( client, config, uri_path, last).await,
+        Some(Magnetometer)  => get_updates_since::<MagnetometerData>( client, config, uri_path, last).await,
+        Some(Orientation)   => get_updates_since::<OrientationData>( client, config, uri_path, last).await,
+        Some(Person)        => get_updates_since::<PersonData>( client, config, uri_path, last).await,
+        Some(Power)         => get_updates_since::<PowerData>( client, config, uri_path, last).await,
+        Some(Smoke)         => get_updates_since::<SmokeData>( client, config, uri_path, last).await,
+        Some(Thermometer)   => get_updates_since::<ThermometerData>( client, config, uri_path, last).await,
+        Some(Valve)         => get_updates_since::<ValveData>( client, config, uri_path, last).await,
+        Some(Voc)           => get_updates_since::<VocData>( client, config, uri_path, last).await,
+
+        Some(Image)         => {
+            let recs = get_records_since::<ImageData>(client, &config.base_uri, &config.access_token, uri_path, last).await?;
+            Ok( recs.into_iter().map( |mut rec| { rec.set_local_filename(); SentinelUpdate::from(Arc::new(rec)) }).collect() )
+        }
+
+        None => Err( op_failed("unknown capability")) 
+    }
+}
+
+async fn get_latest_update<T> (client: &Client, config: &SentinelConfig, device_id: &str, sensor_no: u32) -> Result<SentinelUpdate> 
+    where T: RecordDataBounds, SentinelUpdate: From<Arc<SensorRecord<T>>>
+{
+    let rec = get_latest_record::<T>(client, &config.base_uri, &config.access_token, device_id, sensor_no).await?;
+    Ok( SentinelUpdate::from(Arc::new(rec)) )
+}
+
+async fn get_updates_since<T> (client: &Client, config: &SentinelConfig, uri_path: &str, last: &str) -> Result<Vec<SentinelUpdate>> 
+    where T: RecordDataBounds, SentinelUpdate: From<Arc<SensorRecord<T>>>
+{
+    let recs = get_records_since::<T>(client, &config.base_uri, &config.access_token, uri_path, last).await?;
+    Ok( recs.into_iter().map( |rec| SentinelUpdate::from(Arc::new(rec))).collect() )
+}
+
+/* #endregion record retrieval */
+
 /* #region FileFetcher ***********************************************************************************************/
 
 /// struct to request a SentinelFile from an external server
@@ -0,0 +1,96 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License. You may obtain a copy
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+#![allow(unused)]
+
+use std::{sync::{Arc,Mutex}, time::Duration, collections::HashMap};
+use async_trait::async_trait;
+use chrono::{DateTime,TimeDelta,Utc};
+use odin_actor::prelude::*;
+use odin_sentinel::{Alarm, AlarmMessenger, AlarmThrottle, FireData, SensorRecord, SentinelActorMsg, SentinelAlarmMonitor,
+    SentinelAlarmMonitorConfig, SentinelUpdate};
+
+/// a messenger that just records (confidence) of all alarms it is asked to send out
+struct RecordingMessenger { confidences: Arc<Mutex<Vec<f64>>> }
+
+#[async_trait]
+impl AlarmMessenger for RecordingMessenger {
+    async fn send_alarm (&self, alarm: &Alarm)->odin_sentinel::Result<()> {
+        self.confidences.lock().unwrap().push( alarm.confidence);
+        Ok(())
+    }
+}
+
+fn fire_update (id: &str, time_recorded: DateTime<Utc>, fire_prob: f64)->SentinelUpdate {
+    let rec = SensorRecord {
+        id: id.to_string(),
+        time_recorded,
+        sensor_no: 1,
+        device_id: "dev-1".to_string(),
+        evidences: Vec::new(),
+        claims: Vec::new(),
+        extra: Default::default(),
+        data: FireData { fire_prob }
+    };
+    Arc::new(rec).into()
+}
+
+#[tokio::test]
+async fn test_monitor_escalation()->odin_sentinel::Result<()> {
+    let config = SentinelAlarmMonitorConfig {
+        new_alarm_duration: Duration::from_secs(600),
+        attach_image: false,
+        fire_prob: 0.7,
+        throttle: AlarmThrottle::new( Duration::from_secs(60), true).with_escalation_thresholds( vec![0.9]),
+        ..Default::default()
+    };
+    let confidences = Arc::new( Mutex::new( Vec::new()));
+    let messengers: Vec<Box<dyn AlarmMessenger>> = vec![ Box::new( RecordingMessenger{ confidences: confidences.clone() }) ];
+
+    let mut actor_system = ActorSystem::new("test");
+
+    // we don't have an updater - the dropped receiver makes position queries fail right away
+    let hupdater = PreActorHandle::<SentinelActorMsg>::new( &actor_system, "updater", 8).to_actor_handle();
+
+    let hmonitor = spawn_actor!( actor_system, "monitor", SentinelAlarmMonitor::new(
+        config, HashMap::new(), hupdater, messengers
+    ))?;
+
+    let hsys = actor_system.clone_handle();
+    let updates = spawn( "updates", async move {
+        let t0 = Utc::now();
+        let records = [
+            ( 0, 0.8),  // first one is dispatched
+            ( 2, 0.8),  // throttled
+            ( 4, 0.95), // escalation - dispatched although it is within new_alarm_duration of the first one
+            ( 6, 0.96), // no further escalation - throttled
+            (90, 0.8)   // outside of throttle window but still within new_alarm_duration - suppressed
+        ];
+        for (i,(dt,prob)) in records.iter().enumerate() {
+            hmonitor.send_msg( fire_update( &format!("r{i}"), t0 + TimeDelta::seconds(*dt), *prob)).await.ok();
+        }
+
+        sleep( millis(200)).await; // give the monitor time to send out alarms
+        hsys.request_termination( secs(1)).await.ok();
+    })?;
+
+    actor_system.timeout_start_all( millis(20)).await?;
+    actor_system.process_requests().await?;
+    updates.await.unwrap();
+
+    let confidences = confidences.lock().unwrap().clone();
+    println!("dispatched alarms: {:?}", confidences);
+    assert_eq!( confidences, vec![0.8, 0.95]);
+
+    Ok(())
+}
@@ -0,0 +1,256 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License. You may obtain a copy
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+use std::{time::{Duration,Instant}, sync::{Arc,Mutex}, collections::HashSet};
+use tokio::{io::{AsyncReadExt,AsyncWriteExt}, net::TcpListener};
+use odin_sentinel::{SentinelConfig, SentinelStore, SensorCapability, FireData, PersonData, SmokeData, build_sentinel_client, get_device_snapshot,
+    get_notified_update, get_missing_updates, rec_key};
+
+/// a mock server that accepts connections but never responds
+async fn spawn_slow_server ()->String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn( async move {
+        let mut conns = Vec::new();
+        while let Ok((stream,_)) = listener.accept().await {
+            conns.push(stream); // keep it open but silent
+        }
+    });
+
+    format!("http://{addr}/devices")
+}
+
+#[tokio::test]
+async fn test_request_timeout() {
+    let uri = spawn_slow_server().await;
+
+    let mut config = SentinelConfig::default();
+    config.request_timeout = Duration::from_millis(300);
+    let client = build_sentinel_client( &config).unwrap();
+
+    let t0 = Instant::now();
+    let res = client.get(&uri).send().await;
+    let elapsed = t0.elapsed();
+    println!("request to slow server returned after {elapsed:?}: {res:?}");
+
+    assert!( res.is_err_and(|e| e.is_timeout()));
+    assert!( elapsed >= Duration::from_millis(300) && elapsed < Duration::from_secs(5));
+}
+
+const N_DEVICES: usize = 6;
+const RESPONSE_DELAY: Duration = Duration::from_millis(100);
+
+fn device_response (path: &str)->String {
+    if path == "/devices" {
+        let devices: Vec<String> = (0..N_DEVICES).map( |i| format!(r#"{{"id":"dev-{i}","info":"test"}}"#)).collect();
+        format!(r#"{{"data":[{}]}}"#, devices.join(","))
+    } else {
+        let device_id = path.split('/').nth(2).unwrap_or("?");
+        if path.ends_with("/sensors") {
+            format!(r#"{{"data":[{{"no":1,"deviceId":"{device_id}","partNo":null,"capabilities":["fire"]}}]}}"#)
+        } else {
+            format!(r#"{{"data":[{{"id":"rec-{device_id}","type":"fire","timeRecorded":"2024-01-01T00:00:00.000Z","sensorNo":1,"deviceId":"{device_id}","fire":{{"fireProb":0.5}},"evidences":[],"claims":[]}}]}}"#)
+        }
+    }
+}
+
+/// a mock server for the device, sensor and record queries that delays each response (except the device list)
+async fn spawn_device_server ()->String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn( async move {
+        while let Ok((mut socket,_)) = listener.accept().await {
+            tokio::spawn( async move {
+                let mut buf = vec![0u8; 4096];
+                let len = socket.read( &mut buf).await.unwrap();
+                let req = String::from_utf8_lossy( &buf[..len]).to_string();
+                let uri = req.split_whitespace().nth(1).unwrap_or("").to_string();
+                let path = uri.split('?').next().unwrap_or("");
+
+                if path != "/devices" { tokio::time::sleep( RESPONSE_DELAY).await; }
+                let body = device_response( path);
+                let response = format!("HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}", body.len(), body);
+                socket.write_all( response.as_bytes()).await.unwrap();
+                socket.shutdown().await.ok();
+            });
+        }
+    });
+
+    format!("http://{addr}")
+}
+
+#[tokio::test]
+async fn test_concurrent_fetch() {
+    let mut config = SentinelConfig::default();
+    config.base_uri = spawn_device_server().await;
+    let client = build_sentinel_client( &config).unwrap();
+
+    let t0 = Instant::now();
+    let mut store = SentinelStore::new();
+    store.fetch_from_config( &client, &config).await.unwrap();
+    let t_serial = t0.elapsed();
+
+    let t0 = Instant::now();
+    let mut concurrent_store = SentinelStore::new();
+    concurrent_store.fetch_from_config_concurrent( &client, &config, N_DEVICES).await.unwrap();
+    let t_concurrent = t0.elapsed();
+    println!("serial fetch: {t_serial:?}, concurrent fetch: {t_concurrent:?}");
+
+    let mut ids = concurrent_store.get_device_ids();
+    ids.sort();
+    let mut serial_ids = store.get_device_ids();
+    serial_ids.sort();
+    assert_eq!( ids, serial_ids);
+    assert_eq!( ids.len(), N_DEVICES);
+    for id in &ids {
+        let fire = concurrent_store.get( id).unwrap().latest::<FireData>().unwrap();
+        assert_eq!( fire.id, format!("rec-{id}"));
+        assert!( concurrent_store.get_update( &fire.id).is_some());
+    }
+
+    assert!( t_serial >= RESPONSE_DELAY * 2 * N_DEVICES as u32); // sensor list + records per device
+    assert!( t_concurrent < t_serial / 2);
+}
+
+const N_RECS: usize = 3; // per sensor and capability
+
+/// device "dev-0" with sensor 1 (fire,smoke) and sensor 2 (fire,person)
+fn snapshot_response (path: &str)->String {
+    if path == "/devices" {
+        r#"{"data":[{"id":"dev-0","info":"snapshot test"}]}"#.to_string()
+    } else if path.ends_with("/sensors") {
+        r#"{"data":[{"no":1,"deviceId":"dev-0","partNo":null,"capabilities":["fire","smoke"]},{"no":2,"deviceId":"dev-0","partNo":null,"capabilities":["fire","person"]}]}"#.to_string()
+    } else {
+        // /devices/dev-0/sensors/<no>/<capability>
+        let mut parts = path.split('/').skip(4);
+        let sensor_no: u32 = parts.next().and_then( |s| s.parse().ok()).unwrap_or(0);
+        let capa = parts.next().unwrap_or("?").to_lowercase();
+        let data = match capa.as_str() {
+            "fire" => r#""fire":{"fireProb":0.5}"#,
+            "smoke" => r#""smoke":{"smokeProb":0.3}"#,
+            "person" => r#""person":{"personProb":0.1}"#,
+            _ => ""
+        };
+        let recs: Vec<String> = (0..N_RECS).map( |i| {
+            format!(r#"{{"id":"{capa}-{sensor_no}-{i}","type":"{capa}","timeRecorded":"2024-01-01T00:0{i}:{sensor_no}0.000Z","sensorNo":{sensor_no},"deviceId":"dev-0",{data},"evidences":[],"claims":[]}}"#)
+        }).rev().collect(); // newest first
+        format!(r#"{{"data":[{}]}}"#, recs.join(","))
+    }
+}
+
+async fn spawn_snapshot_server ()->String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn( async move {
+        while let Ok((mut socket,_)) = listener.accept().await {
+            tokio::spawn( async move {
+                let mut buf = vec![0u8; 4096];
+                let len = socket.read( &mut buf).await.unwrap();
+                let req = String::from_utf8_lossy( &buf[..len]).to_string();
+                let uri = req.split_whitespace().nth(1).unwrap_or("").to_string();
+                let path = uri.split('?').next().unwrap_or("");
+
+                let body = snapshot_response( path);
+                let response = format!("HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}", body.len(), body);
+                socket.write_all( response.as_bytes()).await.unwrap();
+                socket.shutdown().await.ok();
+            });
+        }
+    });
+
+    format!("http://{addr}")
+}
+
+#[tokio::test]
+async fn test_device_snapshot() {
+    let mut config = SentinelConfig::default();
+    config.base_uri = spawn_snapshot_server().await;
+    let client = build_sentinel_client( &config).unwrap();
+
+    let sentinel = get_device_snapshot( &client, &config.base_uri, "?", "dev-0", 10).await.unwrap();
+    let v = serde_json::to_value( &sentinel).unwrap();
+    println!("snapshot: {}", serde_json::to_string_pretty( &v).unwrap());
+
+    assert_eq!( v["deviceName"], "snapshot test");
+    assert_eq!( v["fire"].as_array().unwrap().len(), 2 * N_RECS); // two sensors
+    assert_eq!( v["smoke"].as_array().unwrap().len(), N_RECS);
+    assert_eq!( v["person"].as_array().unwrap().len(), N_RECS);
+    assert!( v["gps"].as_array().unwrap().is_empty());
+
+    // newest record first, regardless of the order in which capability responses arrived
+    assert_eq!( sentinel.latest::<FireData>().unwrap().id, format!("fire-2-{}", N_RECS-1));
+    assert_eq!( sentinel.latest::<SmokeData>().unwrap().id, format!("smoke-1-{}", N_RECS-1));
+    assert_eq!( sentinel.latest::<PersonData>().unwrap().id, format!("person-2-{}", N_RECS-1));
+    assert!( v["timeRecorded"].is_number()); // epoch millis of the latest record
+}
+
+/// a snapshot server that also logs the paths of all requests it gets
+async fn spawn_logging_record_server (requests: Arc<Mutex<Vec<String>>>)->String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn( async move {
+        while let Ok((mut socket,_)) = listener.accept().await {
+            let requests = requests.clone();
+            tokio::spawn( async move {
+                let mut buf = vec![0u8; 4096];
+                let len = socket.read( &mut buf).await.unwrap();
+                let req = String::from_utf8_lossy( &buf[..len]).to_string();
+                let uri = req.split_whitespace().nth(1).unwrap_or("").to_string();
+                let path = format!("/{}", uri.split('?').next().unwrap_or("").trim_start_matches('/')); // record paths are appended to base_uri
+                requests.lock().unwrap().push( path.clone());
+
+                let body = snapshot_response( &path);
+                let response = format!("HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}", body.len(), body);
+                socket.write_all( response.as_bytes()).await.unwrap();
+                socket.shutdown().await.ok();
+            });
+        }
+    });
+
+    format!("http://{addr}")
+}
+
+#[tokio::test]
+async fn test_forwarded_capabilities() {
+    let requests = Arc::new( Mutex::new( Vec::<String>::new()));
+    let mut config = SentinelConfig::default();
+    config.base_uri = spawn_logging_record_server( requests.clone()).await;
+    config.capability_filter = HashSet::from( [SensorCapability::Fire]);
+    let client = build_sentinel_client( &config).unwrap();
+
+    //--- websocket record notifications
+    let fire = get_notified_update( &client, &config, "dev-0", 1, SensorCapability::Fire).await.unwrap();
+    assert_eq!( fire.map( |u| u.capability()), Some(SensorCapability::Fire));
+
+    let smoke = get_notified_update( &client, &config, "dev-0", 1, SensorCapability::Smoke).await.unwrap();
+    assert!( smoke.is_none());
+
+    //--- missing updates after a websocket reconnect (our mock server does not check 'last')
+    let fire = get_missing_updates( &client, &config, &rec_key( "dev-0", 1, SensorCapability::Fire), "fire-1-0").await.unwrap();
+    assert_eq!( fire.len(), N_RECS);
+    assert!( fire.iter().all( |u| u.capability() == SensorCapability::Fire));
+
+    let person = get_missing_updates( &client, &config, &rec_key( "dev-0", 2, SensorCapability::Person), "person-2-0").await.unwrap();
+    assert!( person.is_empty());
+
+    //--- records of capabilities we don't forward are not even retrieved
+    let requests = requests.lock().unwrap();
+    println!("requests: {:?}", requests);
+    assert_eq!( requests.len(), 2);
+    assert!( requests.iter().all( |p| p.to_lowercase().ends_with("/fire")));
+}
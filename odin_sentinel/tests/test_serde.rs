@@ -12,7 +12,7 @@
  * and limitations under the License.
  */
 
-use odin_sentinel::{Result,DeviceList,SensorList, RecordList, GpsData, SensorRecord, VocData};
+use odin_sentinel::{Result,DeviceList,SensorList, RecordList, FireData, GpsData, SensorRecord, VocData, ExternalImage, OdinSentinelError, parse_json, check_unknown_fields, unknown_field_record_count, unknown_field_names};
 
 // get {host}/devices
 #[test]
@@ -60,3 +60,75 @@ fn test_serde_roundtrip()->Result<()> {
     assert_eq!( json.as_str(), input);
     Ok(())
 }
+
+#[test]
+fn test_unknown_fields()->Result<()> {
+    // a server update added a top level "model" property and a "fireArea" data property
+    let input = r#"{"id":"r1","type":"fire","timeRecorded":"2024-06-01T12:00:00Z","sensorNo":7,"deviceId":"dev-1","evidences":[],"claims":[],
+        "model":{"name":"yolo","version":2},"fire":{"fireProb":0.82,"fireArea":12.5}}"#;
+
+    let rec: SensorRecord<FireData> = parse_json( input.as_bytes())?;
+    println!("parsed record with unknown fields: {:?}", rec);
+
+    assert_eq!( rec.id, "r1");
+    assert_eq!( rec.sensor_no, 7);
+    assert_eq!( rec.device_id, "dev-1");
+    assert_eq!( rec.data.fire_prob, 0.82);
+    assert!( rec.has_unknown_fields());
+    assert_eq!( rec.unknown_fields().collect::<Vec<_>>(), vec!["model"]); // "type" is known but not stored
+    assert_eq!( rec.extra["model"]["version"], 2);
+
+    let n = unknown_field_record_count();
+    check_unknown_fields( &[rec.clone(), rec]); // only the first one is reported but both are counted
+    assert_eq!( unknown_field_record_count(), n + 2);
+    assert!( unknown_field_names().contains( &"model".to_string()));
+
+    // unknown properties are not serialized
+    let rec: SensorRecord<FireData> = parse_json( input.as_bytes())?;
+    let json = serde_json::to_string( &rec)?;
+    println!("generated json: {}", json);
+    assert!( !json.contains("model"));
+
+    Ok(())
+}
+
+#[test]
+fn test_unused_fields()->Result<()> {
+    let input = r#"{"id":"r2","type":"fire","timeRecorded":"2024-06-01T12:00:00Z","sensorNo":7,"deviceId":"dev-1","evidences":[],"claims":[],"fire":{"fireProb":0.5}}"#;
+    let rec: SensorRecord<FireData> = parse_json( input.as_bytes())?;
+    assert!( !rec.has_unknown_fields());
+    assert!( rec.extra.is_empty()); // "type" is not stored
+    Ok(())
+}
+
+#[test]
+fn test_external_image_roundtrip()->Result<()> {
+    // the ExternalImage variant is identified by its "type" tag, not by the Rust type name
+    let input = r#"{"type":"image","name":"cam-1","sensors":[0,1],"filename":"cam1","uri":"https://example.com/cam1.jpg"}"#;
+
+    let img: ExternalImage = serde_json::from_str(input)?;
+    println!("parsed external image: {:?}", img);
+    assert_eq!( img.name(), "cam-1");
+    assert!( img.supports_sensor(1));
+
+    let json = serde_json::to_string(&img)?;
+    println!("generated json: {}", json);
+    assert_eq!( json.as_str(), input);
+    Ok(())
+}
+
+#[test]
+fn test_json_error_context() {
+    let input = r#"{"id":"x","timeRecorded":"2024-06-01T12:00:00Z","sensorNo":9,"deviceId":"dev-1","evidences":[],"claims":[],
+"gps":{"latitude":37.0,"longitude":oops}}"#;
+
+    match parse_json::<SensorRecord<GpsData>>( input.as_bytes()) {
+        Err(OdinSentinelError::JsonError(msg)) => {
+            println!("{msg}");
+            assert!( msg.contains("GpsData"));
+            assert!( msg.contains("line 2 column"));
+            assert!( msg.starts_with("Syntax"));
+        }
+        other => panic!("expected JsonError, got {other:?}")
+    }
+}
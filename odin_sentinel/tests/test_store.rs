@@ -0,0 +1,240 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License. You may obtain a copy
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+
+use std::{sync::Arc, time::Duration, collections::HashSet};
+use chrono::{DateTime,Utc};
+use odin_common::angle::{LatAngle,LonAngle};
+use uom::si::{f64::{ElectricPotential,ElectricCurrent,ThermodynamicTemperature}, electric_potential::volt, electric_current::ampere, thermodynamic_temperature::kelvin};
+use odin_sentinel::{Sentinel, SentinelStore, SentinelChange, SentinelUpdate, SensorRecord, SensorCapability, FireData, PowerData, GpsData, InactivityTracker, InactivityEvent, LiveSentinelConnector, SentinelConfig};
+
+fn fire_update (id: &str, secs: i64, fire_prob: f64)->SentinelUpdate {
+    let rec = SensorRecord {
+        id: id.to_string(),
+        time_recorded: DateTime::<Utc>::from_timestamp(secs, 0).unwrap(),
+        sensor_no: 1,
+        device_id: "dev-1".to_string(),
+        evidences: Vec::new(),
+        claims: Vec::new(),
+        extra: Default::default(),
+        data: FireData { fire_prob }
+    };
+    Arc::new(rec).into()
+}
+
+fn ids (change: &SentinelChange)->(Option<String>,Option<String>,Option<String>) {
+    ( change.added.as_ref().map(|u| u.record_id().clone()),
+      change.removed.as_ref().map(|u| u.record_id().clone()),
+      change.replaced.as_ref().map(|u| u.record_id().clone()) )
+}
+
+#[test]
+fn test_update_add_and_evict() {
+    let mut store = SentinelStore::new();
+    let max_len = 2;
+
+    let change = store.update_with( fire_update("a", 100, 0.1), max_len);
+    assert_eq!( ids(&change), (Some("a".into()), None, None));
+
+    let change = store.update_with( fire_update("b", 200, 0.2), max_len);
+    assert_eq!( ids(&change), (Some("b".into()), None, None));
+
+    // newer record on a full sensor list evicts the oldest one
+    let change = store.update_with( fire_update("c", 300, 0.3), max_len);
+    assert_eq!( ids(&change), (Some("c".into()), Some("a".into()), None));
+    assert!( store.get_update(&"a".to_string()).is_none());
+}
+
+#[test]
+fn test_update_replace() {
+    let mut store = SentinelStore::new();
+    store.update_with( fire_update("a", 100, 0.1), 2);
+
+    // same id and timestamp but different data replaces in place
+    let change = store.update_with( fire_update("a", 100, 0.9), 2);
+    assert_eq!( ids(&change), (None, None, Some("a".into())));
+
+    let upd = store.get_update(&"a".to_string()).unwrap();
+    println!("replaced: {}", upd.description());
+    assert!( upd.description().contains("0.9"));
+}
+
+#[test]
+fn test_update_duplicate() {
+    let mut store = SentinelStore::new();
+    store.update_with( fire_update("a", 100, 0.1), 2);
+
+    // an identical record is also reported as replaced, not as no-op
+    let change = store.update_with( fire_update("a", 100, 0.1), 2);
+    assert_eq!( ids(&change), (None, None, Some("a".into())));
+}
+
+#[test]
+fn test_update_drop() {
+    let mut store = SentinelStore::new();
+    store.update_with( fire_update("b", 200, 0.2), 2);
+    store.update_with( fire_update("c", 300, 0.3), 2);
+
+    // older than the retained window of a full list - not stored at all
+    let change = store.update_with( fire_update("a", 100, 0.1), 2);
+    assert_eq!( ids(&change), (None, None, None));
+    assert!( store.get_update(&"a".to_string()).is_none());
+}
+
+fn power_update (id: &str, secs: i64, battery_voltage: f64, battery_temp: f64)->SentinelUpdate {
+    let rec = SensorRecord {
+        id: id.to_string(),
+        time_recorded: DateTime::<Utc>::from_timestamp(secs, 0).unwrap(),
+        sensor_no: 3,
+        device_id: "dev-1".to_string(),
+        evidences: Vec::new(),
+        claims: Vec::new(),
+        extra: Default::default(),
+        data: PowerData {
+            battery_voltage: ElectricPotential::new::<volt>(battery_voltage),
+            battery_current: ElectricCurrent::new::<ampere>(0.5),
+            solar_voltage: ElectricPotential::new::<volt>(18.0),
+            solar_current: ElectricCurrent::new::<ampere>(1.0),
+            load_voltage: ElectricPotential::new::<volt>(12.0),
+            load_current: ElectricCurrent::new::<ampere>(0.25),
+            soc: 0.75,
+            battery_temp: ThermodynamicTemperature::new::<kelvin>(battery_temp),
+            controller_temp: ThermodynamicTemperature::new::<kelvin>(310.0),
+        }
+    };
+    Arc::new(rec).into()
+}
+
+#[test]
+fn test_power_csv() {
+    let mut sentinel = Sentinel::new( "dev-1".to_string(), "test".to_string(), 10);
+    sentinel.update_with( power_update("p2", 200, 12.5, 301.0));
+    sentinel.update_with( power_update("p1", 100, 12.0, 300.0));
+
+    let mut buf: Vec<u8> = Vec::new();
+    sentinel.write_capability_csv( SensorCapability::Power, &mut buf).unwrap();
+    let csv = String::from_utf8(buf).unwrap();
+    println!("{csv}");
+
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!( lines.len(), 3);
+    assert_eq!( lines[0], "timeRecorded,sensorNo,batteryCurrent,batteryTemp,batteryVoltage,controllerTemp,loadCurrent,loadVoltage,soc,solarCurrent,solarVoltage");
+    assert_eq!( lines[1], "1970-01-01T00:01:40.000Z,3,0.5,300.0,12.0,310.0,0.25,12.0,0.75,1.0,18.0"); // oldest first
+}
+
+#[test]
+fn test_latest_record() {
+    let mut sentinel = Sentinel::new( "dev-1".to_string(), "test".to_string(), 10);
+    assert!( sentinel.latest::<FireData>().is_none());
+
+    sentinel.update_with( fire_update("f2", 200, 0.2));
+    sentinel.update_with( fire_update("f1", 100, 0.1));
+    sentinel.update_with( power_update("p1", 150, 12.0, 300.0));
+    sentinel.update_with( power_update("p2", 250, 12.5, 301.0));
+
+    let fire = sentinel.latest::<FireData>().unwrap();
+    assert_eq!( fire.id, "f2");
+    assert_eq!( fire.data.fire_prob, 0.2);
+
+    let power = sentinel.latest::<PowerData>().unwrap();
+    assert_eq!( power.id, "p2");
+}
+
+fn gps_update (id: &str, secs: i64, lat: f64, lon: f64, alt: f64)->SentinelUpdate {
+    let rec = SensorRecord {
+        id: id.to_string(),
+        time_recorded: DateTime::<Utc>::from_timestamp(secs, 0).unwrap(),
+        sensor_no: 4,
+        device_id: "dev-1".to_string(),
+        evidences: Vec::new(),
+        claims: Vec::new(),
+        extra: Default::default(),
+        data: GpsData {
+            latitude: LatAngle::from_degrees(lat),
+            longitude: LonAngle::from_degrees(lon),
+            altitude: Some(alt),
+            quality: None,
+            number_of_satellites: None,
+            hdop: None,
+        }
+    };
+    Arc::new(rec).into()
+}
+
+#[test]
+fn test_interpolated_position() {
+    let mut sentinel = Sentinel::new( "dev-1".to_string(), "test".to_string(), 10);
+    sentinel.update_with( gps_update("g1", 100, 37.0, -122.0, 100.0));
+    sentinel.update_with( gps_update("g2", 200, 38.0, -121.0, 200.0));
+
+    let pos = sentinel.get_interpolated_position_at( DateTime::<Utc>::from_timestamp(150, 0).unwrap()).unwrap();
+    println!("midpoint: {pos:?}");
+    assert!( (pos.lat.degrees() - 37.5).abs() < 1e-9);
+    assert!( (pos.lon.degrees() - -121.5).abs() < 1e-9);
+    assert!( (pos.alt - 150.0).abs() < 1e-9);
+
+    // outside of the record span we get the closest fix
+    let pos = sentinel.get_interpolated_position_at( DateTime::<Utc>::from_timestamp(300, 0).unwrap()).unwrap();
+    assert_eq!( pos.lat.degrees(), 38.0);
+    let pos = sentinel.get_interpolated_position_at( DateTime::<Utc>::from_timestamp(50, 0).unwrap()).unwrap();
+    assert_eq!( pos.lat.degrees(), 37.0);
+}
+
+fn at (secs: i64)->DateTime<Utc> { DateTime::<Utc>::from_timestamp(secs, 0).unwrap() }
+
+#[test]
+fn test_inactivity_transitions() {
+    let mut store = SentinelStore::new();
+    let mut tracker = InactivityTracker::new( Duration::from_secs(60));
+    let device_id = "dev-1".to_string();
+
+    store.update_with( fire_update("a", 100, 0.1), 2);
+    assert!( tracker.check( &store, at(150)).is_empty());
+
+    // crossing the threshold reports the device once
+    let events = tracker.check( &store, at(161));
+    assert_eq!( events, vec![ InactivityEvent::DeviceInactive { device_id: device_id.clone(), last_seen: Some(at(100)) } ]);
+    assert!( tracker.is_inactive( &device_id));
+    assert!( tracker.check( &store, at(300)).is_empty());
+
+    // fresh data brings it back, again only once
+    store.update_with( fire_update("b", 310, 0.2), 2);
+    let events = tracker.check( &store, at(320));
+    assert_eq!( events, vec![ InactivityEvent::DeviceReactivated { device_id: device_id.clone(), last_seen: at(310) } ]);
+    assert!( !tracker.is_inactive( &device_id));
+    assert!( tracker.check( &store, at(330)).is_empty());
+
+    // and it can go inactive again
+    let events = tracker.check( &store, at(400));
+    assert_eq!( events.len(), 1);
+}
+
+#[test]
+fn test_capability_filter() {
+    let mut connector = LiveSentinelConnector::new( SentinelConfig::default());
+    let updates = vec![
+        fire_update("f1", 100, 0.1),
+        power_update("p1", 110, 12.0, 300.0),
+        gps_update("g1", 120, 37.0, -122.0, 100.0),
+        fire_update("f2", 130, 0.2),
+        power_update("p2", 140, 12.5, 301.0),
+    ];
+
+    // without a filter everything is forwarded
+    assert!( updates.iter().all( |u| connector.forwards_update(u)));
+
+    connector.set_capability_filter( HashSet::from( [SensorCapability::Fire, SensorCapability::Gps]));
+    let forwarded: Vec<String> = updates.iter().filter( |u| connector.forwards_update(u)).map( |u| u.record_id().clone()).collect();
+    println!("forwarded: {forwarded:?}");
+    assert_eq!( forwarded, vec!["f1", "g1", "f2"]);
+}
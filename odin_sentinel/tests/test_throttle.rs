@@ -0,0 +1,74 @@
+/*
+ * Copyright © 2024, United States Government, as represented by the Administrator of
+ * the National Aeronautics and Space Administration. All rights reserved.
+ *
+ * The “ODIN” software is licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License. You may obtain a copy
+ * of the License at http://www.apache.org/licenses/LICENSE-2.0.
+ *
+ * Unless required by applicable law or agreed to in writing, software distributed under
+ * the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND,
+ * either express or implied. See the License for the specific language governing permissions
+ * and limitations under the License.
+ */
+#![allow(unused)]
+
+use std::time::Duration;
+use chrono::{DateTime,TimeDelta,Utc};
+use odin_sentinel::{AlarmThrottle,AlarmDispatchHistory};
+
+#[test]
+fn test_throttle_burst() {
+    let throttle = AlarmThrottle::new( Duration::from_secs(60), true);
+    let mut history = AlarmDispatchHistory::new();
+    let t0 = Utc::now();
+
+    // a sensor reporting FireData every 2 sec for 30 sec
+    let n_dispatched = (0..15).filter( |i| {
+        history.should_dispatch( &throttle, "dev_1", "fire", t0 + TimeDelta::seconds(i*2), 0.8)
+    }).count();
+    assert_eq!( n_dispatched, 1);
+
+    // outside of the window we get a new one
+    assert!( history.should_dispatch( &throttle, "dev_1", "fire", t0 + TimeDelta::seconds(60), 0.8));
+    assert!( !history.should_dispatch( &throttle, "dev_1", "fire", t0 + TimeDelta::seconds(62), 0.8));
+}
+
+#[test]
+fn test_throttle_escalation() {
+    let throttle = AlarmThrottle::new( Duration::from_secs(60), true).with_escalation_thresholds( vec![0.9, 0.95]);
+    let mut history = AlarmDispatchHistory::new();
+    let t0 = Utc::now();
+    let probs = [0.7, 0.75, 0.8, 0.92, 0.91, 0.93, 0.96, 0.97, 0.8, 0.99];
+
+    let dispatched: Vec<f64> = probs.iter().enumerate().filter_map( |(i,p)| {
+        if history.should_dispatch( &throttle, "dev_1", "fire", t0 + TimeDelta::seconds(i as i64), *p) { Some(*p) } else { None }
+    }).collect();
+    println!("dispatched: {:?}", dispatched);
+
+    // first alarm and the ones crossing the 0.9 and 0.95 thresholds
+    assert_eq!( dispatched, vec![0.7, 0.92, 0.96]);
+}
+
+#[test]
+fn test_throttle_keys() {
+    let t0 = Utc::now();
+
+    let throttle = AlarmThrottle::new( Duration::from_secs(60), true);
+    let mut history = AlarmDispatchHistory::new();
+    assert!( history.should_dispatch( &throttle, "dev_1", "fire", t0, 0.8));
+    assert!( history.should_dispatch( &throttle, "dev_2", "fire", t0, 0.8)); // different device
+    assert!( history.should_dispatch( &throttle, "dev_1", "smoke", t0, 0.8)); // different capability
+    assert!( !history.should_dispatch( &throttle, "dev_2", "fire", t0 + TimeDelta::seconds(1), 0.8));
+
+    let throttle = AlarmThrottle::new( Duration::from_secs(60), false);
+    let mut history = AlarmDispatchHistory::new();
+    assert!( history.should_dispatch( &throttle, "dev_1", "fire", t0, 0.8));
+    assert!( !history.should_dispatch( &throttle, "dev_2", "fire", t0, 0.8)); // not per device
+    assert!( history.should_dispatch( &throttle, "dev_2", "smoke", t0, 0.8));
+
+    // zero interval disables throttling
+    let throttle = AlarmThrottle::new( Duration::ZERO, true);
+    let mut history = AlarmDispatchHistory::new();
+    assert!( (0..10).all( |_| history.should_dispatch( &throttle, "dev_1", "fire", t0, 0.8)));
+}